@@ -0,0 +1,422 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Declarative binding regression tests for `bunnylol test`.
+//!
+//! A test file lists inputs and the URL each one must resolve to:
+//!
+//! ```toml
+//! # bunnylol.tests.toml
+//! # Optional: resolve against this config instead of the normal one.
+//! # Relative paths are resolved against the test file's directory.
+//! config = "config.toml"
+//!
+//! [[case]]
+//! input = "jira PROJ-123"
+//! expected = "https://corp.atlassian.net/browse/PROJ-123"
+//!
+//! [[case]]
+//! name = "work repo"
+//! input = "work"
+//! expected = "https://github.com/mycompany/repo"
+//! config = "other-config.toml"  # per-case override
+//! ```
+//!
+//! Config precedence for a case: the case's `config`, then the file's
+//! `config`, then the config passed to [`run`].
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{BunnylolConfig, init_global_config};
+use crate::{BunnylolCommandRegistry, utils};
+
+/// Default test file name, looked up in the current directory.
+pub const DEFAULT_TEST_FILE: &str = "bunnylol.tests.toml";
+
+/// A parsed `bunnylol.tests.toml` file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BindingTestFile {
+    /// Config file to resolve every case against, unless a case overrides it.
+    #[serde(default)]
+    pub config: Option<PathBuf>,
+
+    /// The test cases, in file order.
+    #[serde(default, rename = "case")]
+    pub cases: Vec<BindingTestCase>,
+}
+
+/// One `[[case]]` entry: an input and the URL it must resolve to.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BindingTestCase {
+    /// Display name for the report (defaults to the input).
+    #[serde(default)]
+    pub name: Option<String>,
+    pub input: String,
+    pub expected: String,
+    /// Config file override for this case only.
+    #[serde(default)]
+    pub config: Option<PathBuf>,
+}
+
+impl BindingTestFile {
+    /// Load a test file. Relative `config` paths are resolved against the
+    /// directory containing the test file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read test file {:?}: {}", path, e))?;
+        let mut file: BindingTestFile = toml::from_str(&contents)
+            .map_err(|e| format!("Failed to parse test file {:?}: {}", path, e))?;
+
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let resolve = |p: &mut Option<PathBuf>| {
+            if let Some(rel) = p.as_ref()
+                && rel.is_relative()
+            {
+                *p = Some(base_dir.join(rel));
+            }
+        };
+        resolve(&mut file.config);
+        for case in &mut file.cases {
+            resolve(&mut case.config);
+        }
+
+        Ok(file)
+    }
+}
+
+/// Outcome of a single case.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BindingTestResult {
+    pub name: String,
+    pub input: String,
+    pub expected: String,
+    /// The resolved URL, or `None` if the case's config failed to load.
+    pub actual: Option<String>,
+    /// Why the case could not be run, if it errored.
+    pub error: Option<String>,
+    /// The config file the case ran against, if not the default.
+    pub config: Option<String>,
+}
+
+impl BindingTestResult {
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.actual.as_deref() == Some(self.expected.as_str())
+    }
+}
+
+/// Results for every case in a test file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BindingTestReport {
+    pub file: String,
+    pub results: Vec<BindingTestResult>,
+}
+
+/// Run every case through the resolver.
+///
+/// Built-in commands read settings from the global config, so each case
+/// installs its config as the global snapshot before resolving. `base` is
+/// restored afterwards.
+pub fn run(file: &BindingTestFile, file_path: &Path, base: &BunnylolConfig) -> BindingTestReport {
+    let mut configs: HashMap<PathBuf, Result<BunnylolConfig, String>> = HashMap::new();
+    let mut results = Vec::with_capacity(file.cases.len());
+
+    for case in &file.cases {
+        let config_path = case.config.as_ref().or(file.config.as_ref());
+        let config = match config_path {
+            Some(path) => configs
+                .entry(path.clone())
+                .or_insert_with(|| BunnylolConfig::read_from_path(path))
+                .clone(),
+            None => Ok(base.clone()),
+        };
+
+        let (actual, error) = match config {
+            Ok(config) => {
+                init_global_config(config);
+                let command = utils::get_command_from_query_string(&case.input);
+                (
+                    Some(BunnylolCommandRegistry::process_command(
+                        command,
+                        &case.input,
+                    )),
+                    None,
+                )
+            }
+            Err(e) => (None, Some(e)),
+        };
+
+        results.push(BindingTestResult {
+            name: case.name.clone().unwrap_or_else(|| case.input.clone()),
+            input: case.input.clone(),
+            expected: case.expected.clone(),
+            actual,
+            error,
+            config: config_path.map(|p| p.display().to_string()),
+        });
+    }
+
+    init_global_config(base.clone());
+
+    BindingTestReport {
+        file: file_path.display().to_string(),
+        results,
+    }
+}
+
+impl BindingTestReport {
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|r| r.passed()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.passed()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failed() == 0
+    }
+
+    /// Human-readable report with a `-`/`+` diff for each failing case.
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "{}: {} case{}\n\n",
+            self.file,
+            self.results.len(),
+            if self.results.len() == 1 { "" } else { "s" }
+        );
+        for result in &self.results {
+            if result.passed() {
+                out.push_str(&format!("PASS  {}\n", result.name));
+                continue;
+            }
+            match (&result.error, &result.actual) {
+                (Some(error), _) => {
+                    out.push_str(&format!("ERROR {}\n", result.name));
+                    out.push_str(&format!("      input:    {}\n", result.input));
+                    out.push_str(&format!("      error:    {}\n", error));
+                }
+                (None, actual) => {
+                    out.push_str(&format!("FAIL  {}\n", result.name));
+                    out.push_str(&format!("      input:    {}\n", result.input));
+                    if let Some(config) = &result.config {
+                        out.push_str(&format!("      config:   {}\n", config));
+                    }
+                    out.push_str(&format!("    - expected: {}\n", result.expected));
+                    out.push_str(&format!(
+                        "    + actual:   {}\n",
+                        actual.as_deref().unwrap_or("")
+                    ));
+                }
+            }
+        }
+        out.push_str(&format!(
+            "\n{} passed, {} failed\n",
+            self.passed(),
+            self.failed()
+        ));
+        out
+    }
+
+    /// Machine-readable JSON report.
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct JsonReport<'a> {
+            file: &'a str,
+            passed: usize,
+            failed: usize,
+            results: Vec<JsonResult<'a>>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonResult<'a> {
+            #[serde(flatten)]
+            result: &'a BindingTestResult,
+            passed: bool,
+        }
+
+        let report = JsonReport {
+            file: &self.file,
+            passed: self.passed(),
+            failed: self.failed(),
+            results: self
+                .results
+                .iter()
+                .map(|result| JsonResult {
+                    result,
+                    passed: result.passed(),
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&report).expect("test report serializes to JSON")
+    }
+
+    /// JUnit XML report, for CI systems that render test results.
+    pub fn to_junit(&self) -> String {
+        let errors = self.results.iter().filter(|r| r.error.is_some()).count();
+        let failures = self.failed() - errors;
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!(
+            "<testsuite name=\"bunnylol\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+            self.results.len(),
+            failures,
+            errors
+        ));
+        for result in &self.results {
+            out.push_str(&format!(
+                "  <testcase classname=\"{}\" name=\"{}\"",
+                escape_xml(&self.file),
+                escape_xml(&result.name)
+            ));
+            if result.passed() {
+                out.push_str("/>\n");
+                continue;
+            }
+            out.push_str(">\n");
+            match &result.error {
+                Some(error) => {
+                    out.push_str(&format!("    <error message=\"{}\"/>\n", escape_xml(error)))
+                }
+                None => out.push_str(&format!(
+                    "    <failure message=\"expected {}, got {}\">input: {}</failure>\n",
+                    escape_xml(&result.expected),
+                    escape_xml(result.actual.as_deref().unwrap_or("")),
+                    escape_xml(&result.input)
+                )),
+            }
+            out.push_str("  </testcase>\n");
+        }
+        out.push_str("</testsuite>\n");
+        out
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, expected: &str, actual: Option<&str>) -> BindingTestResult {
+        BindingTestResult {
+            name: name.to_string(),
+            input: name.to_string(),
+            expected: expected.to_string(),
+            actual: actual.map(str::to_string),
+            error: None,
+            config: None,
+        }
+    }
+
+    fn report() -> BindingTestReport {
+        BindingTestReport {
+            file: "bunnylol.tests.toml".to_string(),
+            results: vec![
+                result("gh", "https://github.com", Some("https://github.com")),
+                result("work", "https://a.example/<x>", Some("https://b.example")),
+                BindingTestResult {
+                    error: Some("Failed to read config".to_string()),
+                    ..result("corp", "https://corp.example", None)
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_parse_test_file_and_resolve_relative_config_paths() {
+        let dir = std::env::temp_dir().join(format!(
+            "bunnylol-binding-tests-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(DEFAULT_TEST_FILE);
+        fs::write(
+            &path,
+            r#"
+config = "shared.toml"
+
+[[case]]
+input = "gh"
+expected = "https://github.com"
+
+[[case]]
+name = "absolute override"
+input = "cal"
+expected = "https://calendar.google.com"
+config = "/etc/bunnylol/config.toml"
+"#,
+        )
+        .unwrap();
+
+        let file = BindingTestFile::load(&path).unwrap();
+        assert_eq!(file.config, Some(dir.join("shared.toml")));
+        assert_eq!(file.cases.len(), 2);
+        assert_eq!(file.cases[0].name, None);
+        assert_eq!(
+            file.cases[1].config,
+            Some(PathBuf::from("/etc/bunnylol/config.toml"))
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_report_counts_errors_as_failures() {
+        let report = report();
+        assert_eq!(report.passed(), 1);
+        assert_eq!(report.failed(), 2);
+        assert!(!report.is_success());
+    }
+
+    #[test]
+    fn test_text_report_shows_diff_for_failures() {
+        let text = report().to_text();
+        assert!(text.contains("PASS  gh\n"));
+        assert!(text.contains("FAIL  work\n"));
+        assert!(text.contains("    - expected: https://a.example/<x>\n"));
+        assert!(text.contains("    + actual:   https://b.example\n"));
+        assert!(text.contains("ERROR corp\n"));
+        assert!(text.ends_with("1 passed, 2 failed\n"));
+    }
+
+    #[test]
+    fn test_json_and_junit_reports() {
+        let report = report();
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["failed"], 2);
+        assert_eq!(json["results"][0]["passed"], true);
+        assert_eq!(json["results"][1]["actual"], "https://b.example");
+
+        let junit = report.to_junit();
+        assert!(junit.contains(r#"tests="3" failures="1" errors="1""#));
+        assert!(junit.contains("expected https://a.example/&lt;x&gt;, got https://b.example"));
+        assert!(junit.contains(r#"<error message="Failed to read config"/>"#));
+    }
+}
@@ -8,12 +8,31 @@
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::config::get_global_config;
+use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo, PrefixHandler};
+use crate::commands::providers::{self, ProviderCategory};
+use crate::config::{
+    BunnylolConfig, CommandConfig, ResolvedBinding, UserBinding, get_global_config,
+    substitute_url_template,
+};
+use crate::explain::{ResolutionTier, ResolutionTrace, TemplateSubstitution, TierOutcome};
 
 // Type alias for command handler functions
 type CommandHandler = fn(&str) -> String;
 
+/// A configurable built-in that named `[commands.<name>]` instances can use
+struct InstanceType {
+    process: fn(&str, Option<&CommandConfig>) -> String,
+    info: fn() -> BunnylolCommandInfo,
+    default_base_url: &'static str,
+}
+
+/// A registered prefix handler: its prefix, matcher and URL builder
+struct RegisteredPrefix {
+    prefix: &'static str,
+    matches: fn(&str) -> bool,
+    process: CommandHandler,
+}
+
 // Global command lookup table, initialized once on first access
 static COMMAND_LOOKUP: OnceLock<HashMap<&'static str, CommandHandler>> = OnceLock::new();
 static BINDINGS_DATA: OnceLock<Vec<BunnylolCommandInfo>> = OnceLock::new();
@@ -47,6 +66,35 @@ macro_rules! register_commands {
     };
 }
 
+/// Macro to register all prefix handlers in one place, in the order they
+/// are checked
+macro_rules! register_prefix_handlers {
+    ($($handler:ty),+ $(,)?) => {
+        /// All registered prefix handlers, in evaluation order
+        fn prefix_handlers() -> &'static [RegisteredPrefix] {
+            static HANDLERS: &[RegisteredPrefix] = &[
+                $(
+                    RegisteredPrefix {
+                        prefix: <$handler as PrefixHandler>::PREFIX,
+                        matches: <$handler as PrefixHandler>::matches,
+                        process: <$handler as PrefixHandler>::process_prefix,
+                    },
+                )+
+            ];
+            HANDLERS
+        }
+
+        /// Get all registered prefix handler descriptions
+        fn get_all_prefix_handlers_impl() -> Vec<BunnylolCommandInfo> {
+            vec![
+                $(
+                    <$handler as PrefixHandler>::get_info(),
+                )+
+            ]
+        }
+    };
+}
+
 /// Bunnylol Command Registry that manages all Bunnylol commands
 ///
 /// This struct provides a centralized way to register and lookup commands
@@ -105,107 +153,499 @@ impl BunnylolCommandRegistry {
         crate::commands::ProtonMailCommand,
         crate::commands::ProtonDriveCommand,
         crate::commands::WaybackCommand,
+        crate::commands::PkgCommand,
     }
 
-    /// Process commands that use special prefixes (like $ for stock tickers)
-    fn process_prefix_commands(command: &str, full_args: &str) -> Option<String> {
-        use crate::commands::*;
-
-        if command.starts_with('$') {
-            // Don't process bare $ - let it fall through to default search
-            if command.len() <= 1 {
-                return None;
-            }
-            return Some(StockCommand::process_ticker(command));
-        }
+    // Register all prefix handlers here - ADD NEW PREFIX HANDLERS TO THIS LIST
+    register_prefix_handlers! {
+        crate::commands::StockTickerPrefix,
+        crate::commands::SubredditPrefix,
+        crate::commands::RedditUserPrefix,
+        crate::commands::SocialHandlePrefix,
+        crate::commands::PurlPrefix,
+        crate::commands::PythonVersionPrefix,
+        crate::commands::AmazonStorefrontPrefix,
+    }
 
-        if command.starts_with("r/") && command.len() > 2 {
-            return Some(RedditCommand::process_subreddit_prefix(full_args));
-        }
+    /// Process commands that use special prefixes (like $ for stock tickers).
+    /// Returns the matched prefix alongside the URL so traces can name it.
+    fn process_prefix_commands(command: &str, full_args: &str) -> Option<(&'static str, String)> {
+        Self::prefix_handlers()
+            .iter()
+            .find(|handler| (handler.matches)(command))
+            .map(|handler| (handler.prefix, (handler.process)(full_args)))
+    }
 
-        None
+    /// Whether a built-in prefix handler claims `command`.
+    pub fn is_prefix_command(command: &str) -> bool {
+        Self::prefix_handlers()
+            .iter()
+            .any(|handler| (handler.matches)(command))
     }
 
     /// Process a command string and return the appropriate URL.
     ///
     /// Resolution order (first match wins):
-    ///   1. Special prefix handlers (`$TICKER`, `r/sub`)
+    ///   1. Built-in prefix handlers (`$TICKER`, `r/sub`, `u/user`, `@handle`, `pkg:`), then user
+    ///      `[[patterns]]` regex rules in order
     ///   2. User `[user_bindings]` with `override = true`
     ///   3. Built-in registered commands
     ///   4. User `[user_bindings]` without `override`
-    ///   5. Default search engine fallback
+    ///   5. Go-links (see [`crate::links`])
+    ///   6. Default search engine fallback
+    ///
+    /// The resulting URL then goes through the `[[rewrites]]` rules.
     ///
-    /// `Command` bindings rewrite the input and dispatch into the registry
-    /// **exactly once** with [`Self::process_command_no_user_bindings`] — they
-    /// can resolve to a built-in or the search fallback, but cannot re-enter
-    /// another user binding. This prevents cycles.
+    /// `Command` bindings and `command` patterns rewrite the input and
+    /// dispatch into the registry **exactly once** with user bindings and
+    /// patterns skipped — they can resolve to a built-in or the search
+    /// fallback, but cannot re-enter user config. This prevents cycles.
     pub fn process_command(command: &str, full_args: &str) -> String {
-        // Tier 1: prefix handlers
-        if let Some(url) = Self::process_prefix_commands(command, full_args) {
-            return url;
-        }
+        Self::explain_command(command, full_args).url
+    }
 
-        let cfg = get_global_config();
+    /// Resolve a command string like [`Self::process_command`], returning a
+    /// [`ResolutionTrace`] of what each tier checked, how URL templates were
+    /// substituted, and which candidates were shadowed.
+    pub fn explain_command(command: &str, full_args: &str) -> ResolutionTrace {
+        Self::resolve(get_global_config().as_ref(), command, full_args, true)
+    }
+
+    /// [`Self::explain_command`] against `config` instead of the global
+    /// config. The server uses it to resolve with a signed-in user's
+    /// personal bindings merged in.
+    pub fn explain_command_with_config(
+        config: &BunnylolConfig,
+        command: &str,
+        full_args: &str,
+    ) -> ResolutionTrace {
+        Self::resolve(Some(config), command, full_args, true)
+    }
+
+    /// Walk the six resolution tiers against `config`. `process_command`
+    /// and `explain_command` both go through here so the trace can never
+    /// disagree with the URL that is actually returned.
+    ///
+    /// With `consult_user_config = false` (used to dispatch a `Command`
+    /// binding's or pattern's rewritten string) `[[patterns]]` and tiers 2,
+    /// 4 and 5 are skipped.
+    fn resolve(
+        config: Option<&BunnylolConfig>,
+        command: &str,
+        full_args: &str,
+        consult_user_config: bool,
+    ) -> ResolutionTrace {
+        let mut trace = ResolutionTrace::new(command, full_args);
+        let mut url: Option<String> = None;
+        // Describes whichever candidate produced `url`, for shadow reasons.
+        let mut winner = String::new();
+        let user_config = config.filter(|_| consult_user_config);
+        let binding = user_config.and_then(|cfg| cfg.user_bindings.get(command));
+
+        // Tier 1: built-in prefix handlers, then [[patterns]] in order
+        let pattern = user_config.and_then(|cfg| cfg.match_pattern(full_args.trim()));
+        match (Self::process_prefix_commands(command, full_args), pattern) {
+            (Some((prefix, prefix_url)), pattern) => {
+                trace.record(
+                    ResolutionTier::Prefix,
+                    TierOutcome::Matched,
+                    format!("`{}` prefix handler claimed `{}`", prefix, command),
+                );
+                url = Some(prefix_url);
+                winner = format!("the built-in `{}` prefix handler", prefix);
+                if let Some((index, rule)) = pattern {
+                    trace.shadow(
+                        ResolutionTier::Prefix,
+                        format!("pattern rule `{}`", rule.label(index)),
+                        format!("shadowed by {}; built-in prefixes run first", winner),
+                    );
+                }
+            }
+            (None, Some((index, rule))) => {
+                let label = rule.label(index);
+                let captures = rule.describe_captures(full_args.trim());
+                let detail = if captures.is_empty() {
+                    format!("pattern rule `{}` (`{}`) matched", label, rule.pattern)
+                } else {
+                    format!(
+                        "pattern rule `{}` (`{}`) matched with {}",
+                        label,
+                        rule.pattern,
+                        captures.join(", ")
+                    )
+                };
+                let resolved = rule
+                    .apply(full_args.trim())
+                    .expect("a matching, validated pattern rule always resolves");
+                let template = rule.display_target().to_string();
+                url = Some(Self::dispatch_resolved(
+                    config,
+                    ResolutionTier::Prefix,
+                    detail,
+                    resolved,
+                    Some(template),
+                    &mut trace,
+                ));
+                winner = format!("pattern rule `{}`", label);
+            }
+            (None, None) => {
+                let patterns = user_config.map_or(0, |cfg| cfg.patterns.len());
+                let prefixes: Vec<String> = Self::prefix_handlers()
+                    .iter()
+                    .map(|handler| format!("`{}`", handler.prefix))
+                    .collect();
+                let mut detail = format!(
+                    "no prefix handler ({}) claimed `{}`",
+                    prefixes.join(", "),
+                    command
+                );
+                if patterns > 0 {
+                    detail.push_str(&format!(
+                        ", and none of {} [[patterns]] rule{} matched",
+                        patterns,
+                        if patterns == 1 { "" } else { "s" }
+                    ));
+                }
+                trace.record(ResolutionTier::Prefix, TierOutcome::NoMatch, detail);
+            }
+        }
 
         // Tier 2: user bindings with override = true
-        if let Some(ref cfg) = cfg
-            && let Some((resolved, true)) = cfg.resolve_user_binding(command, full_args)
-        {
-            return Self::dispatch_resolved(resolved);
+        match binding {
+            _ if !consult_user_config => {
+                trace.record(
+                    ResolutionTier::UserBindingOverride,
+                    TierOutcome::Skipped,
+                    "rewritten commands never re-enter [user_bindings]",
+                );
+                if let Some(cfg) = config
+                    && cfg.user_bindings.contains_key(command)
+                {
+                    trace.shadow(
+                        ResolutionTier::UserBinding,
+                        format!("user binding `{}`", command),
+                        "not consulted: rewritten commands dispatch into the registry exactly once",
+                    );
+                }
+            }
+            Some(b) if b.overrides_builtin() && url.is_some() => {
+                trace.record(
+                    ResolutionTier::UserBindingOverride,
+                    TierOutcome::NotReached,
+                    "an earlier tier matched",
+                );
+                trace.shadow(
+                    ResolutionTier::UserBindingOverride,
+                    format!("user binding `{}`", command),
+                    format!(
+                        "shadowed by {}; tier 1 runs before user bindings, even with override = true",
+                        winner
+                    ),
+                );
+            }
+            Some(b) if b.overrides_builtin() => {
+                url = Some(Self::resolve_user_binding(
+                    config,
+                    ResolutionTier::UserBindingOverride,
+                    command,
+                    b,
+                    full_args,
+                    &mut trace,
+                ));
+                winner = format!("the user binding `{}` (override = true)", command);
+            }
+            Some(_) => trace.record(
+                ResolutionTier::UserBindingOverride,
+                TierOutcome::NoMatch,
+                format!(
+                    "`{}` exists without override = true; deferred to tier 4",
+                    command
+                ),
+            ),
+            None if url.is_some() => trace.record(
+                ResolutionTier::UserBindingOverride,
+                TierOutcome::NotReached,
+                "an earlier tier matched",
+            ),
+            None => trace.record(
+                ResolutionTier::UserBindingOverride,
+                TierOutcome::NoMatch,
+                format!("no user binding named `{}`", command),
+            ),
         }
 
-        // Tier 3: built-in commands
+        // Tier 3: built-in commands, then named `[commands.<name>]` instances
         let lookup = COMMAND_LOOKUP.get_or_init(Self::initialize_command_lookup);
-        if let Some(handler) = lookup.get(command) {
-            return handler(full_args);
+        let instance = match lookup.get(command) {
+            Some(_) => None,
+            None => config
+                .and_then(|cfg| cfg.command_instance(command))
+                .and_then(|(kind, settings)| {
+                    Self::instance_type(kind).map(|t| (kind, settings, t.process))
+                }),
+        };
+        let builtin_name = match (lookup.get(command), &instance) {
+            (Some(_), _) => Some(format!("built-in `{}`", command)),
+            (None, Some((kind, _, _))) => {
+                Some(format!("`{}` instance of built-in `{}`", command, kind))
+            }
+            (None, None) => None,
+        };
+        match builtin_name {
+            Some(name) if url.is_some() => {
+                trace.record(
+                    ResolutionTier::BuiltIn,
+                    TierOutcome::NotReached,
+                    "an earlier tier matched",
+                );
+                trace.shadow(
+                    ResolutionTier::BuiltIn,
+                    name,
+                    format!("shadowed by {}", winner),
+                );
+            }
+            Some(name) => {
+                trace.record(
+                    ResolutionTier::BuiltIn,
+                    TierOutcome::Matched,
+                    format!("{} handled the input", name),
+                );
+                url = Some(match (lookup.get(command), instance) {
+                    (Some(handler), _) => handler(full_args),
+                    (None, Some((_, settings, process))) => {
+                        process(Self::instance_query(full_args), Some(settings))
+                    }
+                    (None, None) => unreachable!("builtin_name is only set for a handler"),
+                });
+                winner = format!("the {}", name);
+            }
+            None if url.is_some() => trace.record(
+                ResolutionTier::BuiltIn,
+                TierOutcome::NotReached,
+                "an earlier tier matched",
+            ),
+            None => trace.record(
+                ResolutionTier::BuiltIn,
+                TierOutcome::NoMatch,
+                format!("no built-in command named `{}`", command),
+            ),
         }
 
         // Tier 4: user bindings without override
-        if let Some(ref cfg) = cfg
-            && let Some((resolved, false)) = cfg.resolve_user_binding(command, full_args)
-        {
-            return Self::dispatch_resolved(resolved);
+        match binding {
+            _ if !consult_user_config => trace.record(
+                ResolutionTier::UserBinding,
+                TierOutcome::Skipped,
+                "rewritten commands never re-enter [user_bindings]",
+            ),
+            Some(b) if !b.overrides_builtin() && url.is_some() => {
+                trace.record(
+                    ResolutionTier::UserBinding,
+                    TierOutcome::NotReached,
+                    "an earlier tier matched",
+                );
+                let hint = if trace.matched_tier() == Some(ResolutionTier::BuiltIn) {
+                    "; add override = true to use it"
+                } else {
+                    ""
+                };
+                trace.shadow(
+                    ResolutionTier::UserBinding,
+                    format!("user binding `{}`", command),
+                    format!("shadowed by {}{}", winner, hint),
+                );
+            }
+            Some(b) if !b.overrides_builtin() => {
+                url = Some(Self::resolve_user_binding(
+                    config,
+                    ResolutionTier::UserBinding,
+                    command,
+                    b,
+                    full_args,
+                    &mut trace,
+                ));
+                winner = format!("the user binding `{}`", command);
+            }
+            _ if url.is_some() => trace.record(
+                ResolutionTier::UserBinding,
+                TierOutcome::NotReached,
+                "an earlier tier matched",
+            ),
+            _ => trace.record(
+                ResolutionTier::UserBinding,
+                TierOutcome::NoMatch,
+                format!("no user binding named `{}`", command),
+            ),
         }
 
-        // Tier 5: default search fallback
-        let engine = cfg
-            .map(|c| c.default_search)
-            .unwrap_or_else(|| "google".to_string());
-        crate::commands::search_url(&engine, full_args)
-    }
-
-    /// Same as [`Self::process_command`] but **skips user bindings entirely**.
-    /// Used to dispatch a `Command` binding's rewritten string without
-    /// recursing back into another `[user_bindings]` entry.
-    fn process_command_no_user_bindings(command: &str, full_args: &str) -> String {
-        if let Some(url) = Self::process_prefix_commands(command, full_args) {
-            return url;
+        if let Some(aliased) = user_config.and_then(|cfg| cfg.dropped_aliases.get(command)) {
+            trace.shadow(
+                ResolutionTier::UserBinding,
+                format!("[aliases] entry `{}`", command),
+                format!(
+                    "`{} = \"{}\"` was dropped during alias migration because \
+                     [user_bindings] already defines `{}`",
+                    command, aliased, command
+                ),
+            );
         }
 
-        let lookup = COMMAND_LOOKUP.get_or_init(Self::initialize_command_lookup);
-        if let Some(handler) = lookup.get(command) {
-            return handler(full_args);
+        // Tier 5: go-links
+        match user_config.and_then(|cfg| cfg.links.get(command)) {
+            _ if !consult_user_config => trace.record(
+                ResolutionTier::Link,
+                TierOutcome::Skipped,
+                "rewritten commands never re-enter go-links",
+            ),
+            Some(_) if url.is_some() => {
+                trace.record(
+                    ResolutionTier::Link,
+                    TierOutcome::NotReached,
+                    "an earlier tier matched",
+                );
+                trace.shadow(
+                    ResolutionTier::Link,
+                    format!("go-link `{}`", command),
+                    format!("shadowed by {}", winner),
+                );
+            }
+            Some(link) => {
+                let substitution = substitute_url_template(config, &link.url, command, full_args);
+                url = Some(substitution.url.clone());
+                trace.record(
+                    ResolutionTier::Link,
+                    TierOutcome::Matched,
+                    format!("go-link `{}` (owner {})", command, link.owner),
+                );
+                if let Some(last) = trace.tiers.last_mut() {
+                    last.substitution = Some(substitution);
+                }
+            }
+            None if url.is_some() => trace.record(
+                ResolutionTier::Link,
+                TierOutcome::NotReached,
+                "an earlier tier matched",
+            ),
+            None => trace.record(
+                ResolutionTier::Link,
+                TierOutcome::NoMatch,
+                format!("no go-link named `{}`", command),
+            ),
         }
 
-        let engine = get_global_config()
-            .map(|c| c.default_search)
-            .unwrap_or_else(|| "google".to_string());
-        crate::commands::search_url(&engine, full_args)
+        // Tier 6: default search fallback
+        let url = match url {
+            Some(url) => {
+                trace.record(
+                    ResolutionTier::SearchFallback,
+                    TierOutcome::NotReached,
+                    "an earlier tier matched",
+                );
+                url
+            }
+            None => {
+                let engine = providers::preferred(ProviderCategory::Search, config).name;
+                trace.record(
+                    ResolutionTier::SearchFallback,
+                    TierOutcome::Matched,
+                    format!("searched the full input with `{}`", engine),
+                );
+                crate::commands::fallback_search_url(config, full_args)
+            }
+        };
+
+        // [[rewrites]] run once, on the URL the outermost resolution returns
+        let (url, rewrites) = match user_config {
+            Some(cfg) => cfg.apply_rewrites(&url),
+            None => (url, Vec::new()),
+        };
+        trace.rewrites = rewrites;
+        trace.url = url;
+        trace
+    }
+
+    /// Resolve a matched user binding into a URL, recording the template
+    /// substitution (for `Url`) or the dispatched trace (for `Command`).
+    fn resolve_user_binding(
+        config: Option<&BunnylolConfig>,
+        tier: ResolutionTier,
+        name: &str,
+        binding: &UserBinding,
+        full_args: &str,
+        trace: &mut ResolutionTrace,
+    ) -> String {
+        match binding {
+            UserBinding::Url { url, .. } => {
+                let substitution = substitute_url_template(config, url, name, full_args);
+                let resolved = substitution.url.clone();
+                trace.record(
+                    tier,
+                    TierOutcome::Matched,
+                    format!("URL binding `{}`", name),
+                );
+                if let Some(last) = trace.tiers.last_mut() {
+                    last.substitution = Some(substitution);
+                }
+                resolved
+            }
+            UserBinding::Command { command, .. } => Self::dispatch_resolved(
+                config,
+                tier,
+                format!("command binding `{}`", name),
+                ResolvedBinding::Command(command.clone()),
+                None,
+                trace,
+            ),
+        }
     }
 
-    fn dispatch_resolved(resolved: crate::config::ResolvedBinding) -> String {
+    /// Record a matched tier and turn its [`ResolvedBinding`] into a URL.
+    /// `Command` results are dispatched into the registry exactly once with
+    /// user config skipped, and their trace is attached to `trace`.
+    fn dispatch_resolved(
+        config: Option<&BunnylolConfig>,
+        tier: ResolutionTier,
+        detail: String,
+        resolved: ResolvedBinding,
+        template: Option<String>,
+        trace: &mut ResolutionTrace,
+    ) -> String {
         match resolved {
-            crate::config::ResolvedBinding::Url(url) => url,
-            crate::config::ResolvedBinding::Command(rewritten) => {
+            ResolvedBinding::Url(url) => {
+                trace.record(tier, TierOutcome::Matched, detail);
+                if let (Some(template), Some(last)) = (template, trace.tiers.last_mut()) {
+                    last.substitution = Some(TemplateSubstitution {
+                        template,
+                        arguments: Some(trace.input.clone()),
+                        encoded: None,
+                        missing: None,
+                        url: url.clone(),
+                    });
+                }
+                url
+            }
+            ResolvedBinding::Command(rewritten) => {
+                trace.record(
+                    tier,
+                    TierOutcome::Matched,
+                    format!("{} rewrote the input to `{}`", detail, rewritten),
+                );
                 let cmd_word = crate::utils::get_command_from_query_string(&rewritten);
-                Self::process_command_no_user_bindings(cmd_word, &rewritten)
+                let dispatched = Self::resolve(config, cmd_word, &rewritten, false);
+                let url = dispatched.url.clone();
+                trace.dispatched = Some(Box::new(dispatched));
+                url
             }
         }
     }
 
-    /// Get all registered command bindings
+    /// Get all registered command bindings, followed by the prefix handlers
     pub fn get_all_commands() -> &'static Vec<BunnylolCommandInfo> {
-        BINDINGS_DATA.get_or_init(Self::get_all_commands_impl)
+        BINDINGS_DATA.get_or_init(|| {
+            let mut commands = Self::get_all_commands_impl();
+            commands.extend(Self::get_all_prefix_handlers_impl());
+            commands
+        })
     }
 
     /// All built-in command alias names. Used to detect silent conflicts with
@@ -215,18 +655,140 @@ impl BunnylolCommandRegistry {
         lookup.keys().copied().collect()
     }
 
-    /// Validate `[user_bindings]` against the built-in command set and return
-    /// any silently-shadowed entries (built-ins win unless `override = true`).
+    /// Validate `[user_bindings]` against the built-in command set (including
+    /// named `[commands.<name>]` instances) and return any silently-shadowed
+    /// entries (built-ins win unless `override = true`).
     pub fn validate_user_bindings(
         config: &crate::config::BunnylolConfig,
     ) -> Vec<crate::config::BindingConflict> {
-        config.validate_user_bindings_conflicts(&Self::builtin_binding_names())
+        let mut names: std::collections::HashSet<&str> = Self::builtin_binding_names();
+        names.extend(config.command_instance_names());
+        config.validate_user_bindings_conflicts(&names)
+    }
+
+    /// Named `[commands.<name>]` instances whose name is already a built-in
+    /// binding. The built-in wins, so these instances never fire.
+    pub fn shadowed_command_instances(config: &crate::config::BunnylolConfig) -> Vec<&str> {
+        let builtins = Self::builtin_binding_names();
+        config
+            .command_instance_names()
+            .into_iter()
+            .filter(|name| builtins.contains(name))
+            .collect()
+    }
+
+    /// The built-in a named instance `type` refers to. Every entry in
+    /// [`CONFIGURABLE_COMMANDS`](crate::config::CONFIGURABLE_COMMANDS) must have one.
+    fn instance_type(command_type: &str) -> Option<InstanceType> {
+        use crate::commands::*;
+
+        match command_type {
+            "github" => Some(InstanceType {
+                process: GitHubCommand::process_query,
+                info: <GitHubCommand as BunnylolCommand>::get_info,
+                default_base_url: GitHubCommand::DEFAULT_BASE_URL,
+            }),
+            "gitlab" => Some(InstanceType {
+                process: GitlabCommand::process_query,
+                info: <GitlabCommand as BunnylolCommand>::get_info,
+                default_base_url: GitlabCommand::DEFAULT_BASE_URL,
+            }),
+            "python" => Some(InstanceType {
+                process: PythonCommand::process_query,
+                info: <PythonCommand as BunnylolCommand>::get_info,
+                default_base_url: "https://docs.python.org",
+            }),
+            "rust" => Some(InstanceType {
+                process: RustCommand::process_query,
+                info: <RustCommand as BunnylolCommand>::get_info,
+                default_base_url: "https://doc.rust-lang.org",
+            }),
+            "node" => Some(InstanceType {
+                process: NodeCommand::process_query,
+                info: <NodeCommand as BunnylolCommand>::get_info,
+                default_base_url: "https://nodejs.org",
+            }),
+            "mdn" => Some(InstanceType {
+                process: MdnCommand::process_query,
+                info: <MdnCommand as BunnylolCommand>::get_info,
+                default_base_url: "https://developer.mozilla.org",
+            }),
+            "godocs" => Some(InstanceType {
+                process: GodocsCommand::process_query,
+                info: <GodocsCommand as BunnylolCommand>::get_info,
+                default_base_url: "https://go.dev",
+            }),
+            "amazon" => Some(InstanceType {
+                process: AmazonCommand::process_query,
+                info: <AmazonCommand as BunnylolCommand>::get_info,
+                default_base_url: "https://www.amazon.com",
+            }),
+            "wikipedia" => Some(InstanceType {
+                process: WikipediaCommand::process_query,
+                info: <WikipediaCommand as BunnylolCommand>::get_info,
+                default_base_url: "https://en.wikipedia.org",
+            }),
+            "maps" => Some(InstanceType {
+                process: GoogleMapsCommand::process_query,
+                info: <GoogleMapsCommand as BunnylolCommand>::get_info,
+                default_base_url: "https://www.google.com/maps",
+            }),
+            _ => None,
+        }
+    }
+
+    /// The input after a named instance's command word.
+    fn instance_query(full_args: &str) -> &str {
+        full_args
+            .trim()
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, rest)| rest.trim_start())
+    }
+
+    /// Listing entry for a named `[commands.<name>]` instance, derived from
+    /// the built-in it behaves like. `None` if `command_type` is unknown.
+    pub fn command_instance_info(
+        name: &str,
+        command_type: &str,
+        settings: &CommandConfig,
+    ) -> Option<BunnylolCommandInfo> {
+        let instance = Self::instance_type(command_type)?;
+        let builtin = (instance.info)();
+        let base_url = settings
+            .base_url()
+            .unwrap_or_else(|| instance.default_base_url.to_string());
+        let target = match (&settings.version, &settings.locale) {
+            (Some(version), _) => format!("{}, version {}", base_url, version),
+            (None, Some(locale)) => format!("{}, locale {}", base_url, locale),
+            (None, None) => base_url,
+        };
+        let example_args = Self::instance_query(&builtin.example);
+        let example = if example_args.is_empty() {
+            name.to_string()
+        } else {
+            format!("{} {}", name, example_args)
+        };
+        Some(BunnylolCommandInfo::new(
+            &[name],
+            &format!("{} ({})", builtin.description, target),
+            &example,
+        ))
+    }
+
+    /// Check `[[patterns]]` for rules that shadow built-in names or user
+    /// bindings, and for `examples` that a built-in prefix or an earlier rule
+    /// claims first.
+    pub fn validate_patterns(
+        config: &crate::config::BunnylolConfig,
+    ) -> Vec<crate::config::PatternDiagnostic> {
+        config.validate_patterns_conflicts(&Self::builtin_binding_names(), Self::is_prefix_command)
     }
 }
 
 #[cfg(test)]
 mod cache_tests {
     use super::*;
+    use crate::config::{CONFIGURABLE_COMMANDS, PatternRule};
 
     #[test]
     fn test_command_lookup_contains_all_bindings() {
@@ -267,8 +829,12 @@ mod cache_tests {
     fn test_bindings_data_cache() {
         let commands = BunnylolCommandRegistry::get_all_commands();
 
-        // Verify we have all expected commands
-        assert_eq!(commands.len(), 49, "Expected 49 commands");
+        // Verify we have all expected commands and prefix handlers
+        assert_eq!(
+            commands.len(),
+            50 + 7,
+            "Expected 50 commands and 7 prefix handlers"
+        );
 
         // Verify cache returns same pointer (not regenerated)
         let commands2 = BunnylolCommandRegistry::get_all_commands();
@@ -290,6 +856,177 @@ mod cache_tests {
         );
     }
 
+    #[test]
+    fn test_prefix_handler_examples_are_claimed_by_their_handler() {
+        use crate::commands::*;
+
+        fn check<H: PrefixHandler>() {
+            for example in H::EXAMPLES {
+                let command = crate::utils::get_command_from_query_string(example);
+                let (prefix, url) =
+                    BunnylolCommandRegistry::process_prefix_commands(command, example)
+                        .unwrap_or_else(|| panic!("'{}' was not claimed", example));
+                assert_eq!(
+                    prefix,
+                    H::PREFIX,
+                    "'{}' claimed by another handler",
+                    example
+                );
+                assert_eq!(url, H::process_prefix(example));
+            }
+        }
+
+        check::<StockTickerPrefix>();
+        check::<SubredditPrefix>();
+        check::<RedditUserPrefix>();
+        check::<SocialHandlePrefix>();
+        check::<PurlPrefix>();
+        check::<PythonVersionPrefix>();
+        check::<AmazonStorefrontPrefix>();
+    }
+
+    #[test]
+    fn test_prefix_handlers_listed_with_commands() {
+        let commands = BunnylolCommandRegistry::get_all_commands();
+        for usage in ["$<ticker>", "r/<subreddit>", "u/<user>", "@<handle>"] {
+            assert!(
+                commands.iter().any(|c| c.bindings == [usage]),
+                "{} missing from get_all_commands",
+                usage
+            );
+        }
+    }
+
+    #[test]
+    fn test_bare_prefixes_fall_through() {
+        for bare in ["$", "r/", "u/", "@"] {
+            assert!(!BunnylolCommandRegistry::is_prefix_command(bare));
+        }
+        assert_eq!(
+            BunnylolCommandRegistry::process_command("u/spez", "u/spez"),
+            "https://www.reddit.com/user/spez/"
+        );
+    }
+
+    #[test]
+    fn test_every_configurable_command_has_an_instance_type() {
+        for kind in CONFIGURABLE_COMMANDS {
+            assert!(
+                BunnylolCommandRegistry::instance_type(kind).is_some(),
+                "no instance type for '{}'",
+                kind
+            );
+        }
+    }
+
+    fn with_instances(toml_body: &str) -> BunnylolConfig {
+        let cfg: BunnylolConfig = toml::from_str(toml_body).expect("valid TOML");
+        cfg.validate_commands().expect("valid [commands]");
+        cfg
+    }
+
+    #[test]
+    fn test_named_instance_resolves_in_builtin_tier() {
+        let cfg = with_instances(
+            r#"
+[commands.ghe]
+type = "github"
+host = "github.corp.example"
+
+[commands.glab]
+type = "gitlab"
+"#,
+        );
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "ghe", "ghe infra/deploy", true);
+        assert_eq!(trace.url, "https://github.corp.example/infra/deploy");
+        assert_eq!(trace.matched_tier(), Some(ResolutionTier::BuiltIn));
+        assert!(
+            trace.tiers[2]
+                .detail
+                .contains("instance of built-in `github`")
+        );
+
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "ghe", "ghe", true);
+        assert_eq!(trace.url, "https://github.corp.example");
+
+        // Without a host, an instance uses the built-in's public default
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "glab", "glab a/b", true);
+        assert_eq!(trace.url, "https://gitlab.com/a/b");
+
+        // `gh` keeps opening github.com
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "gh", "gh a/b", true);
+        assert_eq!(trace.url, "https://github.com/a/b");
+    }
+
+    #[test]
+    fn test_docs_instances_and_default_versions() {
+        let cfg = with_instances(
+            r#"
+[commands.rust]
+version = "nightly"
+
+[commands.py39]
+type = "python"
+version = "3.9"
+"#,
+        );
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "py39", "py39 asyncio", true);
+        assert_eq!(
+            trace.url,
+            "https://docs.python.org/3.9/search.html?q=asyncio"
+        );
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "py", "py asyncio", true);
+        assert_eq!(trace.url, "https://docs.python.org/3/search.html?q=asyncio");
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "py3.12", "py3.12", true);
+        assert_eq!(trace.url, "https://docs.python.org/3.12/");
+
+        let info =
+            BunnylolCommandRegistry::command_instance_info("py39", "python", &cfg.commands["py39"])
+                .expect("python instance info");
+        assert!(
+            info.description
+                .ends_with("(https://docs.python.org, version 3.9)")
+        );
+    }
+
+    #[test]
+    fn test_named_instance_conflicts() {
+        let mut cfg = with_instances(
+            r#"
+[commands.ghe]
+type = "github"
+
+[commands.gl]
+type = "gitlab"
+host = "gitlab.corp.example"
+"#,
+        );
+        cfg.user_bindings
+            .insert("ghe".to_string(), url("https://example.com", false));
+
+        let conflicts = BunnylolCommandRegistry::validate_user_bindings(&cfg);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].name, "ghe");
+        assert_eq!(
+            BunnylolCommandRegistry::shadowed_command_instances(&cfg),
+            vec!["gl"]
+        );
+    }
+
+    #[test]
+    fn test_command_instance_info() {
+        let settings = CommandConfig {
+            command_type: Some("github".to_string()),
+            host: Some("github.corp.example".to_string()),
+            ..Default::default()
+        };
+        let info = BunnylolCommandRegistry::command_instance_info("ghe", "github", &settings)
+            .expect("github instance info");
+        assert_eq!(info.bindings, vec!["ghe"]);
+        assert!(info.description.ends_with("(https://github.corp.example)"));
+        assert_eq!(info.example, "ghe facebook/react");
+    }
+
     // ---------------- [user_bindings] regression tests ----------------
     //
     // These tests exercise pure helpers over a passed-in config. Tests that
@@ -342,6 +1079,309 @@ mod cache_tests {
         assert_eq!(names, vec!["gh"]);
     }
 
+    // ---------------- explain / resolution trace tests ----------------
+
+    fn config_with_bindings(bindings: &[(&str, crate::config::UserBinding)]) -> BunnylolConfig {
+        let mut cfg = BunnylolConfig::default();
+        for (name, binding) in bindings {
+            cfg.user_bindings.insert(name.to_string(), binding.clone());
+        }
+        cfg
+    }
+
+    fn url(url: &str, override_builtin: bool) -> UserBinding {
+        UserBinding::Url {
+            url: url.to_string(),
+            description: None,
+            override_builtin,
+        }
+    }
+
+    fn outcomes(trace: &ResolutionTrace) -> Vec<TierOutcome> {
+        trace.tiers.iter().map(|t| t.outcome).collect()
+    }
+
+    #[test]
+    fn test_explain_reports_user_binding_shadowed_by_builtin() {
+        let cfg = config_with_bindings(&[("gh", url("https://example.com/fork", false))]);
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "gh", "gh", true);
+
+        assert_eq!(trace.url, "https://github.com");
+        assert_eq!(trace.matched_tier(), Some(ResolutionTier::BuiltIn));
+        assert_eq!(
+            outcomes(&trace),
+            vec![
+                TierOutcome::NoMatch,
+                TierOutcome::NoMatch,
+                TierOutcome::Matched,
+                TierOutcome::NotReached,
+                TierOutcome::NotReached,
+                TierOutcome::NotReached,
+            ]
+        );
+        assert_eq!(trace.shadowed.len(), 1);
+        assert_eq!(trace.shadowed[0].tier, ResolutionTier::UserBinding);
+        assert!(trace.shadowed[0].reason.contains("override = true"));
+    }
+
+    #[test]
+    fn test_explain_reports_builtin_shadowed_by_override_and_prefix() {
+        let cfg = config_with_bindings(&[
+            ("gh", url("https://example.com/fork", true)),
+            ("r/rust", url("https://example.com/never", false)),
+        ]);
+
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "gh", "gh", true);
+        assert_eq!(trace.url, "https://example.com/fork");
+        assert_eq!(
+            trace.matched_tier(),
+            Some(ResolutionTier::UserBindingOverride)
+        );
+        assert_eq!(trace.shadowed[0].tier, ResolutionTier::BuiltIn);
+
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "r/rust", "r/rust", true);
+        assert_eq!(trace.url, "https://www.reddit.com/r/rust/");
+        assert_eq!(trace.matched_tier(), Some(ResolutionTier::Prefix));
+        assert!(trace.shadowed[0].reason.contains("prefix handler"));
+    }
+
+    #[test]
+    fn test_explain_records_url_template_substitution() {
+        let cfg =
+            config_with_bindings(&[("jira", url("https://corp.atlassian.net/browse/{}", false))]);
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "jira", "jira PROJ 1", true);
+
+        let matched = trace
+            .tiers
+            .iter()
+            .find(|t| t.outcome == TierOutcome::Matched)
+            .unwrap();
+        let substitution = matched.substitution.as_ref().unwrap();
+        assert_eq!(substitution.arguments.as_deref(), Some("PROJ 1"));
+        assert_eq!(substitution.encoded.as_deref(), Some("PROJ%201"));
+        assert_eq!(trace.url, "https://corp.atlassian.net/browse/PROJ%201");
+    }
+
+    #[test]
+    fn test_explain_command_binding_dispatches_once() {
+        let cfg = config_with_bindings(&[
+            (
+                "work",
+                UserBinding::Command {
+                    command: "jira PROJ-1".to_string(),
+                    description: None,
+                    override_builtin: false,
+                },
+            ),
+            ("jira", url("https://corp.atlassian.net/browse/{}", false)),
+        ]);
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "work", "work", true);
+
+        let dispatched = trace.dispatched.as_ref().expect("command dispatch trace");
+        assert_eq!(
+            dispatched.matched_tier(),
+            Some(ResolutionTier::SearchFallback)
+        );
+        assert_eq!(dispatched.tiers[1].outcome, TierOutcome::Skipped);
+        assert_eq!(dispatched.shadowed[0].name, "user binding `jira`");
+        assert_eq!(trace.url, dispatched.url);
+    }
+
+    #[test]
+    fn test_go_links_resolve_after_user_bindings() {
+        let mut cfg = config_with_bindings(&[("kb", url("https://kb.example/{}", false))]);
+        let link = |url: &str| crate::ShortLink {
+            url: url.to_string(),
+            description: None,
+            owner: "alice".to_string(),
+            created_at: 0,
+            hits: 0,
+            expires_at: None,
+        };
+        cfg.links = std::sync::Arc::new(
+            [
+                ("kb".to_string(), link("https://never.example")),
+                ("oncall".to_string(), link("https://oncall.example/{}")),
+            ]
+            .into(),
+        );
+
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "oncall", "oncall infra", true);
+        assert_eq!(trace.url, "https://oncall.example/infra");
+        assert_eq!(trace.matched_tier(), Some(ResolutionTier::Link));
+        assert!(trace.to_string().contains("Tier 5 · go-links"));
+
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "kb", "kb rust", true);
+        assert_eq!(trace.url, "https://kb.example/rust");
+        assert_eq!(trace.shadowed[0].tier, ResolutionTier::Link);
+        assert!(trace.shadowed[0].reason.contains("user binding `kb`"));
+
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "oncall", "oncall", false);
+        assert_eq!(trace.matched_tier(), Some(ResolutionTier::SearchFallback));
+    }
+
+    #[test]
+    fn test_explain_reports_alias_dropped_during_migration() {
+        let mut cfg = config_with_bindings(&[("work", url("https://example.com/work", false))]);
+        cfg.dropped_aliases
+            .insert("work".to_string(), "gh mycompany".to_string());
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "work", "work", true);
+
+        assert_eq!(trace.url, "https://example.com/work");
+        assert!(
+            trace
+                .shadowed
+                .iter()
+                .any(|c| c.name == "[aliases] entry `work`" && c.reason.contains("gh mycompany"))
+        );
+    }
+
+    #[test]
+    fn test_pattern_rule_resolves_in_tier_one() {
+        let mut cfg = BunnylolConfig::default();
+        cfg.patterns.push(PatternRule::url(
+            "jira",
+            r"^([A-Z]+)-(\d+)$",
+            "https://corp.atlassian.net/browse/$1-$2",
+        ));
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "PROJ-123", "PROJ-123", true);
+
+        assert_eq!(trace.url, "https://corp.atlassian.net/browse/PROJ-123");
+        assert_eq!(trace.matched_tier(), Some(ResolutionTier::Prefix));
+        assert!(trace.tiers[0].detail.contains(r#"$1="PROJ""#));
+
+        // Not consulted once the input no longer matches
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "gh", "gh", true);
+        assert_eq!(trace.url, "https://github.com");
+    }
+
+    #[test]
+    fn test_pattern_rule_shadowing_and_ordering() {
+        let mut cfg = config_with_bindings(&[("gh", url("https://example.com/gh", true))]);
+        cfg.patterns.push(PatternRule::url(
+            "greedy",
+            r"^\S+$",
+            "https://example.com/w/$0",
+        ));
+        cfg.patterns.push(PatternRule::url(
+            "never",
+            r"^gh$",
+            "https://example.com/never",
+        ));
+
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "gh", "gh", true);
+        assert_eq!(trace.url, "https://example.com/w/gh");
+        assert!(
+            trace
+                .shadowed
+                .iter()
+                .any(|c| c.name == "built-in `gh`"
+                    && c.reason == "shadowed by pattern rule `greedy`")
+        );
+        assert!(
+            trace.shadowed.iter().any(
+                |c| c.name == "user binding `gh`" && c.reason.contains("pattern rule `greedy`")
+            )
+        );
+
+        // Built-in prefixes still win over patterns
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "$META", "$META", true);
+        assert!(trace.url.contains("META"));
+        assert!(
+            trace
+                .shadowed
+                .iter()
+                .any(|c| c.name == "pattern rule `greedy`" && c.reason.contains("`$` prefix"))
+        );
+    }
+
+    #[test]
+    fn test_command_pattern_dispatches_once() {
+        let mut cfg = config_with_bindings(&[("PROJ", url("https://example.com/loop", false))]);
+        cfg.patterns.push(PatternRule::command(
+            "pr",
+            r"^#(\d+)$",
+            "gh facebook/react/pull/$1",
+        ));
+        // Rewritten input that would match the pattern again must not loop
+        cfg.patterns
+            .push(PatternRule::command("loop", r"^PROJ", "PROJ again"));
+
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "#42", "#42", true);
+        assert_eq!(trace.url, "https://github.com/facebook/react/pull/42");
+        let dispatched = trace.dispatched.as_ref().expect("command dispatch trace");
+        assert_eq!(dispatched.matched_tier(), Some(ResolutionTier::BuiltIn));
+
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "PROJ", "PROJ", true);
+        let dispatched = trace.dispatched.as_ref().expect("command dispatch trace");
+        assert_eq!(
+            dispatched.matched_tier(),
+            Some(ResolutionTier::SearchFallback)
+        );
+    }
+
+    #[test]
+    fn test_rewrites_apply_to_every_tier_once() {
+        let mut cfg = config_with_bindings(&[("news", url("http://reddit.com/r/news", false))]);
+        cfg.rewrites.push(crate::config::RewriteRule::replace_host(
+            "old-reddit",
+            r"^(www\.)?reddit\.com$",
+            "old.reddit.com",
+        ));
+
+        // Built-in prefix handler
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "r/rust", "r/rust", true);
+        assert_eq!(trace.url, "https://old.reddit.com/r/rust/");
+        assert_eq!(trace.rewrites.len(), 1);
+        assert_eq!(trace.rewrites[0].rule, "old-reddit");
+        assert_eq!(trace.rewrites[0].before, "https://www.reddit.com/r/rust/");
+
+        // User binding
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "news", "news", true);
+        assert_eq!(trace.url, "http://old.reddit.com/r/news");
+
+        // The search fallback, and no rewrite without a match
+        let trace = BunnylolCommandRegistry::resolve(Some(&cfg), "zzz", "zzz", true);
+        assert!(trace.rewrites.is_empty());
+        assert!(
+            trace
+                .to_string()
+                .contains("Result:  https://www.google.com/search")
+        );
+    }
+
+    #[test]
+    fn test_validate_patterns_reports_shadowing_and_examples() {
+        let mut cfg = config_with_bindings(&[("wiki", url("https://example.com/wiki", false))]);
+        let mut greedy = PatternRule::url("greedy", r"^[a-z]+$", "https://example.com/$0");
+        greedy.examples = vec!["$AAPL".to_string(), "abc".to_string()];
+        let mut later = PatternRule::url("later", r"^abc$", "https://example.com/abc");
+        later.examples = vec!["abc".to_string(), "ABC".to_string()];
+        cfg.patterns = vec![greedy, later];
+
+        let messages: Vec<String> = BunnylolCommandRegistry::validate_patterns(&cfg)
+            .into_iter()
+            .map(|d| format!("{}: {}", d.rule, d.message))
+            .collect();
+
+        assert!(
+            messages
+                .iter()
+                .any(|m| m.starts_with("greedy: matches built-in commands") && m.contains("'gh'"))
+        );
+        assert!(messages.contains(
+            &"greedy: matches user binding 'wiki'; patterns run before user bindings".to_string()
+        ));
+        assert!(messages.contains(
+            &"greedy: example '$AAPL' is claimed by a built-in prefix handler first".to_string()
+        ));
+        assert!(messages.contains(
+            &"later: example 'abc' is claimed by earlier rule 'greedy'; move this rule above it"
+                .to_string()
+        ));
+        assert!(messages.contains(&"later: example 'ABC' does not match this rule".to_string()));
+    }
+
     #[test]
     fn test_no_binding_collisions() {
         use std::collections::HashMap;
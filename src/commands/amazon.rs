@@ -14,31 +14,117 @@
 /// - az cart -> https://www.amazon.com/gp/cart/view.html/
 /// - az pay/wallet -> https://www.amazon.com/cpe/yourpayments/wallet
 /// - az [search terms] -> https://www.amazon.com/s?k=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+/// - amazon.[tld] ... -> the same on that storefront (amazon.de headphones)
+///
+/// The storefront follows the configured `locale` (`[commands.amazon] locale`
+/// or the global one): "de-DE" opens amazon.de, "ja-JP" amazon.co.jp.
+use crate::commands::Locale;
+use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo, PrefixHandler};
+use crate::commands::providers::{self, ProviderCategory};
+use crate::config::{CommandConfig, get_global_config};
 use crate::utils::url_encoding::build_search_url;
 
 pub struct AmazonCommand;
 
+impl AmazonCommand {
+    /// Storefront domains by region
+    const STOREFRONTS: &'static [(&'static str, &'static str)] = &[
+        ("US", "com"),
+        ("CA", "ca"),
+        ("MX", "com.mx"),
+        ("BR", "com.br"),
+        ("GB", "co.uk"),
+        ("IE", "ie"),
+        ("DE", "de"),
+        ("AT", "de"),
+        ("CH", "de"),
+        ("FR", "fr"),
+        ("BE", "com.be"),
+        ("NL", "nl"),
+        ("IT", "it"),
+        ("ES", "es"),
+        ("SE", "se"),
+        ("PL", "pl"),
+        ("TR", "com.tr"),
+        ("AE", "ae"),
+        ("SA", "sa"),
+        ("EG", "eg"),
+        ("IN", "in"),
+        ("JP", "co.jp"),
+        ("SG", "sg"),
+        ("AU", "com.au"),
+    ];
+
+    /// Region assumed for a locale given as a language only
+    const LANGUAGE_REGIONS: &'static [(&'static str, &'static str)] = &[
+        ("de", "DE"),
+        ("fr", "FR"),
+        ("it", "IT"),
+        ("es", "ES"),
+        ("nl", "NL"),
+        ("sv", "SE"),
+        ("pl", "PL"),
+        ("tr", "TR"),
+        ("ja", "JP"),
+    ];
+
+    /// The storefront TLD for `locale` ("com" when unknown or unset).
+    pub fn storefront(locale: Option<&Locale>) -> &'static str {
+        let region = locale.and_then(|locale| match &locale.region {
+            Some(region) => Some(region.as_str()),
+            None => Self::LANGUAGE_REGIONS
+                .iter()
+                .find(|(language, _)| *language == locale.language)
+                .map(|(_, region)| *region),
+        });
+        region
+            .and_then(|region| Self::STOREFRONTS.iter().find(|(r, _)| *r == region))
+            .map_or("com", |(_, tld)| tld)
+    }
+
+    fn is_storefront(tld: &str) -> bool {
+        Self::STOREFRONTS.iter().any(|(_, known)| *known == tld)
+    }
+
+    /// The URL for `query` on the `amazon.<tld>` storefront.
+    fn storefront_url(tld: &str, query: &str) -> String {
+        let site = format!("https://www.amazon.{}", tld);
+        match query {
+            "" => format!("https://amazon.{}/", tld),
+            "orders" => format!("{}/gp/css/order-history?ref_=nav_orders_first", site),
+            "account" => format!("{}/gp/css/homepage.html?ref_=nav_youraccount_btn", site),
+            "messages" => format!("{}/gp/message", site),
+            "cart" => format!("{}/gp/cart/view.html/", site),
+            "pay" | "wallet" => format!("{}/cpe/yourpayments/wallet", site),
+            _ => build_search_url(&format!("{}/s", site), "k", query),
+        }
+    }
+
+    /// Build the URL for `query` (the input after the command word) on the
+    /// storefront for the configured locale. With another `[providers]
+    /// shopping`, searches go to that site; account pages stay on Amazon.
+    pub fn process_query(query: &str, settings: Option<&CommandConfig>) -> String {
+        let config = get_global_config();
+        let provider = providers::preferred(ProviderCategory::Shopping, config.as_ref());
+        let account_page = matches!(
+            query,
+            "orders" | "account" | "messages" | "cart" | "pay" | "wallet"
+        );
+        if !provider.is_default() && !account_page {
+            return provider.url(query);
+        }
+        let locale = Locale::configured(config.as_ref(), settings);
+        Self::storefront_url(Self::storefront(locale.as_ref()), query)
+    }
+}
+
 impl BunnylolCommand for AmazonCommand {
     const BINDINGS: &'static [&'static str] = &["az", "amzn", "azn", "amazon"];
 
     fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
-        if query.is_empty() {
-            "https://amazon.com/".to_string()
-        } else {
-            match query {
-                "orders" => {
-                    "https://www.amazon.com/gp/css/order-history?ref_=nav_orders_first".to_string()
-                }
-                "account" => "https://www.amazon.com/gp/css/homepage.html?ref_=nav_youraccount_btn"
-                    .to_string(),
-                "messages" => "https://www.amazon.com/gp/message".to_string(),
-                "cart" => "https://www.amazon.com/gp/cart/view.html/".to_string(),
-                "pay" | "wallet" => "https://www.amazon.com/cpe/yourpayments/wallet".to_string(),
-                _ => build_search_url("https://www.amazon.com/s", "k", query),
-            }
-        }
+        let config = get_global_config();
+        let settings = config.as_ref().and_then(|c| c.command_settings("amazon"));
+        Self::process_query(Self::get_command_args(args), settings)
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -47,6 +133,35 @@ impl BunnylolCommand for AmazonCommand {
             "Navigate to Amazon or search for products",
             "az headphones",
         )
+        .with_examples(&["amazon.de headphones", "az orders"])
+    }
+}
+
+/// `amazon.de headphones`: a storefront named in the command word.
+pub struct AmazonStorefrontPrefix;
+
+impl PrefixHandler for AmazonStorefrontPrefix {
+    const PREFIX: &'static str = "amazon.";
+    const EXAMPLES: &'static [&'static str] = &["amazon.de headphones", "amazon.co.jp"];
+
+    fn process_prefix(full_args: &str) -> String {
+        let (command, query) = full_args.split_once(' ').unwrap_or((full_args, ""));
+        let tld = command.strip_prefix(Self::PREFIX).unwrap_or("com");
+        AmazonCommand::storefront_url(&tld.to_ascii_lowercase(), query.trim())
+    }
+
+    fn matches(command: &str) -> bool {
+        command
+            .strip_prefix(Self::PREFIX)
+            .is_some_and(|tld| AmazonCommand::is_storefront(&tld.to_ascii_lowercase()))
+    }
+
+    fn get_info() -> BunnylolCommandInfo {
+        BunnylolCommandInfo::new(
+            &["amazon.<tld>"],
+            "Open a regional Amazon storefront, or search it",
+            &Self::EXAMPLES.join("  or  "),
+        )
     }
 }
 
@@ -101,4 +216,52 @@ mod tests {
             "https://www.amazon.com/cpe/yourpayments/wallet"
         );
     }
+
+    #[test]
+    fn test_amazon_storefront_for_locale() {
+        let storefront = |tag: &str| AmazonCommand::storefront(Locale::parse(tag).as_ref());
+        assert_eq!(storefront("de-DE"), "de");
+        assert_eq!(storefront("de-AT"), "de");
+        assert_eq!(storefront("ja"), "co.jp");
+        assert_eq!(storefront("en-GB"), "co.uk");
+        assert_eq!(storefront("en"), "com");
+        assert_eq!(storefront("fi-FI"), "com");
+        assert_eq!(AmazonCommand::storefront(None), "com");
+    }
+
+    #[test]
+    fn test_amazon_configured_locale() {
+        let settings = CommandConfig {
+            locale: Some("ja-JP".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            AmazonCommand::process_query("headphones", Some(&settings)),
+            "https://www.amazon.co.jp/s?k=headphones"
+        );
+        assert_eq!(
+            AmazonCommand::process_query("", Some(&settings)),
+            "https://amazon.co.jp/"
+        );
+    }
+
+    #[test]
+    fn test_amazon_storefront_prefix() {
+        assert!(AmazonStorefrontPrefix::matches("amazon.de"));
+        assert!(AmazonStorefrontPrefix::matches("amazon.co.uk"));
+        assert!(!AmazonStorefrontPrefix::matches("amazon."));
+        assert!(!AmazonStorefrontPrefix::matches("amazon.xyz"));
+        assert_eq!(
+            AmazonStorefrontPrefix::process_prefix("amazon.de headphones"),
+            "https://www.amazon.de/s?k=headphones"
+        );
+        assert_eq!(
+            AmazonStorefrontPrefix::process_prefix("amazon.co.jp orders"),
+            "https://www.amazon.co.jp/gp/css/order-history?ref_=nav_orders_first"
+        );
+        assert_eq!(
+            AmazonStorefrontPrefix::process_prefix("amazon.fr"),
+            "https://amazon.fr/"
+        );
+    }
 }
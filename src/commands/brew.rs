@@ -8,9 +8,13 @@
 /// Homebrew command handler
 /// Supports:
 /// - brew/homebrew -> https://formulae.brew.sh
-/// - brew [search terms] -> https://formulae.brew.sh/?search=[search terms]
+/// - brew [search terms] -> https://formulae.brew.sh/?search=[search terms] (two or more words)
+/// - brew [formula] -> https://formulae.brew.sh/formula/[formula]
+/// - brew [formula]@[version] -> https://formulae.brew.sh/formula/[formula] (Homebrew has one version per formula)
+/// - brew [formula] source -> the formula's Ruby file in homebrew-core
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use crate::commands::package::{PackagePage, PackageRegistry};
+use crate::utils::url_encoding::{build_path_url, encode_url};
 
 pub struct BrewCommand;
 
@@ -21,6 +25,8 @@ impl BunnylolCommand for BrewCommand {
         let query = Self::get_command_args(args);
         if query.is_empty() {
             "https://formulae.brew.sh".to_string()
+        } else if let Some(url) = Self::route_package(query) {
+            url
         } else {
             format!("https://formulae.brew.sh/?search={}", encode_url(query))
         }
@@ -32,6 +38,31 @@ impl BunnylolCommand for BrewCommand {
             "Navigate to formulae.brew.sh or search for Homebrew packages",
             "brew wget",
         )
+        .with_examples(&["brew wget@latest", "brew ripgrep source"])
+    }
+}
+
+impl PackageRegistry for BrewCommand {
+    const PURL_TYPE: &'static str = "brew";
+
+    fn package_url(name: &str, _version: Option<&str>) -> String {
+        build_path_url("https://formulae.brew.sh/formula", name)
+    }
+
+    fn page_url(name: &str, _version: Option<&str>, page: PackagePage) -> Option<String> {
+        match page {
+            // homebrew-core shards formulae by their first letter
+            PackagePage::Source => name.chars().next().map(|first| {
+                build_path_url(
+                    "https://github.com/Homebrew/homebrew-core/blob/HEAD/Formula",
+                    &format!("{}/{}.rb", first.to_ascii_lowercase(), name),
+                )
+            }),
+            PackagePage::Docs
+            | PackagePage::Versions
+            | PackagePage::Deps
+            | PackagePage::Changelog => None,
+        }
     }
 }
 
@@ -55,11 +86,23 @@ mod tests {
     fn test_brew_command_search() {
         assert_eq!(
             BrewCommand::process_args("brew wget"),
-            "https://formulae.brew.sh/?search=wget"
+            "https://formulae.brew.sh/formula/wget"
+        );
+        assert_eq!(
+            BrewCommand::process_args("homebrew web server"),
+            "https://formulae.brew.sh/?search=web%20server"
+        );
+    }
+
+    #[test]
+    fn test_brew_package_routes() {
+        assert_eq!(
+            BrewCommand::process_args("brew wget@latest"),
+            "https://formulae.brew.sh/formula/wget"
         );
         assert_eq!(
-            BrewCommand::process_args("homebrew nginx"),
-            "https://formulae.brew.sh/?search=nginx"
+            BrewCommand::process_args("brew ripgrep source"),
+            "https://github.com/Homebrew/homebrew-core/blob/HEAD/Formula/r/ripgrep.rb"
         );
     }
 }
@@ -13,6 +13,8 @@ pub struct BunnylolCommandInfo {
     pub bindings: Vec<String>,
     pub description: String,
     pub example: String,
+    /// Further examples, one per supported input form (beyond `example`)
+    pub examples: Vec<String>,
 }
 
 impl BunnylolCommandInfo {
@@ -22,8 +24,20 @@ impl BunnylolCommandInfo {
             bindings: bindings.iter().map(|s| s.to_string()).collect(),
             description: description.to_string(),
             example: example.to_string(),
+            examples: Vec::new(),
         }
     }
+
+    /// Add further examples, one per supported input form
+    pub fn with_examples(mut self, examples: &[&str]) -> Self {
+        self.examples.extend(examples.iter().map(|s| s.to_string()));
+        self
+    }
+
+    /// `example` followed by the further `examples`
+    pub fn all_examples(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.example.as_str()).chain(self.examples.iter().map(String::as_str))
+    }
 }
 
 /// Bunnylol Command trait that all URL builders must implement
@@ -58,6 +72,29 @@ pub trait BunnylolCommand {
     fn get_info() -> BunnylolCommandInfo;
 }
 
+/// Prefix handler trait for inputs recognized by their leading characters
+/// rather than a command word (e.g. `$META`, `r/rust`). Prefix handlers are
+/// checked before every other resolution tier.
+pub trait PrefixHandler {
+    /// The literal prefix the command word must start with (e.g. "$", "r/")
+    const PREFIX: &'static str;
+
+    /// Inputs this handler claims, shown in listings (e.g. ["$META", "$AAPL"])
+    const EXAMPLES: &'static [&'static str];
+
+    /// Process the full input (prefixed word included) and return the URL
+    fn process_prefix(full_args: &str) -> String;
+
+    /// Check if this handler claims the given command word. A bare prefix
+    /// (e.g. just `$`) is not claimed and falls through to the other tiers.
+    fn matches(command: &str) -> bool {
+        command.len() > Self::PREFIX.len() && command.starts_with(Self::PREFIX)
+    }
+
+    /// Get information about this handler (usage form, description, examples)
+    fn get_info() -> BunnylolCommandInfo;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,6 +119,33 @@ mod tests {
         }
     }
 
+    // Mock prefix handler for testing
+    struct TestPrefix;
+
+    impl PrefixHandler for TestPrefix {
+        const PREFIX: &'static str = "t/";
+        const EXAMPLES: &'static [&'static str] = &["t/hello"];
+
+        fn process_prefix(full_args: &str) -> String {
+            format!("https://test.com/{}", &full_args[Self::PREFIX.len()..])
+        }
+
+        fn get_info() -> BunnylolCommandInfo {
+            BunnylolCommandInfo::new(&["t/NAME"], "Test prefix", Self::EXAMPLES[0])
+        }
+    }
+
+    #[test]
+    fn test_prefix_handler_matches() {
+        assert!(TestPrefix::matches("t/hello"));
+        assert!(!TestPrefix::matches("t/"));
+        assert!(!TestPrefix::matches("test"));
+        assert_eq!(
+            TestPrefix::process_prefix("t/hello"),
+            "https://test.com/hello"
+        );
+    }
+
     #[test]
     fn test_bunnylol_command_get_command_args() {
         assert_eq!(TestCommand::get_command_args("test"), "");
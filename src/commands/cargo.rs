@@ -8,11 +8,15 @@
 /// Cargo/Crates command handler
 /// Supports:
 /// - cargo -> https://crates.io
-/// - cargo [search terms] -> https://crates.io/search?q=[search terms]
+/// - cargo [search terms] -> https://crates.io/search?q=[search terms] (two or more words)
+/// - cargo [crate] -> https://crates.io/crates/[crate]
 /// - cargo settings -> https://crates.io/settings/profile
 /// - cargo tokens/api -> https://crates.io/settings/tokens
+/// - cargo [crate]@[version] -> https://crates.io/crates/[crate]/[version]
+/// - cargo [crate][@version] docs|versions|source|deps|changelog -> docs.rs / crates.io pages
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::build_search_url;
+use crate::commands::package::{PackagePage, PackageRegistry, deps_dev_url};
+use crate::utils::url_encoding::{build_path_url, build_search_url};
 
 pub struct CargoCommand;
 
@@ -25,7 +29,8 @@ impl BunnylolCommand for CargoCommand {
             "" => "https://crates.io".to_string(),
             "settings" => "https://crates.io/settings/profile".to_string(),
             "tokens" | "api" => "https://crates.io/settings/tokens".to_string(),
-            _ => build_search_url("https://crates.io/search", "q", query),
+            _ => Self::route_package(query)
+                .unwrap_or_else(|| build_search_url("https://crates.io/search", "q", query)),
         }
     }
 
@@ -35,6 +40,49 @@ impl BunnylolCommand for CargoCommand {
             "Navigate to crates.io or search for Rust crates",
             "cargo serde",
         )
+        .with_examples(&[
+            "cargo serde@1.0.200",
+            "cargo tokio docs",
+            "cargo serde@latest deps",
+        ])
+    }
+}
+
+impl PackageRegistry for CargoCommand {
+    const PURL_TYPE: &'static str = "cargo";
+
+    fn package_url(name: &str, version: Option<&str>) -> String {
+        let path = match version {
+            Some(version) => format!("{}/{}", name, version),
+            None => name.to_string(),
+        };
+        build_path_url("https://crates.io/crates", &path)
+    }
+
+    fn page_url(name: &str, version: Option<&str>, page: PackagePage) -> Option<String> {
+        let docs_rs = |path: &str| {
+            build_path_url(
+                "https://docs.rs",
+                &format!("{}/{}{}", name, version.unwrap_or("latest"), path),
+            )
+        };
+        Some(match page {
+            PackagePage::Docs => docs_rs(""),
+            PackagePage::Versions => format!("{}/versions", Self::package_url(name, None)),
+            PackagePage::Source => build_path_url(
+                "https://docs.rs/crate",
+                &format!("{}/{}/source/", name, version.unwrap_or("latest")),
+            ),
+            PackagePage::Deps => deps_dev_url("cargo", name, version),
+            PackagePage::Changelog => build_path_url(
+                "https://docs.rs/crate",
+                &format!(
+                    "{}/{}/source/CHANGELOG.md",
+                    name,
+                    version.unwrap_or("latest")
+                ),
+            ),
+        })
     }
 }
 
@@ -52,7 +100,7 @@ mod tests {
     fn test_cargo_command_search() {
         assert_eq!(
             CargoCommand::process_args("cargo serde"),
-            "https://crates.io/search?q=serde"
+            "https://crates.io/crates/serde"
         );
         assert_eq!(
             CargoCommand::process_args("crates tokio async"),
@@ -79,4 +127,40 @@ mod tests {
             "https://crates.io/settings/tokens"
         );
     }
+
+    #[test]
+    fn test_cargo_package_routes() {
+        assert_eq!(
+            CargoCommand::process_args("cargo serde@1.0.200"),
+            "https://crates.io/crates/serde/1.0.200"
+        );
+        assert_eq!(
+            CargoCommand::process_args("cargo serde@latest"),
+            "https://crates.io/crates/serde"
+        );
+        assert_eq!(
+            CargoCommand::process_args("cargo tokio docs"),
+            "https://docs.rs/tokio/latest"
+        );
+        assert_eq!(
+            CargoCommand::process_args("cargo tokio@1.40.0 docs"),
+            "https://docs.rs/tokio/1.40.0"
+        );
+        assert_eq!(
+            CargoCommand::process_args("cargo serde versions"),
+            "https://crates.io/crates/serde/versions"
+        );
+        assert_eq!(
+            CargoCommand::process_args("cargo serde source"),
+            "https://docs.rs/crate/serde/latest/source/"
+        );
+        assert_eq!(
+            CargoCommand::process_args("cargo serde@1.0.200 deps"),
+            "https://deps.dev/cargo/serde/1.0.200"
+        );
+        assert_eq!(
+            CargoCommand::process_args("cargo serde changelog"),
+            "https://docs.rs/crate/serde/latest/source/CHANGELOG.md"
+        );
+    }
 }
@@ -8,9 +8,13 @@
 /// Chocolatey command handler
 /// Supports:
 /// - choco/chocolatey -> https://community.chocolatey.org
-/// - choco [search terms] -> https://community.chocolatey.org/packages?q=[search terms]
+/// - choco [search terms] -> https://community.chocolatey.org/packages?q=[search terms] (two or more words)
+/// - choco [package] -> https://community.chocolatey.org/packages/[package]
+/// - choco [package]@[version] -> https://community.chocolatey.org/packages/[package]/[version]
+/// - choco [package] versions -> the package's version history
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::build_search_url;
+use crate::commands::package::{PackagePage, PackageRegistry};
+use crate::utils::url_encoding::{build_path_url, build_search_url};
 
 pub struct ChocoCommand;
 
@@ -21,6 +25,8 @@ impl BunnylolCommand for ChocoCommand {
         let query = Self::get_command_args(args);
         if query.is_empty() {
             "https://community.chocolatey.org".to_string()
+        } else if let Some(url) = Self::route_package(query) {
+            url
         } else {
             build_search_url("https://community.chocolatey.org/packages", "q", query)
         }
@@ -32,6 +38,31 @@ impl BunnylolCommand for ChocoCommand {
             "Navigate to community.chocolatey.org or search for Windows packages",
             "choco git",
         )
+        .with_examples(&["choco git@2.45.1", "choco vscode versions"])
+    }
+}
+
+impl PackageRegistry for ChocoCommand {
+    const PURL_TYPE: &'static str = "chocolatey";
+
+    fn package_url(name: &str, version: Option<&str>) -> String {
+        let path = match version {
+            Some(version) => format!("{}/{}", name, version),
+            None => name.to_string(),
+        };
+        build_path_url("https://community.chocolatey.org/packages", &path)
+    }
+
+    fn page_url(name: &str, _version: Option<&str>, page: PackagePage) -> Option<String> {
+        match page {
+            PackagePage::Versions => {
+                Some(format!("{}#versionhistory", Self::package_url(name, None)))
+            }
+            PackagePage::Docs
+            | PackagePage::Source
+            | PackagePage::Deps
+            | PackagePage::Changelog => None,
+        }
     }
 }
 
@@ -55,11 +86,23 @@ mod tests {
     fn test_choco_command_search() {
         assert_eq!(
             ChocoCommand::process_args("choco git"),
-            "https://community.chocolatey.org/packages?q=git"
+            "https://community.chocolatey.org/packages/git"
+        );
+        assert_eq!(
+            ChocoCommand::process_args("chocolatey visual studio"),
+            "https://community.chocolatey.org/packages?q=visual%20studio"
+        );
+    }
+
+    #[test]
+    fn test_choco_package_routes() {
+        assert_eq!(
+            ChocoCommand::process_args("choco git@2.45.1"),
+            "https://community.chocolatey.org/packages/git/2.45.1"
         );
         assert_eq!(
-            ChocoCommand::process_args("chocolatey vscode"),
-            "https://community.chocolatey.org/packages?q=vscode"
+            ChocoCommand::process_args("choco vscode versions"),
+            "https://community.chocolatey.org/packages/vscode#versionhistory"
         );
     }
 }
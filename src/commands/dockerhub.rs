@@ -8,9 +8,14 @@
 /// Docker Hub command handler
 /// Supports:
 /// - dockerhub/docker -> https://hub.docker.com
-/// - dockerhub [search terms] -> https://hub.docker.com/search?q=[search terms]
+/// - dockerhub [search terms] -> https://hub.docker.com/search?q=[search terms] (two or more words)
+/// - docker [image] -> the image page
+/// - docker [image]@[tag] -> the image's tags filtered to [tag]
+/// - docker [image] versions -> the image's tags
+/// - official images (no `/`) live under https://hub.docker.com/_/[image]
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::build_search_url;
+use crate::commands::package::{PackagePage, PackageRegistry};
+use crate::utils::url_encoding::{build_path_url, build_search_url};
 
 pub struct DockerhubCommand;
 
@@ -21,6 +26,8 @@ impl BunnylolCommand for DockerhubCommand {
         let query = Self::get_command_args(args);
         if query.is_empty() {
             "https://hub.docker.com".to_string()
+        } else if let Some(url) = Self::route_package(query) {
+            url
         } else {
             build_search_url("https://hub.docker.com/search", "q", query)
         }
@@ -32,6 +39,40 @@ impl BunnylolCommand for DockerhubCommand {
             "Navigate to Docker Hub or search for container images",
             "docker nginx",
         )
+        .with_examples(&["docker nginx@1.27", "docker grafana/grafana versions"])
+    }
+}
+
+impl DockerhubCommand {
+    fn image_url(name: &str) -> String {
+        // `library/` is the namespace of official images
+        match name.strip_prefix("library/").unwrap_or(name) {
+            official if !official.contains('/') => {
+                build_path_url("https://hub.docker.com/_", official)
+            }
+            repository => build_path_url("https://hub.docker.com/r", repository),
+        }
+    }
+}
+
+impl PackageRegistry for DockerhubCommand {
+    const PURL_TYPE: &'static str = "docker";
+
+    fn package_url(name: &str, version: Option<&str>) -> String {
+        match version {
+            Some(tag) => build_search_url(&format!("{}/tags", Self::image_url(name)), "name", tag),
+            None => Self::image_url(name),
+        }
+    }
+
+    fn page_url(name: &str, _version: Option<&str>, page: PackagePage) -> Option<String> {
+        match page {
+            PackagePage::Versions => Some(format!("{}/tags", Self::image_url(name))),
+            PackagePage::Docs
+            | PackagePage::Source
+            | PackagePage::Deps
+            | PackagePage::Changelog => None,
+        }
     }
 }
 
@@ -55,11 +96,27 @@ mod tests {
     fn test_dockerhub_command_search() {
         assert_eq!(
             DockerhubCommand::process_args("docker nginx"),
-            "https://hub.docker.com/search?q=nginx"
+            "https://hub.docker.com/_/nginx"
+        );
+        assert_eq!(
+            DockerhubCommand::process_args("dockerhub postgres alpine"),
+            "https://hub.docker.com/search?q=postgres%20alpine"
+        );
+    }
+
+    #[test]
+    fn test_dockerhub_package_routes() {
+        assert_eq!(
+            DockerhubCommand::process_args("docker nginx@1.27"),
+            "https://hub.docker.com/_/nginx/tags?name=1.27"
+        );
+        assert_eq!(
+            DockerhubCommand::process_args("docker grafana/grafana versions"),
+            "https://hub.docker.com/r/grafana/grafana/tags"
         );
         assert_eq!(
-            DockerhubCommand::process_args("dockerhub postgres"),
-            "https://hub.docker.com/search?q=postgres"
+            DockerhubCommand::process_args("docker library/redis@latest"),
+            "https://hub.docker.com/_/redis"
         );
     }
 }
@@ -9,70 +9,250 @@
 /// Supports: gh, gh @[user], gh [user/repo], gh token[s]/pat, gh settings, gh bills/billing,
 /// gh notifications/notifs, gh teams, gh orgs, gh ssh/gpg/keys, gh security/passwords/auth/mfa/2fa,
 /// gh emails, gh [search terms]
+/// Deep links: gh [org/repo]#[issue], gh [org/repo]![pr], gh [org/repo]@[sha or ref],
+/// gh [org/repo/path/to/file]:[line], gh [org/repo] pulls/issues/actions/releases,
+/// gh code: [query], gh issues: [query]. With `[commands.github] default_org`, the
+/// `org/` can be left out of deep links (gh [repo]#[issue]).
+/// In the CLI, inside a checkout of a repo on the same host: gh . (the repo), gh .#[issue],
+/// gh pr (PRs for the current branch), gh pulls/issues/actions/releases, gh branch, gh commit.
+/// The host is configurable with `[commands.github] host = "..."` (GitHub Enterprise).
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::config::{CommandConfig, get_global_config};
+use crate::repo_context::RepoContext;
 use crate::utils::url_encoding::{build_path_url, build_search_url};
 
 pub struct GitHubCommand;
 
-impl BunnylolCommand for GitHubCommand {
-    const BINDINGS: &'static [&'static str] = &["gh"];
+impl GitHubCommand {
+    /// Base URL used when `[commands.github]` doesn't set a `host`
+    pub const DEFAULT_BASE_URL: &'static str = "https://github.com";
+
+    /// Build the URL for `query` (the input after the command word) against
+    /// a `[commands.github]`-style settings table, e.g. a GitHub Enterprise
+    /// host. Every sub-route honors the configured host.
+    pub fn process_query(query: &str, settings: Option<&CommandConfig>) -> String {
+        let base = settings
+            .and_then(CommandConfig::base_url)
+            .unwrap_or_else(|| Self::DEFAULT_BASE_URL.to_string());
+        let settings_page = |page: &str| format!("{}/settings/{}", base, page);
 
-    fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://github.com".to_string()
+            base
+        } else if let Some(url) = Self::current_repo_link(&base, query) {
+            url
         } else if query == "settings" {
-            "https://github.com/settings/profile".to_string()
+            settings_page("profile")
         } else if query == "token" || query == "tokens" || query == "pat" {
-            "https://github.com/settings/personal-access-tokens".to_string()
+            settings_page("personal-access-tokens")
         } else if query == "bills" || query == "billing" {
-            "https://github.com/settings/billing".to_string()
+            settings_page("billing")
         } else if query == "notifications" || query == "notifs" {
-            "https://github.com/settings/notifications".to_string()
+            settings_page("notifications")
         } else if query == "teams" {
-            "https://github.com/settings/teams".to_string()
+            settings_page("teams")
         } else if query == "orgs" {
-            "https://github.com/settings/organizations".to_string()
+            settings_page("organizations")
         } else if query == "ssh" || query == "gpg" || query == "keys" {
-            "https://github.com/settings/keys".to_string()
+            settings_page("keys")
         } else if query == "security"
             || query == "passwords"
             || query == "auth"
             || query == "mfa"
             || query == "2fa"
         {
-            "https://github.com/settings/security".to_string()
+            settings_page("security")
         } else if query == "emails" {
-            "https://github.com/settings/emails".to_string()
+            settings_page("emails")
         } else if let Some(username) = query.strip_prefix('@') {
             if username.is_empty() {
-                "https://github.com".to_string()
+                base
             } else {
-                build_path_url("https://github.com", username)
+                build_path_url(&base, username)
             }
+        } else if let Some(url) = Self::typed_search(&base, query) {
+            url
+        } else if let Some(url) = Self::deep_link(
+            &base,
+            query,
+            settings.and_then(|s| s.default_org.as_deref()),
+        ) {
+            url
         } else if let Some((author, repo)) = query.split_once('/') {
             if !author.is_empty() && !repo.is_empty() {
-                build_path_url("https://github.com", query)
+                build_path_url(&base, query)
             } else {
-                format!(
-                    "{}&type=repositories",
-                    build_search_url("https://github.com/search", "q", query)
-                )
+                Self::construct_search_url(&base, query)
             }
         } else {
-            format!(
-                "{}&type=repositories",
-                build_search_url("https://github.com/search", "q", query)
-            )
+            Self::construct_search_url(&base, query)
+        }
+    }
+
+    /// Links into the repository the CLI was run from, when one of its
+    /// remotes is hosted at `base`. `.` stands for that repo in any deep
+    /// link (`gh .#12`, `gh . releases`).
+    fn current_repo_link(base: &str, query: &str) -> Option<String> {
+        let context = RepoContext::current()?;
+        let repo = context.repo_for(base)?;
+        let repo_url = build_path_url(base, repo);
+
+        if let Some(rest) = query.strip_prefix('.')
+            && (rest.is_empty() || rest.starts_with(['#', '!', '@', ' ']))
+        {
+            return Some(
+                Self::deep_link(base, &format!("{}{}", repo, rest), None).unwrap_or(repo_url),
+            );
+        }
+        match query {
+            "pr" => Some(match &context.branch {
+                Some(branch) => format!(
+                    "{}/pulls?q={}",
+                    repo_url,
+                    crate::utils::url_encoding::encode_url(&format!("is:pr head:{}", branch))
+                ),
+                None => format!("{}/pulls", repo_url),
+            }),
+            "pulls" | "prs" | "issues" | "actions" | "releases" => {
+                Self::deep_link(base, &format!("{} {}", repo, query), None)
+            }
+            "branch" => Some(match &context.branch {
+                Some(branch) => build_path_url(&repo_url, &format!("tree/{}", branch)),
+                None => repo_url,
+            }),
+            "commit" => Some(match &context.sha {
+                Some(sha) => format!("{}/commit/{}", repo_url, sha),
+                None => repo_url,
+            }),
+            _ => None,
+        }
+    }
+
+    /// `code: <query>` and `issues: <query>` pick the search type.
+    fn typed_search(base: &str, query: &str) -> Option<String> {
+        const SEARCH_TYPES: &[(&str, &str)] = &[("code:", "code"), ("issues:", "issues")];
+
+        SEARCH_TYPES.iter().find_map(|(prefix, search_type)| {
+            let terms = query.strip_prefix(prefix)?.trim();
+            Some(format!(
+                "{}&type={}",
+                build_search_url(&format!("{}/search", base), "q", terms),
+                search_type
+            ))
+        })
+    }
+
+    /// Repository deep links: `repo#123`, `repo!45`, `repo@ref`,
+    /// `repo/path:42` and `repo pulls|issues|actions|releases`, where `repo`
+    /// is `org/repo`, or a bare repo name when `default_org` is set.
+    fn deep_link(base: &str, query: &str, default_org: Option<&str>) -> Option<String> {
+        if let Some((spec, section)) = query.split_once(char::is_whitespace) {
+            let page = match section.trim() {
+                "pulls" | "prs" => "pulls",
+                "issues" => "issues",
+                "actions" => "actions",
+                "releases" => "releases",
+                _ => return None,
+            };
+            let repo = Self::repo_path(spec, default_org)?;
+            return Some(format!("{}/{}/{}", base, repo, page));
         }
+
+        if let Some((spec, number)) = query.split_once('#') {
+            let repo = Self::repo_path(spec, default_org)?;
+            return Self::is_number(number).then(|| format!("{}/{}/issues/{}", base, repo, number));
+        }
+        if let Some((spec, number)) = query.split_once('!') {
+            let repo = Self::repo_path(spec, default_org)?;
+            return Self::is_number(number).then(|| format!("{}/{}/pull/{}", base, repo, number));
+        }
+        if let Some((spec, git_ref)) = query.split_once('@') {
+            let repo = Self::repo_path(spec, default_org)?;
+            if git_ref.is_empty() {
+                return None;
+            }
+            let is_sha =
+                (7..=40).contains(&git_ref.len()) && git_ref.chars().all(|c| c.is_ascii_hexdigit());
+            let kind = if is_sha { "commit" } else { "tree" };
+            return Some(build_path_url(
+                base,
+                &format!("{}/{}/{}", repo, kind, git_ref),
+            ));
+        }
+        if let Some((path, lines)) = query.rsplit_once(':') {
+            let mut parts = path.splitn(3, '/');
+            let (org, name, file) = (parts.next()?, parts.next()?, parts.next()?);
+            let repo = Self::repo_path(&format!("{}/{}", org, name), None)?;
+            let anchor = match lines.split_once('-') {
+                Some((start, end)) if Self::is_number(start) && Self::is_number(end) => {
+                    format!("L{}-L{}", start, end)
+                }
+                None if Self::is_number(lines) => format!("L{}", lines),
+                _ => return None,
+            };
+            if file.is_empty() {
+                return None;
+            }
+            return Some(format!(
+                "{}#{}",
+                build_path_url(base, &format!("{}/blob/HEAD/{}", repo, file)),
+                anchor
+            ));
+        }
+        None
+    }
+
+    /// `org/repo` as a URL path, or `<default_org>/repo` for a bare repo name.
+    fn repo_path(spec: &str, default_org: Option<&str>) -> Option<String> {
+        let is_name = |s: &str| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        match spec.split_once('/') {
+            Some((org, repo)) if is_name(org) && is_name(repo) => Some(format!("{}/{}", org, repo)),
+            None if is_name(spec) => default_org.map(|org| format!("{}/{}", org, spec)),
+            _ => None,
+        }
+    }
+
+    fn is_number(s: &str) -> bool {
+        !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+    }
+
+    fn construct_search_url(base: &str, query: &str) -> String {
+        format!(
+            "{}&type=repositories",
+            build_search_url(&format!("{}/search", base), "q", query)
+        )
+    }
+}
+
+impl BunnylolCommand for GitHubCommand {
+    const BINDINGS: &'static [&'static str] = &["gh"];
+
+    fn process_args(args: &str) -> String {
+        let config = get_global_config();
+        let settings = config.as_ref().and_then(|c| c.command_settings("github"));
+        Self::process_query(Self::get_command_args(args), settings)
     }
 
     fn get_info() -> BunnylolCommandInfo {
         BunnylolCommandInfo::new(
             Self::BINDINGS,
-            "Navigate to GitHub profiles, repositories, or search GitHub",
+            "Navigate to GitHub profiles, repositories, issues, PRs, commits and files, or search GitHub",
             "gh facebook/react",
         )
+        .with_examples(&[
+            "gh facebook/react#123",
+            "gh facebook/react!45",
+            "gh facebook/react@v18.2.0",
+            "gh facebook/react/packages/react/index.js:10",
+            "gh facebook/react pulls",
+            "gh code: useEffect cleanup",
+            "gh issues: hydration mismatch",
+            "gh react#12  (with default_org = \"facebook\")",
+            "gh .  or  gh pr  (inside a checkout, CLI only)",
+        ])
     }
 }
 
@@ -273,4 +453,225 @@ mod tests {
             "https://github.com/settings/emails"
         );
     }
+
+    fn enterprise() -> CommandConfig {
+        CommandConfig {
+            command_type: None,
+            host: Some("github.corp.example".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_github_enterprise_host_for_every_route() {
+        let settings = Some(enterprise());
+        let cases = [
+            ("", "https://github.corp.example"),
+            ("settings", "https://github.corp.example/settings/profile"),
+            (
+                "tokens",
+                "https://github.corp.example/settings/personal-access-tokens",
+            ),
+            ("keys", "https://github.corp.example/settings/keys"),
+            ("@octocat", "https://github.corp.example/octocat"),
+            ("infra/deploy", "https://github.corp.example/infra/deploy"),
+            (
+                "rust async",
+                "https://github.corp.example/search?q=rust%20async&type=repositories",
+            ),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(
+                GitHubCommand::process_query(query, settings.as_ref()),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_github_without_settings_uses_github_com() {
+        assert_eq!(
+            GitHubCommand::process_query("facebook/react", None),
+            "https://github.com/facebook/react"
+        );
+    }
+
+    fn query(query: &str) -> String {
+        GitHubCommand::process_query(query, None)
+    }
+
+    #[test]
+    fn test_github_issue_and_pr_links() {
+        assert_eq!(
+            query("facebook/react#123"),
+            "https://github.com/facebook/react/issues/123"
+        );
+        assert_eq!(
+            query("facebook/react!45"),
+            "https://github.com/facebook/react/pull/45"
+        );
+        // Not a number: treated as a plain repo path
+        assert_eq!(
+            query("facebook/react#abc"),
+            "https://github.com/facebook/react%23abc"
+        );
+    }
+
+    #[test]
+    fn test_github_ref_links() {
+        assert_eq!(
+            query("facebook/react@a1b2c3d"),
+            "https://github.com/facebook/react/commit/a1b2c3d"
+        );
+        assert_eq!(
+            query("facebook/react@v18.2.0"),
+            "https://github.com/facebook/react/tree/v18.2.0"
+        );
+        assert_eq!(
+            query("facebook/react@release/18"),
+            "https://github.com/facebook/react/tree/release/18"
+        );
+    }
+
+    #[test]
+    fn test_github_file_line_links() {
+        assert_eq!(
+            query("facebook/react/packages/react/index.js:10"),
+            "https://github.com/facebook/react/blob/HEAD/packages/react/index.js#L10"
+        );
+        assert_eq!(
+            query("facebook/react/README.md:5-12"),
+            "https://github.com/facebook/react/blob/HEAD/README.md#L5-L12"
+        );
+        // Paths without a line anchor keep passing through unchanged
+        assert_eq!(
+            query("facebook/react/pull/42"),
+            "https://github.com/facebook/react/pull/42"
+        );
+    }
+
+    #[test]
+    fn test_github_repo_sections() {
+        for section in ["pulls", "issues", "actions", "releases"] {
+            assert_eq!(
+                query(&format!("facebook/react {}", section)),
+                format!("https://github.com/facebook/react/{}", section)
+            );
+        }
+        assert_eq!(
+            query("facebook/react prs"),
+            "https://github.com/facebook/react/pulls"
+        );
+    }
+
+    #[test]
+    fn test_github_typed_search() {
+        assert_eq!(
+            query("code: useEffect cleanup"),
+            "https://github.com/search?q=useEffect%20cleanup&type=code"
+        );
+        assert_eq!(
+            query("issues:hydration"),
+            "https://github.com/search?q=hydration&type=issues"
+        );
+    }
+
+    #[test]
+    fn test_github_default_org() {
+        let settings = CommandConfig {
+            default_org: Some("facebook".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            GitHubCommand::process_query("react#12", Some(&settings)),
+            "https://github.com/facebook/react/issues/12"
+        );
+        assert_eq!(
+            GitHubCommand::process_query("react releases", Some(&settings)),
+            "https://github.com/facebook/react/releases"
+        );
+        // An explicit org still wins
+        assert_eq!(
+            GitHubCommand::process_query("rust-lang/rust!1", Some(&settings)),
+            "https://github.com/rust-lang/rust/pull/1"
+        );
+        // Without default_org a bare repo is not a deep link
+        assert_eq!(
+            query("react#12"),
+            "https://github.com/search?q=react%2312&type=repositories"
+        );
+        // Plain words are still repository searches
+        assert_eq!(
+            GitHubCommand::process_query("react", Some(&settings)),
+            "https://github.com/search?q=react&type=repositories"
+        );
+    }
+
+    #[test]
+    fn test_github_deep_links_honor_host() {
+        let settings = Some(enterprise());
+        assert_eq!(
+            GitHubCommand::process_query("infra/deploy!7", settings.as_ref()),
+            "https://github.corp.example/infra/deploy/pull/7"
+        );
+        assert_eq!(
+            GitHubCommand::process_query("code: TODO", settings.as_ref()),
+            "https://github.corp.example/search?q=TODO&type=code"
+        );
+    }
+
+    fn in_checkout(remote_host: &str, branch: Option<&str>) {
+        RepoContext::install(Some(RepoContext {
+            remotes: vec![crate::repo_context::GitRemote {
+                name: "origin".to_string(),
+                host: remote_host.to_string(),
+                path: "facebook/react".to_string(),
+            }],
+            branch: branch.map(str::to_string),
+            sha: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+        }));
+    }
+
+    #[test]
+    fn test_github_current_repo_links() {
+        in_checkout("github.com", Some("fix/hooks"));
+        assert_eq!(query("."), "https://github.com/facebook/react");
+        assert_eq!(query(".#12"), "https://github.com/facebook/react/issues/12");
+        assert_eq!(
+            query(". releases"),
+            "https://github.com/facebook/react/releases"
+        );
+        assert_eq!(
+            query("pr"),
+            "https://github.com/facebook/react/pulls?q=is:pr%20head:fix/hooks"
+        );
+        assert_eq!(query("issues"), "https://github.com/facebook/react/issues");
+        assert_eq!(
+            query("branch"),
+            "https://github.com/facebook/react/tree/fix/hooks"
+        );
+        assert_eq!(
+            query("commit"),
+            "https://github.com/facebook/react/commit/0123456789abcdef0123456789abcdef01234567"
+        );
+        // Everything else is unchanged
+        assert_eq!(query("settings"), "https://github.com/settings/profile");
+        RepoContext::install(None);
+    }
+
+    #[test]
+    fn test_github_current_repo_requires_matching_host() {
+        in_checkout("gitlab.com", None);
+        assert_eq!(
+            query("issues"),
+            "https://github.com/search?q=issues&type=repositories"
+        );
+        RepoContext::install(None);
+
+        // Without a context (e.g. on the server) `.` and `pr` are searches
+        assert_eq!(
+            query("pr"),
+            "https://github.com/search?q=pr&type=repositories"
+        );
+    }
 }
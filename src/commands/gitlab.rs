@@ -10,38 +10,85 @@
 /// - gitlab/gl -> https://gitlab.com
 /// - gitlab [user/project] -> https://gitlab.com/[user/project]
 /// - gitlab [search terms] -> https://gitlab.com/search?search=[search terms]
+/// - host: set `[commands.gitlab] host = "..."` for a self-hosted GitLab
+/// - CLI only, inside a checkout of a project on the same host: gl . (the project),
+///   gl mr/mrs/issues/pipelines/releases, gl branch, gl commit
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use crate::config::{CommandConfig, get_global_config};
+use crate::repo_context::RepoContext;
+use crate::utils::url_encoding::{build_path_url, encode_url};
 
 pub struct GitlabCommand;
 
-impl BunnylolCommand for GitlabCommand {
-    const BINDINGS: &'static [&'static str] = &["gitlab", "gl"];
+impl GitlabCommand {
+    /// Base URL used when `[commands.gitlab]` doesn't set a `host`
+    pub const DEFAULT_BASE_URL: &'static str = "https://gitlab.com";
+
+    /// Build the URL for `query` (the input after the command word) against
+    /// a `[commands.gitlab]`-style settings table, e.g. a self-hosted GitLab.
+    pub fn process_query(query: &str, settings: Option<&CommandConfig>) -> String {
+        let base = settings
+            .and_then(CommandConfig::base_url)
+            .unwrap_or_else(|| Self::DEFAULT_BASE_URL.to_string());
 
-    fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://gitlab.com".to_string()
+            base
+        } else if let Some(url) = Self::current_project_link(&base, query) {
+            url
         } else if query.contains('/') {
             // Validate and encode project path (user/project format)
             let parts: Vec<&str> = query.split('/').collect();
             if parts.len() == 2 && !parts[0].is_empty() && !parts[1].is_empty() {
                 // Valid user/project format - encode each part
-                format!(
-                    "https://gitlab.com/{}/{}",
-                    encode_url(parts[0]),
-                    encode_url(parts[1])
-                )
+                format!("{}/{}/{}", base, encode_url(parts[0]), encode_url(parts[1]))
             } else {
                 // Invalid path format (e.g., foo//bar or foo/bar/baz), fall back to search
-                format!("https://gitlab.com/search?search={}", encode_url(query))
+                format!("{}/search?search={}", base, encode_url(query))
             }
         } else {
             // Otherwise, treat it as a search query
-            format!("https://gitlab.com/search?search={}", encode_url(query))
+            format!("{}/search?search={}", base, encode_url(query))
         }
     }
 
+    /// Links into the project the CLI was run from, when one of its remotes
+    /// is hosted at `base`.
+    fn current_project_link(base: &str, query: &str) -> Option<String> {
+        let context = RepoContext::current()?;
+        let project_url = build_path_url(base, context.repo_for(base)?);
+        let page = match query {
+            "." => return Some(project_url),
+            "mr" | "mrs" => "merge_requests",
+            "issues" => "issues",
+            "pipelines" => "pipelines",
+            "releases" => "releases",
+            "branch" => {
+                return Some(match &context.branch {
+                    Some(branch) => build_path_url(&project_url, &format!("-/tree/{}", branch)),
+                    None => project_url,
+                });
+            }
+            "commit" => {
+                return Some(match &context.sha {
+                    Some(sha) => format!("{}/-/commit/{}", project_url, sha),
+                    None => project_url,
+                });
+            }
+            _ => return None,
+        };
+        Some(format!("{}/-/{}", project_url, page))
+    }
+}
+
+impl BunnylolCommand for GitlabCommand {
+    const BINDINGS: &'static [&'static str] = &["gitlab", "gl"];
+
+    fn process_args(args: &str) -> String {
+        let config = get_global_config();
+        let settings = config.as_ref().and_then(|c| c.command_settings("gitlab"));
+        Self::process_query(Self::get_command_args(args), settings)
+    }
+
     fn get_info() -> BunnylolCommandInfo {
         BunnylolCommandInfo::new(
             Self::BINDINGS,
@@ -122,4 +169,56 @@ mod tests {
             "https://gitlab.com/user/my%20project"
         );
     }
+
+    #[test]
+    fn test_gitlab_self_hosted_host() {
+        let settings = CommandConfig {
+            command_type: None,
+            host: Some("https://code.corp.example/gitlab/".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            GitlabCommand::process_query("", Some(&settings)),
+            "https://code.corp.example/gitlab"
+        );
+        assert_eq!(
+            GitlabCommand::process_query("infra/deploy", Some(&settings)),
+            "https://code.corp.example/gitlab/infra/deploy"
+        );
+        assert_eq!(
+            GitlabCommand::process_query("rust async", Some(&settings)),
+            "https://code.corp.example/gitlab/search?search=rust%20async"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_current_project_links() {
+        RepoContext::install(Some(RepoContext {
+            remotes: vec![crate::repo_context::GitRemote {
+                name: "origin".to_string(),
+                host: "gitlab.com".to_string(),
+                path: "group/sub/project".to_string(),
+            }],
+            branch: Some("main".to_string()),
+            sha: None,
+        }));
+        let query = |q| GitlabCommand::process_query(q, None);
+        assert_eq!(query("."), "https://gitlab.com/group/sub/project");
+        assert_eq!(
+            query("issues"),
+            "https://gitlab.com/group/sub/project/-/issues"
+        );
+        assert_eq!(
+            query("mr"),
+            "https://gitlab.com/group/sub/project/-/merge_requests"
+        );
+        assert_eq!(
+            query("branch"),
+            "https://gitlab.com/group/sub/project/-/tree/main"
+        );
+        assert_eq!(query("commit"), "https://gitlab.com/group/sub/project");
+        RepoContext::install(None);
+
+        assert_eq!(query("issues"), "https://gitlab.com/search?search=issues");
+    }
 }
@@ -6,20 +6,39 @@
  */
 
 /// Gmail command handler
-/// Supports: mail (simple redirect to Gmail)
+/// Supports: mail -> https://mail.google.com, mail [search terms] -> a Gmail search,
+/// mail @[account] ... -> the same in that Google account (see `google_account`)
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::google_account::{account_url, resolve_account};
+use crate::config::get_global_config;
+use crate::utils::url_encoding::encode_url;
 
 pub struct GmailCommand;
 
 impl BunnylolCommand for GmailCommand {
     const BINDINGS: &'static [&'static str] = &["gmail", "mail"];
 
-    fn process_args(_args: &str) -> String {
-        "https://mail.google.com".to_string()
+    fn process_args(args: &str) -> String {
+        let config = get_global_config();
+        let (account, query) = resolve_account(Self::get_command_args(args), config.as_ref());
+        if query.is_empty() && account.is_none() {
+            return "https://mail.google.com".to_string();
+        }
+        let rest = if query.is_empty() {
+            String::new()
+        } else {
+            format!("#search/{}", encode_url(query))
+        };
+        account_url("https://mail.google.com/mail/", &rest, account.as_ref())
     }
 
     fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(Self::BINDINGS, "Navigate to Gmail", "mail")
+        BunnylolCommandInfo::new(
+            Self::BINDINGS,
+            "Navigate to Gmail or search your mail",
+            "mail",
+        )
+        .with_examples(&["gmail is:unread", "gmail @work is:unread"])
     }
 }
 
@@ -36,11 +55,26 @@ mod tests {
     }
 
     #[test]
-    fn test_gmail_command_with_args() {
-        // Gmail command ignores any additional arguments
+    fn test_gmail_command_search() {
         assert_eq!(
-            GmailCommand::process_args("mail some args"),
-            "https://mail.google.com"
+            GmailCommand::process_args("mail is:unread from:boss"),
+            "https://mail.google.com/mail/#search/is:unread%20from:boss"
+        );
+    }
+
+    #[test]
+    fn test_gmail_command_account() {
+        assert_eq!(
+            GmailCommand::process_args("gmail @1"),
+            "https://mail.google.com/mail/u/1/"
+        );
+        assert_eq!(
+            GmailCommand::process_args("gmail @1 is:unread"),
+            "https://mail.google.com/mail/u/1/#search/is:unread"
+        );
+        assert_eq!(
+            GmailCommand::process_args("gmail @me@corp.example is:unread"),
+            "https://mail.google.com/mail/?authuser=me@corp.example#search/is:unread"
         );
     }
 }
@@ -8,24 +8,114 @@
 /// Go documentation command handler
 /// Supports:
 /// - godocs -> https://go.dev/doc/
+/// - godocs [import path][@version] -> https://pkg.go.dev/[import path][@version]
+///
+/// `[commands.godocs] mirror = "..."` points at a Go module cache (`go env GOMODCACHE`)
+/// or `$GOROOT/src`; packages found there open as a local source directory.
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::config::{CommandConfig, get_global_config};
+use crate::docs_mirror;
+use crate::utils::url_encoding::build_path_url;
 
 pub struct GodocsCommand;
 
+impl GodocsCommand {
+    fn is_import_path(query: &str) -> bool {
+        query.chars().all(|c| {
+            c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '~' | '/' | '@' | '+')
+        }) && !query.starts_with(['.', '/', '@'])
+            && !query.split('/').any(|segment| segment == "..")
+    }
+
+    /// Build the URL for `query` (the input after the command word),
+    /// preferring the package's directory in a configured mirror.
+    pub fn process_query(query: &str, settings: Option<&CommandConfig>) -> String {
+        if query.is_empty() || !Self::is_import_path(query) {
+            // Always redirect to Go documentation
+            return "https://go.dev/doc/".to_string();
+        }
+        let root = settings.and_then(CommandConfig::mirror_root);
+        match root
+            .as_deref()
+            .and_then(|root| Self::mirror_dir(root, query))
+        {
+            Some(dir) => docs_mirror::mirror_url(root.as_deref().unwrap(), &dir, ""),
+            None => build_path_url("https://pkg.go.dev", query),
+        }
+    }
+
+    /// The directory (relative to `root`, ending in `/`) holding `path`:
+    /// `root/<path>` as in `$GOROOT/src`, or the newest
+    /// `<module>@<version>/<subpath>` in a module cache.
+    fn mirror_dir(root: &Path, path: &str) -> Option<String> {
+        let path = escape_module_path(path);
+        if root.join(&path).is_dir() {
+            return Some(format!("{}/", path));
+        }
+        if path.contains('@') {
+            return None;
+        }
+        // Try the longest module path first: a/b/c, then a/b with subpath c, ...
+        let splits = path.match_indices('/').map(|(i, _)| i).chain([path.len()]);
+        let mut splits: Vec<usize> = splits.collect();
+        splits.reverse();
+        splits.into_iter().find_map(|end| {
+            let (module, subpath) = path.split_at(end);
+            let (parent, name) = module.rsplit_once('/').unwrap_or(("", module));
+            let version = fs::read_dir(root.join(parent))
+                .ok()?
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .filter_map(|entry| Some(entry.strip_prefix(name)?.strip_prefix('@')?.to_string()))
+                .max_by(|a, b| compare_versions(a, b))?;
+            let dir = format!("{}@{}{}", module, version, subpath);
+            root.join(&dir).is_dir().then(|| format!("{}/", dir))
+        })
+    }
+}
+
+/// Module cache directories escape uppercase letters as `!` + lowercase.
+fn escape_module_path(path: &str) -> String {
+    path.chars()
+        .flat_map(|c| {
+            if c.is_ascii_uppercase() {
+                vec!['!', c.to_ascii_lowercase()]
+            } else {
+                vec![c]
+            }
+        })
+        .collect()
+}
+
+/// Order `v1.10.0` after `v1.9.2` by comparing numeric runs as numbers.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let key = |v: &str| -> Vec<u64> {
+        v.split(|c: char| !c.is_ascii_digit())
+            .filter_map(|n| n.parse().ok())
+            .collect()
+    };
+    key(a).cmp(&key(b)).then_with(|| a.cmp(b))
+}
+
 impl BunnylolCommand for GodocsCommand {
     const BINDINGS: &'static [&'static str] = &["godocs"];
 
-    fn process_args(_args: &str) -> String {
-        // Always redirect to Go documentation
-        "https://go.dev/doc/".to_string()
+    fn process_args(args: &str) -> String {
+        let config = get_global_config();
+        let settings = config.as_ref().and_then(|c| c.command_settings("godocs"));
+        Self::process_query(Self::get_command_args(args), settings)
     }
 
     fn get_info() -> BunnylolCommandInfo {
         BunnylolCommandInfo::new(
             Self::BINDINGS,
-            "Navigate to Go language documentation",
+            "Navigate to Go language documentation or a package's docs",
             "godocs",
         )
+        .with_examples(&["godocs net/http", "godocs github.com/gorilla/mux"])
     }
 }
 
@@ -37,8 +127,67 @@ mod tests {
     fn test_godocs_command() {
         assert_eq!(GodocsCommand::process_args("godocs"), "https://go.dev/doc/");
         assert_eq!(
-            GodocsCommand::process_args("godocs anything"),
+            GodocsCommand::process_args("godocs anything else"),
+            "https://go.dev/doc/"
+        );
+    }
+
+    #[test]
+    fn test_godocs_package() {
+        assert_eq!(
+            GodocsCommand::process_args("godocs net/http"),
+            "https://pkg.go.dev/net/http"
+        );
+        assert_eq!(
+            GodocsCommand::process_args("godocs github.com/gorilla/mux@v1.8.1"),
+            "https://pkg.go.dev/github.com/gorilla/mux@v1.8.1"
+        );
+        assert_eq!(
+            GodocsCommand::process_args("godocs ../etc"),
             "https://go.dev/doc/"
         );
     }
+
+    #[test]
+    fn test_godocs_module_cache_mirror() {
+        let dir = tempfile::tempdir().unwrap();
+        for module in [
+            "github.com/gorilla/mux@v1.8.1/middleware",
+            "github.com/gorilla/mux@v1.10.0/middleware",
+            "github.com/!burnt!sushi/toml@v1.3.2",
+            "net/http",
+        ] {
+            fs::create_dir_all(dir.path().join(module)).unwrap();
+        }
+        let settings = CommandConfig {
+            mirror: Some(dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let local = |dir_path: &str| format!("file://{}/{}", dir.path().display(), dir_path);
+
+        assert_eq!(
+            GodocsCommand::process_query("github.com/gorilla/mux", Some(&settings)),
+            local("github.com/gorilla/mux@v1.10.0/")
+        );
+        assert_eq!(
+            GodocsCommand::process_query("github.com/gorilla/mux/middleware", Some(&settings)),
+            local("github.com/gorilla/mux@v1.10.0/middleware/")
+        );
+        assert_eq!(
+            GodocsCommand::process_query("github.com/gorilla/mux@v1.8.1", Some(&settings)),
+            local("github.com/gorilla/mux@v1.8.1/")
+        );
+        assert_eq!(
+            GodocsCommand::process_query("github.com/BurntSushi/toml", Some(&settings)),
+            local("github.com/!burnt!sushi/toml@v1.3.2/")
+        );
+        assert_eq!(
+            GodocsCommand::process_query("net/http", Some(&settings)),
+            local("net/http/")
+        );
+        assert_eq!(
+            GodocsCommand::process_query("golang.org/x/net", Some(&settings)),
+            "https://pkg.go.dev/golang.org/x/net"
+        );
+    }
 }
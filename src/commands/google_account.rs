@@ -0,0 +1,133 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+/// Google account selection shared by `gmail`, `docs`, `gsheets`, `gslides`,
+/// `gchat` and `maps`.
+///
+/// The account comes from a leading `@name`, `@N` or `@email` in the query
+/// (`gmail @work is:unread`), else `[google] account`. An `@name` that isn't
+/// in `[google.accounts]` is left in the query. Index accounts use Google's
+/// `/u/N/` paths; email accounts use `authuser=<email>`.
+use crate::config::{BunnylolConfig, GoogleAccount, GoogleConfig};
+use crate::utils::url_encoding::encode_url;
+
+/// Split a leading account selector off `query`, falling back to the
+/// configured default account.
+pub fn resolve_account<'a>(
+    query: &'a str,
+    config: Option<&BunnylolConfig>,
+) -> (Option<GoogleAccount>, &'a str) {
+    let unconfigured = GoogleConfig::default();
+    let google = config.map_or(&unconfigured, |config| &config.google);
+    let (first, rest) = query.split_once(' ').unwrap_or((query, ""));
+    let selected = first
+        .strip_prefix('@')
+        .filter(|selector| !selector.is_empty())
+        .and_then(|selector| google.resolve(selector));
+    match selected {
+        Some(account) => (Some(account), rest.trim_start()),
+        None => (google.default_account(), query),
+    }
+}
+
+/// The `authuser` value for `account`.
+pub fn authuser(account: &GoogleAccount) -> String {
+    match account {
+        GoogleAccount::Index(index) => index.to_string(),
+        GoogleAccount::Email(email) => encode_url(email),
+    }
+}
+
+/// `<base>u/N/<rest>` for an index account, `<base><rest>` with
+/// `authuser=<email>` (ahead of any `#fragment`) for an email account, and
+/// `<base><rest>` without an account. `base` ends with `/`.
+pub fn account_url(base: &str, rest: &str, account: Option<&GoogleAccount>) -> String {
+    match account {
+        Some(GoogleAccount::Index(index)) => format!("{}u/{}/{}", base, index, rest),
+        Some(email @ GoogleAccount::Email(_)) => {
+            let (path, fragment) = match rest.find('#') {
+                Some(split) => rest.split_at(split),
+                None => (rest, ""),
+            };
+            let separator = if path.contains('?') { '&' } else { '?' };
+            format!(
+                "{}{}{}authuser={}{}",
+                base,
+                path,
+                separator,
+                authuser(email),
+                fragment
+            )
+        }
+        None => format!("{}{}", base, rest),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> BunnylolConfig {
+        toml::from_str(
+            r#"
+[google]
+account = "work"
+
+[google.accounts]
+work = "me@corp.example"
+personal = 0
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_account() {
+        let cfg = config();
+        let work = GoogleAccount::Email("me@corp.example".to_string());
+        assert_eq!(
+            resolve_account("@personal is:unread", Some(&cfg)),
+            (Some(GoogleAccount::Index(0)), "is:unread")
+        );
+        assert_eq!(
+            resolve_account("@2", Some(&cfg)),
+            (Some(GoogleAccount::Index(2)), "")
+        );
+        assert_eq!(
+            resolve_account("is:unread", Some(&cfg)),
+            (Some(work.clone()), "is:unread")
+        );
+        // Unknown names stay in the query
+        assert_eq!(
+            resolve_account("@someone hello", Some(&cfg)),
+            (Some(work), "@someone hello")
+        );
+        assert_eq!(
+            resolve_account("@1 x", None),
+            (Some(GoogleAccount::Index(1)), "x")
+        );
+        assert_eq!(resolve_account("x", None), (None, "x"));
+    }
+
+    #[test]
+    fn test_account_url_forms() {
+        let base = "https://mail.google.com/mail/";
+        assert_eq!(
+            account_url(base, "#inbox", Some(&GoogleAccount::Index(1))),
+            "https://mail.google.com/mail/u/1/#inbox"
+        );
+        assert_eq!(
+            account_url(
+                base,
+                "#inbox",
+                Some(&GoogleAccount::Email("me@corp.example".to_string()))
+            ),
+            "https://mail.google.com/mail/?authuser=me@corp.example#inbox"
+        );
+        assert_eq!(account_url(base, "", None), base);
+    }
+}
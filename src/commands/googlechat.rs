@@ -7,15 +7,20 @@
 
 /// Google Chat command handler
 /// Supports: gchat -> redirects to Google Chat
+/// Add @[account] to open it in another Google account (`gchat @work`)
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::google_account::{account_url, resolve_account};
+use crate::config::get_global_config;
 
 pub struct GoogleChatCommand;
 
 impl BunnylolCommand for GoogleChatCommand {
     const BINDINGS: &'static [&'static str] = &["gchat"];
 
-    fn process_args(_args: &str) -> String {
-        "https://chat.google.com/".to_string()
+    fn process_args(args: &str) -> String {
+        let config = get_global_config();
+        let (account, _) = resolve_account(Self::get_command_args(args), config.as_ref());
+        account_url("https://chat.google.com/", "", account.as_ref())
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -42,4 +47,12 @@ mod tests {
             "https://chat.google.com/"
         );
     }
+
+    #[test]
+    fn test_google_chat_account() {
+        assert_eq!(
+            GoogleChatCommand::process_args("gchat @1"),
+            "https://chat.google.com/u/1/"
+        );
+    }
 }
@@ -7,15 +7,21 @@
 
 /// Google Docs command handler
 /// Supports: docs, gdoc -> redirects to Google Docs
+/// Add @[account] to open it in another Google account (`docs @work`)
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::google_account::{account_url, resolve_account};
+use crate::config::{GoogleAccount, get_global_config};
 
 pub struct GoogleDocsCommand;
 
 impl BunnylolCommand for GoogleDocsCommand {
     const BINDINGS: &'static [&'static str] = &["docs", "gdoc"];
 
-    fn process_args(_args: &str) -> String {
-        "https://docs.google.com/document/u/0/".to_string()
+    fn process_args(args: &str) -> String {
+        let config = get_global_config();
+        let (account, _) = resolve_account(Self::get_command_args(args), config.as_ref());
+        let account = account.unwrap_or(GoogleAccount::Index(0));
+        account_url("https://docs.google.com/document/", "", Some(&account))
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -46,4 +52,16 @@ mod tests {
             "https://docs.google.com/document/u/0/"
         );
     }
+
+    #[test]
+    fn test_googledocs_account() {
+        assert_eq!(
+            GoogleDocsCommand::process_args("docs @2"),
+            "https://docs.google.com/document/u/2/"
+        );
+        assert_eq!(
+            GoogleDocsCommand::process_args("docs @me@corp.example"),
+            "https://docs.google.com/document/?authuser=me@corp.example"
+        );
+    }
 }
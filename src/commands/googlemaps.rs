@@ -5,23 +5,61 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use crate::commands::Locale;
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::google_account::{authuser, resolve_account};
+use crate::commands::providers::{self, ProviderCategory};
+use crate::config::{CommandConfig, get_global_config};
 use crate::utils::url_encoding::encode_url;
 
 pub struct GoogleMapsCommand;
 
-impl BunnylolCommand for GoogleMapsCommand {
-    const BINDINGS: &'static [&'static str] = &["gmaps", "maps"];
-
-    fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
+impl GoogleMapsCommand {
+    /// Build the URL for `query` (the input after the command word), with
+    /// the interface language (`hl`) of its inline `xx:` locale
+    /// (`de:Berlin`) or the configured one, and the Google account
+    /// (`authuser`) of a leading `@account` or the configured one. With
+    /// another `[providers] maps`, the query goes to that site instead.
+    pub fn process_query(query: &str, settings: Option<&CommandConfig>) -> String {
+        let config = get_global_config();
+        let provider = providers::preferred(ProviderCategory::Maps, config.as_ref());
+        if !provider.is_default() {
+            return provider.url(query);
+        }
+        let (account, query) = resolve_account(query, config.as_ref());
+        let (locale, query) = Locale::resolve(query, config.as_ref(), settings);
+        let mut params = Vec::new();
+        if let Some(locale) = locale {
+            params.push(format!("hl={}", encode_url(&locale.tag())));
+        }
+        if let Some(account) = account {
+            params.push(format!("authuser={}", authuser(&account)));
+        }
+        let params = if params.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", params.join("&"))
+        };
 
         if query.is_empty() {
-            return "https://www.google.com/maps".to_string();
+            return format!("https://www.google.com/maps{}", params);
         }
 
         let encoded_query = encode_url(query);
-        format!("https://www.google.com/maps/search/{}/", encoded_query)
+        format!(
+            "https://www.google.com/maps/search/{}/{}",
+            encoded_query, params
+        )
+    }
+}
+
+impl BunnylolCommand for GoogleMapsCommand {
+    const BINDINGS: &'static [&'static str] = &["gmaps", "maps"];
+
+    fn process_args(args: &str) -> String {
+        let config = get_global_config();
+        let settings = config.as_ref().and_then(|c| c.command_settings("maps"));
+        Self::process_query(Self::get_command_args(args), settings)
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -84,4 +122,32 @@ mod tests {
             "https://www.google.com/maps/search/coffee%20shop/"
         );
     }
+
+    #[test]
+    fn test_google_maps_language() {
+        assert_eq!(
+            GoogleMapsCommand::process_args("maps de:Berlin Hauptbahnhof"),
+            "https://www.google.com/maps/search/Berlin%20Hauptbahnhof/?hl=de"
+        );
+        let settings = CommandConfig {
+            locale: Some("ja-JP".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            GoogleMapsCommand::process_query("", Some(&settings)),
+            "https://www.google.com/maps?hl=ja-JP"
+        );
+    }
+
+    #[test]
+    fn test_google_maps_account() {
+        assert_eq!(
+            GoogleMapsCommand::process_args("maps @1 de:Berlin"),
+            "https://www.google.com/maps/search/Berlin/?hl=de&authuser=1"
+        );
+        assert_eq!(
+            GoogleMapsCommand::process_args("maps @me@corp.example"),
+            "https://www.google.com/maps?authuser=me@corp.example"
+        );
+    }
 }
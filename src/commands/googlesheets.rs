@@ -7,15 +7,21 @@
 
 /// Google Sheets command handler
 /// Supports: gsheets -> redirects to Google Sheets
+/// Add @[account] to open it in another Google account (`gsheets @work`)
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::google_account::{account_url, resolve_account};
+use crate::config::{GoogleAccount, get_global_config};
 
 pub struct GoogleSheetsCommand;
 
 impl BunnylolCommand for GoogleSheetsCommand {
     const BINDINGS: &'static [&'static str] = &["gsheets"];
 
-    fn process_args(_args: &str) -> String {
-        "https://docs.google.com/spreadsheets/u/0/".to_string()
+    fn process_args(args: &str) -> String {
+        let config = get_global_config();
+        let (account, _) = resolve_account(Self::get_command_args(args), config.as_ref());
+        let account = account.unwrap_or(GoogleAccount::Index(0));
+        account_url("https://docs.google.com/spreadsheets/", "", Some(&account))
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -42,4 +48,16 @@ mod tests {
             "https://docs.google.com/spreadsheets/u/0/"
         );
     }
+
+    #[test]
+    fn test_googlesheets_account() {
+        assert_eq!(
+            GoogleSheetsCommand::process_args("gsheets @2"),
+            "https://docs.google.com/spreadsheets/u/2/"
+        );
+        assert_eq!(
+            GoogleSheetsCommand::process_args("gsheets @me@corp.example"),
+            "https://docs.google.com/spreadsheets/?authuser=me@corp.example"
+        );
+    }
 }
@@ -7,15 +7,21 @@
 
 /// Google Slides command handler
 /// Supports: gslides -> redirects to Google Slides
+/// Add @[account] to open it in another Google account (`gslides @work`)
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::google_account::{account_url, resolve_account};
+use crate::config::{GoogleAccount, get_global_config};
 
 pub struct GoogleSlidesCommand;
 
 impl BunnylolCommand for GoogleSlidesCommand {
     const BINDINGS: &'static [&'static str] = &["gslides"];
 
-    fn process_args(_args: &str) -> String {
-        "https://docs.google.com/presentation/u/0/".to_string()
+    fn process_args(args: &str) -> String {
+        let config = get_global_config();
+        let (account, _) = resolve_account(Self::get_command_args(args), config.as_ref());
+        let account = account.unwrap_or(GoogleAccount::Index(0));
+        account_url("https://docs.google.com/presentation/", "", Some(&account))
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -42,4 +48,16 @@ mod tests {
             "https://docs.google.com/presentation/u/0/"
         );
     }
+
+    #[test]
+    fn test_googleslides_account() {
+        assert_eq!(
+            GoogleSlidesCommand::process_args("gslides @2"),
+            "https://docs.google.com/presentation/u/2/"
+        );
+        assert_eq!(
+            GoogleSlidesCommand::process_args("gslides @me@corp.example"),
+            "https://docs.google.com/presentation/?authuser=me@corp.example"
+        );
+    }
 }
@@ -8,12 +8,16 @@
 /// Go package command handler
 /// Supports:
 /// - go/golang/gopkg -> https://pkg.go.dev
-/// - go [search terms] -> https://pkg.go.dev/search?q=[search terms]
+/// - go [search terms] -> https://pkg.go.dev/search?q=[search terms] (two or more words)
+/// - go [module] -> https://pkg.go.dev/[module]
 /// - go playground -> https://go.dev/play/
 /// - go tour -> https://go.dev/tour/
 /// - go docs -> https://go.dev/doc/
+/// - go [module]@[version] -> https://pkg.go.dev/[module]@[version]
+/// - go [module][@version] docs|versions|deps -> pkg.go.dev tabs / deps.dev
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::build_search_url;
+use crate::commands::package::{PackagePage, PackageRegistry, deps_dev_url};
+use crate::utils::url_encoding::{build_path_url, build_search_url};
 
 pub struct GopkgCommand;
 
@@ -27,7 +31,8 @@ impl BunnylolCommand for GopkgCommand {
             "playground" | "play" => "https://go.dev/play/".to_string(),
             "tour" => "https://go.dev/tour/".to_string(),
             "docs" | "doc" => "https://go.dev/doc/".to_string(),
-            _ => build_search_url("https://pkg.go.dev/search", "q", query),
+            _ => Self::route_package(query)
+                .unwrap_or_else(|| build_search_url("https://pkg.go.dev/search", "q", query)),
         }
     }
 
@@ -37,6 +42,35 @@ impl BunnylolCommand for GopkgCommand {
             "Navigate to pkg.go.dev or search for Go packages",
             "go http",
         )
+        .with_examples(&[
+            "go github.com/gorilla/mux@v1.8.1",
+            "go golang.org/x/net versions",
+            "go net/http docs",
+        ])
+    }
+}
+
+impl PackageRegistry for GopkgCommand {
+    const PURL_TYPE: &'static str = "golang";
+
+    fn package_url(name: &str, version: Option<&str>) -> String {
+        let path = match version {
+            Some(version) => format!("{}@{}", name, version),
+            None => name.to_string(),
+        };
+        build_path_url("https://pkg.go.dev", &path)
+    }
+
+    fn page_url(name: &str, version: Option<&str>, page: PackagePage) -> Option<String> {
+        match page {
+            // pkg.go.dev's package page is the documentation
+            PackagePage::Docs => Some(Self::package_url(name, version)),
+            PackagePage::Versions => {
+                Some(format!("{}?tab=versions", Self::package_url(name, None)))
+            }
+            PackagePage::Deps => Some(deps_dev_url("go", name, version)),
+            PackagePage::Source | PackagePage::Changelog => None,
+        }
     }
 }
 
@@ -54,12 +88,12 @@ mod tests {
     #[test]
     fn test_gopkg_command_search() {
         assert_eq!(
-            GopkgCommand::process_args("go http"),
-            "https://pkg.go.dev/search?q=http"
+            GopkgCommand::process_args("go http router"),
+            "https://pkg.go.dev/search?q=http%20router"
         );
         assert_eq!(
-            GopkgCommand::process_args("gopkg gorilla/mux"),
-            "https://pkg.go.dev/search?q=gorilla/mux"
+            GopkgCommand::process_args("gopkg github.com/gorilla/mux"),
+            "https://pkg.go.dev/github.com/gorilla/mux"
         );
     }
 
@@ -88,4 +122,24 @@ mod tests {
         assert_eq!(GopkgCommand::process_args("go docs"), "https://go.dev/doc/");
         assert_eq!(GopkgCommand::process_args("go doc"), "https://go.dev/doc/");
     }
+
+    #[test]
+    fn test_gopkg_package_routes() {
+        assert_eq!(
+            GopkgCommand::process_args("go github.com/gorilla/mux@v1.8.1"),
+            "https://pkg.go.dev/github.com/gorilla/mux@v1.8.1"
+        );
+        assert_eq!(
+            GopkgCommand::process_args("go golang.org/x/net versions"),
+            "https://pkg.go.dev/golang.org/x/net?tab=versions"
+        );
+        assert_eq!(
+            GopkgCommand::process_args("go net/http docs"),
+            "https://pkg.go.dev/net/http"
+        );
+        assert_eq!(
+            GopkgCommand::process_args("go github.com/gorilla/mux deps"),
+            "https://deps.dev/go/github.com%2Fgorilla%2Fmux"
+        );
+    }
 }
@@ -0,0 +1,158 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+/// Locale handling shared by the region-aware commands (`amazon`, `wiki`,
+/// `maps`, `mdn`) and the search fallback.
+///
+/// The locale comes from, in order:
+/// - an inline `xx:` before the query (`wiki fr:Paris`, `mdn ja:Web/API/fetch`)
+/// - `[commands.<name>] locale = "de-DE"`
+/// - the global `locale = "de-DE"`
+///
+/// With none of these, commands keep their English/US defaults.
+use crate::config::{BunnylolConfig, CommandConfig};
+
+/// A `language[-REGION]` tag such as "de", "de-DE" or "pt-BR".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    /// Lowercase ISO 639 language code, e.g. "de".
+    pub language: String,
+    /// Uppercase ISO 3166 region code, e.g. "DE".
+    pub region: Option<String>,
+}
+
+impl Locale {
+    /// Parse "de", "de-DE" or "de_DE" (case-insensitive). `None` if `tag`
+    /// isn't a two or three letter language with an optional two letter
+    /// region.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let mut parts = tag.trim().split(['-', '_']);
+        let language = parts.next()?;
+        let region = parts.next();
+        let letters = |s: &str, lengths: &[usize]| {
+            lengths.contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphabetic())
+        };
+        if parts.next().is_some()
+            || !letters(language, &[2, 3])
+            || region.is_some_and(|region| !letters(region, &[2]))
+        {
+            return None;
+        }
+        Some(Locale {
+            language: language.to_ascii_lowercase(),
+            region: region.map(|region| region.to_ascii_uppercase()),
+        })
+    }
+
+    /// The tag in canonical form, e.g. "de-DE".
+    pub fn tag(&self) -> String {
+        match &self.region {
+            Some(region) => format!("{}-{}", self.language, region),
+            None => self.language.clone(),
+        }
+    }
+
+    /// The locale configured for a command: its `[commands.<name>] locale`,
+    /// else the global `locale`.
+    pub fn configured(
+        config: Option<&BunnylolConfig>,
+        settings: Option<&CommandConfig>,
+    ) -> Option<Self> {
+        settings
+            .and_then(|settings| settings.locale.as_deref())
+            .or_else(|| config.and_then(|config| config.locale.as_deref()))
+            .and_then(Self::parse)
+    }
+
+    /// Split an inline `xx:` or `xx-YY:` locale off the front of `query`
+    /// (`fr:Paris` -> fr, "Paris"). The rest must be non-empty.
+    pub fn split_inline(query: &str) -> (Option<Self>, &str) {
+        let Some((tag, rest)) = query.split_once(':') else {
+            return (None, query);
+        };
+        let rest = rest.trim_start();
+        match Self::parse(tag) {
+            Some(locale) if !rest.is_empty() && !tag.contains(char::is_whitespace) => {
+                (Some(locale), rest)
+            }
+            _ => (None, query),
+        }
+    }
+
+    /// The inline locale of `query` if it has one, else the configured one,
+    /// with the rest of the query.
+    pub fn resolve<'a>(
+        query: &'a str,
+        config: Option<&BunnylolConfig>,
+        settings: Option<&CommandConfig>,
+    ) -> (Option<Self>, &'a str) {
+        match Self::split_inline(query) {
+            (Some(locale), rest) => (Some(locale), rest),
+            (None, query) => (Self::configured(config, settings), query),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locale() {
+        let de = Locale::parse("de_de").unwrap();
+        assert_eq!(de.language, "de");
+        assert_eq!(de.region.as_deref(), Some("DE"));
+        assert_eq!(de.tag(), "de-DE");
+        assert_eq!(Locale::parse("ja").unwrap().tag(), "ja");
+        assert_eq!(Locale::parse("pt-BR").unwrap().tag(), "pt-BR");
+        assert_eq!(Locale::parse("german"), None);
+        assert_eq!(Locale::parse("de-DEU"), None);
+        assert_eq!(Locale::parse("zh-Hant-TW"), None);
+        assert_eq!(Locale::parse(""), None);
+    }
+
+    #[test]
+    fn test_split_inline_locale() {
+        let (locale, rest) = Locale::split_inline("fr:Paris");
+        assert_eq!(locale.unwrap().tag(), "fr");
+        assert_eq!(rest, "Paris");
+        let (locale, rest) = Locale::split_inline("pt-BR: São Paulo");
+        assert_eq!(locale.unwrap().tag(), "pt-BR");
+        assert_eq!(rest, "São Paulo");
+
+        for query in ["Paris", "fr:", "Star Trek: Voyager", "12:30", "http://x"] {
+            assert_eq!(Locale::split_inline(query), (None, query));
+        }
+    }
+
+    #[test]
+    fn test_configured_prefers_command_setting() {
+        let config = BunnylolConfig {
+            locale: Some("de-DE".to_string()),
+            ..Default::default()
+        };
+        let settings = CommandConfig {
+            locale: Some("ja-JP".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Locale::configured(Some(&config), Some(&settings))
+                .unwrap()
+                .tag(),
+            "ja-JP"
+        );
+        assert_eq!(
+            Locale::configured(Some(&config), None).unwrap().tag(),
+            "de-DE"
+        );
+        assert_eq!(Locale::configured(None, None), None);
+
+        let (locale, rest) = Locale::resolve("fr:Paris", Some(&config), Some(&settings));
+        assert_eq!(locale.unwrap().tag(), "fr");
+        assert_eq!(rest, "Paris");
+    }
+}
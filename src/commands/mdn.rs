@@ -8,22 +8,91 @@
 /// MDN command handler
 /// Supports:
 /// - mdn -> https://developer.mozilla.org
+/// - mdn [Section/Page] -> https://developer.mozilla.org/en-US/docs/[Section/Page] (mdn Web/API/fetch)
 /// - mdn [search terms] -> https://developer.mozilla.org/en-US/search?q=[search terms]
+/// - mdn [locale]:... -> the same in that language (mdn ja:Web/API/fetch)
+///
+/// Pages are in the configured `locale`'s language when MDN has it, else en-US.
+/// `[commands.mdn] mirror = "..."` opens pages from a saved copy of
+/// developer.mozilla.org (the directory holding `en-US/docs/...`) when it has them.
+use crate::commands::Locale;
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::build_search_url;
+use crate::config::{CommandConfig, get_global_config};
+use crate::docs_mirror;
+use crate::utils::url_encoding::{build_path_url, build_search_url};
 
 pub struct MdnCommand;
 
+impl MdnCommand {
+    const BASE_URL: &'static str = "https://developer.mozilla.org";
+
+    /// MDN's translated locales, besides the default en-US
+    const LOCALES: &'static [&'static str] = &[
+        "de", "es", "fr", "ja", "ko", "pt-BR", "ru", "zh-CN", "zh-TW",
+    ];
+
+    /// The MDN path locale for `locale`, if MDN is translated into it.
+    fn mdn_locale(locale: &Locale) -> Option<&'static str> {
+        let chinese = match locale.region.as_deref() {
+            Some("TW") | Some("HK") => "zh-TW",
+            _ => "zh-CN",
+        };
+        let wanted = match locale.language.as_str() {
+            "en" => return Some("en-US"),
+            "pt" => "pt-BR",
+            "zh" => chinese,
+            language => language,
+        };
+        Self::LOCALES.iter().copied().find(|known| *known == wanted)
+    }
+
+    /// Build the URL for `query` (the input after the command word),
+    /// preferring a configured mirror's copy of the page.
+    pub fn process_query(query: &str, settings: Option<&CommandConfig>) -> String {
+        // Only MDN's own locales count inline, so `mdn css:hover` still searches
+        let (inline, rest) = Locale::split_inline(query);
+        let (locale, query) = match inline.as_ref().and_then(Self::mdn_locale) {
+            Some(locale) => (Some(locale), rest),
+            None => (
+                Locale::configured(get_global_config().as_ref(), settings)
+                    .as_ref()
+                    .and_then(Self::mdn_locale),
+                query,
+            ),
+        };
+        let language = locale.unwrap_or("en-US");
+
+        let url = if query.is_empty() {
+            match locale {
+                Some(locale) => format!("{}/{}/", Self::BASE_URL, locale),
+                None => Self::BASE_URL.to_string(),
+            }
+        } else if !query.contains(char::is_whitespace)
+            && query.contains('/')
+            && !query.contains("://")
+        {
+            build_path_url(
+                &format!("{}/{}/docs", Self::BASE_URL, language),
+                query.trim_matches('/'),
+            )
+        } else {
+            build_search_url(
+                &format!("{}/{}/search", Self::BASE_URL, language),
+                "q",
+                query,
+            )
+        };
+        docs_mirror::local_url(settings, Self::BASE_URL, &url).unwrap_or(url)
+    }
+}
+
 impl BunnylolCommand for MdnCommand {
     const BINDINGS: &'static [&'static str] = &["mdn"];
 
     fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
-        if query.is_empty() {
-            "https://developer.mozilla.org".to_string()
-        } else {
-            build_search_url("https://developer.mozilla.org/en-US/search", "q", query)
-        }
+        let config = get_global_config();
+        let settings = config.as_ref().and_then(|c| c.command_settings("mdn"));
+        Self::process_query(Self::get_command_args(args), settings)
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -32,6 +101,7 @@ impl BunnylolCommand for MdnCommand {
             "Navigate to MDN Web Docs or search for web development resources",
             "mdn flexbox",
         )
+        .with_examples(&["mdn Web/API/fetch", "mdn ja:Web/API/fetch"])
     }
 }
 
@@ -58,4 +128,76 @@ mod tests {
             "https://developer.mozilla.org/en-US/search?q=array%20methods"
         );
     }
+
+    #[test]
+    fn test_mdn_command_docs_path() {
+        assert_eq!(
+            MdnCommand::process_args("mdn Web/API/fetch"),
+            "https://developer.mozilla.org/en-US/docs/Web/API/fetch"
+        );
+        assert_eq!(
+            MdnCommand::process_args("mdn Glossary/CORS/"),
+            "https://developer.mozilla.org/en-US/docs/Glossary/CORS"
+        );
+    }
+
+    #[test]
+    fn test_mdn_mirror() {
+        let dir = tempfile::tempdir().unwrap();
+        let page = dir.path().join("en-US/docs/Web/API/fetch");
+        std::fs::create_dir_all(&page).unwrap();
+        std::fs::write(page.join("index.html"), "").unwrap();
+        let settings = CommandConfig {
+            mirror: Some(dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            MdnCommand::process_query("Web/API/fetch", Some(&settings)),
+            format!(
+                "file://{}/en-US/docs/Web/API/fetch/index.html",
+                dir.path().display()
+            )
+        );
+        // Pages the mirror doesn't have, and searches, stay online
+        assert_eq!(
+            MdnCommand::process_query("Web/CSS/flex", Some(&settings)),
+            "https://developer.mozilla.org/en-US/docs/Web/CSS/flex"
+        );
+        assert_eq!(
+            MdnCommand::process_query("flexbox", Some(&settings)),
+            "https://developer.mozilla.org/en-US/search?q=flexbox"
+        );
+    }
+
+    #[test]
+    fn test_mdn_locale() {
+        assert_eq!(
+            MdnCommand::process_args("mdn ja:Web/API/fetch"),
+            "https://developer.mozilla.org/ja/docs/Web/API/fetch"
+        );
+        assert_eq!(
+            MdnCommand::process_args("mdn css:hover"),
+            "https://developer.mozilla.org/en-US/search?q=css:hover"
+        );
+        let settings = CommandConfig {
+            locale: Some("pt-PT".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            MdnCommand::process_query("flexbox", Some(&settings)),
+            "https://developer.mozilla.org/pt-BR/search?q=flexbox"
+        );
+        assert_eq!(
+            MdnCommand::process_query("", Some(&settings)),
+            "https://developer.mozilla.org/pt-BR/"
+        );
+        let settings = CommandConfig {
+            locale: Some("fi".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            MdnCommand::process_query("flexbox", Some(&settings)),
+            "https://developer.mozilla.org/en-US/search?q=flexbox"
+        );
+    }
 }
@@ -25,6 +25,7 @@ pub mod gitlab;
 pub mod gmail;
 pub mod godocs;
 pub mod google;
+pub mod google_account;
 pub mod googlechat;
 pub mod googledocs;
 pub mod googlemaps;
@@ -34,15 +35,19 @@ pub mod gopkg;
 pub mod hack;
 pub mod instagram;
 pub mod linkedin;
+pub mod locale;
 pub mod mdn;
 pub mod meta;
 pub mod node;
 pub mod npm;
 pub mod nuget;
 pub mod onepassword;
+pub mod package;
 pub mod packagist;
+pub mod pkg;
 pub mod protondrive;
 pub mod protonmail;
+pub mod providers;
 pub mod pypi;
 pub mod python;
 pub mod reddit;
@@ -51,6 +56,7 @@ pub mod rubygems;
 pub mod rust;
 pub mod schwab;
 pub mod search;
+pub mod social;
 pub mod soundcloud;
 pub mod stackoverflow;
 pub mod stock;
@@ -62,7 +68,7 @@ pub mod wikipedia;
 pub mod youtube;
 
 // Re-export the command structs for convenience
-pub use amazon::AmazonCommand;
+pub use amazon::{AmazonCommand, AmazonStorefrontPrefix};
 pub use bindings::BindingsCommand;
 pub use brew::BrewCommand;
 pub use cargo::CargoCommand;
@@ -86,6 +92,7 @@ pub use gopkg::GopkgCommand;
 pub use hack::HackCommand;
 pub use instagram::InstagramCommand;
 pub use linkedin::LinkedInCommand;
+pub use locale::Locale;
 pub use mdn::MdnCommand;
 pub use meta::MetaCommand;
 pub use node::NodeCommand;
@@ -93,19 +100,22 @@ pub use npm::NpmCommand;
 pub use nuget::NugetCommand;
 pub use onepassword::OnePasswordCommand;
 pub use packagist::PackagistCommand;
+pub use pkg::{PkgCommand, PurlPrefix};
 pub use protondrive::ProtonDriveCommand;
 pub use protonmail::ProtonMailCommand;
+pub use providers::{Provider, ProviderCategory};
 pub use pypi::PypiCommand;
-pub use python::PythonCommand;
-pub use reddit::RedditCommand;
+pub use python::{PythonCommand, PythonVersionPrefix};
+pub use reddit::{RedditCommand, RedditUserPrefix, SubredditPrefix};
 pub use rei::REICommand;
 pub use rubygems::RubygemsCommand;
 pub use rust::RustCommand;
 pub use schwab::SchwabCommand;
-pub use search::search_url;
+pub use search::{fallback_search_url, localized_search_url, search_url};
+pub use social::SocialHandlePrefix;
 pub use soundcloud::SoundCloudCommand;
 pub use stackoverflow::StackOverflowCommand;
-pub use stock::StockCommand;
+pub use stock::{StockCommand, StockTickerPrefix};
 pub use threads::ThreadsCommand;
 pub use twitter::TwitterCommand;
 pub use wayback::WaybackCommand;
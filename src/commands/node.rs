@@ -9,7 +9,15 @@
 /// Supports:
 /// - node/nodejs -> https://nodejs.org/api/
 /// - node [module] -> https://nodejs.org/api/[module].html
+/// - node [version] [module] -> the same for a release line (node 18 fs ->
+///   https://nodejs.org/docs/latest-v18.x/api/fs.html) or exact release (node 18.19.0 fs)
+///
+/// The default version is configurable with `[commands.node] version = "18"`, and
+/// `[commands.node] mirror = "..."` opens a local copy of the default version's
+/// `api/` directory.
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::config::{CommandConfig, get_global_config};
+use crate::docs_mirror;
 
 pub struct NodeCommand;
 
@@ -24,22 +32,65 @@ impl NodeCommand {
             && !name.starts_with('.')
             && !name.starts_with('-')
     }
+
+    /// The docs directory for a version: "18", "v18" and "18.x" name the
+    /// latest release of a line ("latest-v18.x"), "18.19.0" an exact
+    /// release ("v18.19.0"). `None` if `version` isn't one.
+    pub fn parse_version(version: &str) -> Option<String> {
+        let version = version.strip_prefix('v').unwrap_or(version);
+        let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+        let parts: Vec<&str> = version.split('.').collect();
+        match parts.as_slice() {
+            [major] | [major, "x"] if is_number(major) => Some(format!("latest-v{}.x", major)),
+            [major, minor, patch] if parts.iter().all(|p| is_number(p)) => {
+                Some(format!("v{}.{}.{}", major, minor, patch))
+            }
+            _ => None,
+        }
+    }
+
+    /// Build the URL for `query` (the input after the command word). A
+    /// leading version word overrides the configured default version.
+    pub fn process_query(query: &str, settings: Option<&CommandConfig>) -> String {
+        let (first, rest) = query.split_once(' ').unwrap_or((query, ""));
+        let (version, query, pinned) = match Self::parse_version(first) {
+            Some(version) => (Some(version), rest.trim_start(), true),
+            None => (
+                settings
+                    .and_then(|s| s.version.as_deref())
+                    .and_then(Self::parse_version),
+                query,
+                false,
+            ),
+        };
+        let base = match version {
+            Some(version) => format!("https://nodejs.org/docs/{}/api/", version),
+            None => "https://nodejs.org/api/".to_string(),
+        };
+        let url = if !query.is_empty() && !query.contains(' ') && Self::is_valid_module_name(query)
+        {
+            // Single word queries with valid module names are treated as module names
+            format!("{}{}.html", base, query)
+        } else {
+            // Empty, multi-word queries or invalid module names just go to base docs
+            base.clone()
+        };
+        // The mirror holds the default version's docs only
+        if pinned {
+            url
+        } else {
+            docs_mirror::local_url(settings, &base, &url).unwrap_or(url)
+        }
+    }
 }
 
 impl BunnylolCommand for NodeCommand {
     const BINDINGS: &'static [&'static str] = &["node", "nodejs"];
 
     fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
-        if query.is_empty() {
-            "https://nodejs.org/api/".to_string()
-        } else if !query.contains(' ') && Self::is_valid_module_name(query) {
-            // Single word queries with valid module names are treated as module names
-            format!("https://nodejs.org/api/{}.html", query)
-        } else {
-            // Multi-word queries or invalid module names just go to base docs
-            "https://nodejs.org/api/".to_string()
-        }
+        let config = get_global_config();
+        let settings = config.as_ref().and_then(|c| c.command_settings("node"));
+        Self::process_query(Self::get_command_args(args), settings)
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -48,6 +99,7 @@ impl BunnylolCommand for NodeCommand {
             "Navigate to Node.js API documentation or specific module docs",
             "node fs",
         )
+        .with_examples(&["node 18 fs", "node 20.11.0 http"])
     }
 }
 
@@ -130,4 +182,40 @@ mod tests {
             "https://nodejs.org/api/fs.promises.html"
         );
     }
+
+    #[test]
+    fn test_node_command_version() {
+        assert_eq!(
+            NodeCommand::process_args("node 18 fs"),
+            "https://nodejs.org/docs/latest-v18.x/api/fs.html"
+        );
+        assert_eq!(
+            NodeCommand::process_args("node v20.x"),
+            "https://nodejs.org/docs/latest-v20.x/api/"
+        );
+        assert_eq!(
+            NodeCommand::process_args("node 18.19.0 http"),
+            "https://nodejs.org/docs/v18.19.0/api/http.html"
+        );
+        assert_eq!(
+            NodeCommand::process_args("node 18 ../etc"),
+            "https://nodejs.org/docs/latest-v18.x/api/"
+        );
+    }
+
+    #[test]
+    fn test_node_configured_version() {
+        let settings = CommandConfig {
+            version: Some("18".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            NodeCommand::process_query("fs", Some(&settings)),
+            "https://nodejs.org/docs/latest-v18.x/api/fs.html"
+        );
+        assert_eq!(
+            NodeCommand::process_query("22 fs", Some(&settings)),
+            "https://nodejs.org/docs/latest-v22.x/api/fs.html"
+        );
+    }
 }
@@ -8,9 +8,13 @@
 /// NPM command handler
 /// Supports:
 /// - npm/npmjs -> https://www.npmjs.com
-/// - npm [search terms] -> https://www.npmjs.com/search?q=[search terms]
+/// - npm [search terms] -> https://www.npmjs.com/search?q=[search terms] (two or more words)
+/// - npm [package] -> https://www.npmjs.com/package/[package]
+/// - npm [package]@[version] -> https://www.npmjs.com/package/[package]/v/[version]
+/// - npm [package][@version] versions|source|deps -> the package page tabs
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::build_search_url;
+use crate::commands::package::{PackagePage, PackageRegistry};
+use crate::utils::url_encoding::{build_path_url, build_search_url};
 
 pub struct NpmCommand;
 
@@ -21,6 +25,8 @@ impl BunnylolCommand for NpmCommand {
         let query = Self::get_command_args(args);
         if query.is_empty() {
             "https://www.npmjs.com".to_string()
+        } else if let Some(url) = Self::route_package(query) {
+            url
         } else {
             build_search_url("https://www.npmjs.com/search", "q", query)
         }
@@ -32,6 +38,37 @@ impl BunnylolCommand for NpmCommand {
             "Navigate to npmjs.com or search for npm packages",
             "npm react",
         )
+        .with_examples(&[
+            "npm react@18.2.0",
+            "npm @types/node versions",
+            "npm express deps",
+        ])
+    }
+}
+
+impl PackageRegistry for NpmCommand {
+    const PURL_TYPE: &'static str = "npm";
+
+    fn package_url(name: &str, version: Option<&str>) -> String {
+        let path = match version {
+            Some(version) => format!("{}/v/{}", name, version),
+            None => name.to_string(),
+        };
+        build_path_url("https://www.npmjs.com/package", &path)
+    }
+
+    fn page_url(name: &str, version: Option<&str>, page: PackagePage) -> Option<String> {
+        let tab = match page {
+            PackagePage::Versions => "versions",
+            PackagePage::Source => "code",
+            PackagePage::Deps => "dependencies",
+            PackagePage::Docs | PackagePage::Changelog => return None,
+        };
+        Some(format!(
+            "{}?activeTab={}",
+            Self::package_url(name, version),
+            tab
+        ))
     }
 }
 
@@ -49,11 +86,35 @@ mod tests {
     fn test_npm_command_search() {
         assert_eq!(
             NpmCommand::process_args("npm react"),
-            "https://www.npmjs.com/search?q=react"
+            "https://www.npmjs.com/package/react"
         );
         assert_eq!(
             NpmCommand::process_args("npmjs express middleware"),
             "https://www.npmjs.com/search?q=express%20middleware"
         );
     }
+
+    #[test]
+    fn test_npm_package_routes() {
+        assert_eq!(
+            NpmCommand::process_args("npm react@18.2.0"),
+            "https://www.npmjs.com/package/react/v/18.2.0"
+        );
+        assert_eq!(
+            NpmCommand::process_args("npm @types/node versions"),
+            "https://www.npmjs.com/package/@types/node?activeTab=versions"
+        );
+        assert_eq!(
+            NpmCommand::process_args("npm express deps"),
+            "https://www.npmjs.com/package/express?activeTab=dependencies"
+        );
+        assert_eq!(
+            NpmCommand::process_args("npm react source"),
+            "https://www.npmjs.com/package/react?activeTab=code"
+        );
+        assert_eq!(
+            NpmCommand::process_args("npm react docs"),
+            "https://www.npmjs.com/package/react"
+        );
+    }
 }
@@ -8,9 +8,13 @@
 /// NuGet command handler
 /// Supports:
 /// - nuget -> https://www.nuget.org
-/// - nuget [search terms] -> https://www.nuget.org/packages?q=[search terms]
+/// - nuget [search terms] -> https://www.nuget.org/packages?q=[search terms] (two or more words)
+/// - nuget [id] -> https://www.nuget.org/packages/[id]
+/// - nuget [id]@[version] -> https://www.nuget.org/packages/[id]/[version]
+/// - nuget [id][@version] versions|deps -> the package page tabs / deps.dev
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::build_search_url;
+use crate::commands::package::{PackagePage, PackageRegistry, deps_dev_url};
+use crate::utils::url_encoding::{build_path_url, build_search_url};
 
 pub struct NugetCommand;
 
@@ -21,6 +25,8 @@ impl BunnylolCommand for NugetCommand {
         let query = Self::get_command_args(args);
         if query.is_empty() {
             "https://www.nuget.org".to_string()
+        } else if let Some(url) = Self::route_package(query) {
+            url
         } else {
             build_search_url("https://www.nuget.org/packages", "q", query)
         }
@@ -32,6 +38,30 @@ impl BunnylolCommand for NugetCommand {
             "Navigate to nuget.org or search for .NET packages",
             "nuget newtonsoft",
         )
+        .with_examples(&["nuget Newtonsoft.Json@13.0.3", "nuget Serilog versions"])
+    }
+}
+
+impl PackageRegistry for NugetCommand {
+    const PURL_TYPE: &'static str = "nuget";
+
+    fn package_url(name: &str, version: Option<&str>) -> String {
+        let path = match version {
+            Some(version) => format!("{}/{}", name, version),
+            None => name.to_string(),
+        };
+        build_path_url("https://www.nuget.org/packages", &path)
+    }
+
+    fn page_url(name: &str, version: Option<&str>, page: PackagePage) -> Option<String> {
+        match page {
+            PackagePage::Versions => Some(format!(
+                "{}#versions-body-tab",
+                Self::package_url(name, None)
+            )),
+            PackagePage::Deps => Some(deps_dev_url("nuget", name, version)),
+            PackagePage::Docs | PackagePage::Source | PackagePage::Changelog => None,
+        }
     }
 }
 
@@ -48,11 +78,27 @@ mod tests {
     fn test_nuget_command_search() {
         assert_eq!(
             NugetCommand::process_args("nuget newtonsoft"),
-            "https://www.nuget.org/packages?q=newtonsoft"
+            "https://www.nuget.org/packages/newtonsoft"
+        );
+        assert_eq!(
+            NugetCommand::process_args("nuget entity framework"),
+            "https://www.nuget.org/packages?q=entity%20framework"
+        );
+    }
+
+    #[test]
+    fn test_nuget_package_routes() {
+        assert_eq!(
+            NugetCommand::process_args("nuget Newtonsoft.Json@13.0.3"),
+            "https://www.nuget.org/packages/Newtonsoft.Json/13.0.3"
+        );
+        assert_eq!(
+            NugetCommand::process_args("nuget Serilog versions"),
+            "https://www.nuget.org/packages/Serilog#versions-body-tab"
         );
         assert_eq!(
-            NugetCommand::process_args("nuget entityframework"),
-            "https://www.nuget.org/packages?q=entityframework"
+            NugetCommand::process_args("nuget Serilog deps"),
+            "https://deps.dev/nuget/Serilog"
         );
     }
 }
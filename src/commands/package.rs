@@ -0,0 +1,216 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+/// Package-level routing shared by the package registry commands
+/// (`cargo`, `npm`, `pypi`, `gem`, `nuget`, `packagist`, `go`, `docker`,
+/// `brew`, `choco`) and the `pkg` command.
+///
+/// A registry command treats a single word, or a word followed by a page
+/// keyword, as a package:
+/// - [name] -> the package page
+/// - [name]@[version] -> the package page for that version (`@latest` for the current one)
+/// - [name][@version] docs|versions|source|deps|changelog -> that page
+///
+/// Anything longer is a registry search. Pages a registry doesn't have open
+/// the package page.
+use crate::utils::url_encoding::encode_url;
+
+/// A package page reachable with a trailing keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackagePage {
+    Docs,
+    Versions,
+    Source,
+    Deps,
+    Changelog,
+}
+
+impl PackagePage {
+    pub const KEYWORDS: &'static [&'static str] =
+        &["docs", "versions", "source", "deps", "changelog"];
+
+    fn from_keyword(word: &str) -> Option<Self> {
+        match word {
+            "docs" => Some(Self::Docs),
+            "versions" => Some(Self::Versions),
+            "source" => Some(Self::Source),
+            "deps" => Some(Self::Deps),
+            "changelog" => Some(Self::Changelog),
+            _ => None,
+        }
+    }
+}
+
+/// `name[@version] [page]`, parsed from a registry command's input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackageQuery<'a> {
+    pub name: &'a str,
+    /// `None` for the latest version (no `@`, or `@latest`).
+    pub version: Option<&'a str>,
+    pub page: Option<PackagePage>,
+}
+
+impl<'a> PackageQuery<'a> {
+    /// Parse `query` as a package reference, or `None` if it is a plain
+    /// search (more than one word, unless the second is a page keyword).
+    pub fn parse(query: &'a str) -> Option<Self> {
+        let mut words = query.split_whitespace();
+        let spec = words.next()?;
+        let page = match words.next() {
+            Some(word) => Some(PackagePage::from_keyword(word)?),
+            None => None,
+        };
+        if words.next().is_some() {
+            return None;
+        }
+
+        let (name, version) = split_version(spec);
+        // A lone page keyword (`cargo docs`) is more likely a search
+        if name.is_empty() || (page.is_none() && PackagePage::from_keyword(name).is_some()) {
+            return None;
+        }
+        Some(PackageQuery {
+            name,
+            version: version.filter(|v| *v != "latest"),
+            page,
+        })
+    }
+}
+
+/// Split `name@version` at the last `@` that isn't the first character, so
+/// scoped names like `@types/node@20` keep their leading `@`. The version
+/// is `Some` (possibly "latest") only when an `@` was given.
+fn split_version(spec: &str) -> (&str, Option<&str>) {
+    match spec.rfind('@') {
+        Some(index) if index > 0 && index + 1 < spec.len() => {
+            (&spec[..index], Some(&spec[index + 1..]))
+        }
+        _ => (spec, None),
+    }
+}
+
+/// Package-level URLs for one registry.
+pub trait PackageRegistry {
+    /// The Package URL (purl) type routed to this registry, e.g. "npm".
+    const PURL_TYPE: &'static str;
+
+    /// The package's page, at `version` if given.
+    fn package_url(name: &str, version: Option<&str>) -> String;
+
+    /// The URL of `page`, or `None` if the registry has no such page.
+    fn page_url(name: &str, version: Option<&str>, page: PackagePage) -> Option<String>;
+
+    /// Resolve a parsed query, falling back to the package page.
+    fn package_query_url(query: &PackageQuery) -> String {
+        query
+            .page
+            .and_then(|page| Self::page_url(query.name, query.version, page))
+            .unwrap_or_else(|| Self::package_url(query.name, query.version))
+    }
+
+    /// The URL for `query` if it names a package (see the module docs).
+    fn route_package(query: &str) -> Option<String> {
+        PackageQuery::parse(query).map(|query| Self::package_query_url(&query))
+    }
+}
+
+/// deps.dev dependency graph for `system` ("cargo", "npm", "pypi", "go",
+/// "nuget"). Package names are a single path segment there.
+pub fn deps_dev_url(system: &str, name: &str, version: Option<&str>) -> String {
+    let name = encode_url(name).replace('/', "%2F");
+    match version {
+        Some(version) => format!(
+            "https://deps.dev/{}/{}/{}",
+            system,
+            name,
+            encode_url(version)
+        ),
+        None => format!("https://deps.dev/{}/{}", system, name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_package_query() {
+        assert_eq!(
+            PackageQuery::parse("serde@1.0.200"),
+            Some(PackageQuery {
+                name: "serde",
+                version: Some("1.0.200"),
+                page: None
+            })
+        );
+        assert_eq!(
+            PackageQuery::parse("@types/node@20 deps"),
+            Some(PackageQuery {
+                name: "@types/node",
+                version: Some("20"),
+                page: Some(PackagePage::Deps)
+            })
+        );
+        assert_eq!(
+            PackageQuery::parse("react@latest"),
+            Some(PackageQuery {
+                name: "react",
+                version: None,
+                page: None
+            })
+        );
+        assert_eq!(
+            PackageQuery::parse("tokio docs"),
+            Some(PackageQuery {
+                name: "tokio",
+                version: None,
+                page: Some(PackagePage::Docs)
+            })
+        );
+    }
+
+    #[test]
+    fn test_bare_name_is_a_package() {
+        assert_eq!(
+            PackageQuery::parse("serde"),
+            Some(PackageQuery {
+                name: "serde",
+                version: None,
+                page: None
+            })
+        );
+        assert_eq!(
+            PackageQuery::parse("@types/node").map(|query| query.name),
+            Some("@types/node")
+        );
+    }
+
+    #[test]
+    fn test_plain_searches_are_not_packages() {
+        assert_eq!(PackageQuery::parse("tokio async"), None);
+        assert_eq!(PackageQuery::parse("async runtime"), None);
+        // Only the exact page keywords make a deep link
+        for word in ["doc", "src", "code", "releases", "changes", "dependencies"] {
+            assert_eq!(PackageQuery::parse(&format!("serde {}", word)), None);
+        }
+        assert_eq!(PackageQuery::parse("tokio docs please"), None);
+        assert_eq!(PackageQuery::parse("docs"), None);
+        assert_eq!(PackageQuery::parse(""), None);
+    }
+
+    #[test]
+    fn test_deps_dev_url() {
+        assert_eq!(
+            deps_dev_url("npm", "@angular/core", Some("17.0.0")),
+            "https://deps.dev/npm/@angular%2Fcore/17.0.0"
+        );
+        assert_eq!(
+            deps_dev_url("cargo", "serde", None),
+            "https://deps.dev/cargo/serde"
+        );
+    }
+}
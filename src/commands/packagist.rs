@@ -8,9 +8,13 @@
 /// Packagist command handler
 /// Supports:
 /// - packagist/composer -> https://packagist.org
-/// - packagist [search terms] -> https://packagist.org/search/?query=[search terms]
+/// - packagist [search terms] -> https://packagist.org/search/?query=[search terms] (two or more words)
+/// - packagist [vendor/package] -> https://packagist.org/packages/[vendor/package]
+/// - packagist [vendor/package]@[version] -> https://packagist.org/packages/[vendor/package]#[version]
+/// - packagist [vendor/package] versions -> the version list
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use crate::commands::package::{PackagePage, PackageRegistry};
+use crate::utils::url_encoding::{build_path_url, encode_url};
 
 pub struct PackagistCommand;
 
@@ -21,6 +25,8 @@ impl BunnylolCommand for PackagistCommand {
         let query = Self::get_command_args(args);
         if query.is_empty() {
             "https://packagist.org".to_string()
+        } else if let Some(url) = Self::route_package(query) {
+            url
         } else {
             format!("https://packagist.org/search/?query={}", encode_url(query))
         }
@@ -32,6 +38,32 @@ impl BunnylolCommand for PackagistCommand {
             "Navigate to packagist.org or search for PHP packages",
             "packagist symfony",
         )
+        .with_examples(&[
+            "composer laravel/framework@v11.0.0",
+            "composer symfony/console versions",
+        ])
+    }
+}
+
+impl PackageRegistry for PackagistCommand {
+    const PURL_TYPE: &'static str = "composer";
+
+    fn package_url(name: &str, version: Option<&str>) -> String {
+        let url = build_path_url("https://packagist.org/packages", name);
+        match version {
+            Some(version) => format!("{}#{}", url, encode_url(version)),
+            None => url,
+        }
+    }
+
+    fn page_url(name: &str, _version: Option<&str>, page: PackagePage) -> Option<String> {
+        match page {
+            PackagePage::Versions => Some(format!("{}#versions", Self::package_url(name, None))),
+            PackagePage::Docs
+            | PackagePage::Source
+            | PackagePage::Deps
+            | PackagePage::Changelog => None,
+        }
     }
 }
 
@@ -55,11 +87,23 @@ mod tests {
     fn test_packagist_command_search() {
         assert_eq!(
             PackagistCommand::process_args("packagist symfony"),
-            "https://packagist.org/search/?query=symfony"
+            "https://packagist.org/packages/symfony"
+        );
+        assert_eq!(
+            PackagistCommand::process_args("composer laravel auth"),
+            "https://packagist.org/search/?query=laravel%20auth"
+        );
+    }
+
+    #[test]
+    fn test_packagist_package_routes() {
+        assert_eq!(
+            PackagistCommand::process_args("composer laravel/framework@v11.0.0"),
+            "https://packagist.org/packages/laravel/framework#v11.0.0"
         );
         assert_eq!(
-            PackagistCommand::process_args("composer laravel"),
-            "https://packagist.org/search/?query=laravel"
+            PackagistCommand::process_args("composer symfony/console versions"),
+            "https://packagist.org/packages/symfony/console#versions"
         );
     }
 }
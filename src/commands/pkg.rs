@@ -0,0 +1,248 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+/// Package URL (purl) command handler
+/// Supports:
+/// - pkg -> https://github.com/package-url/purl-spec
+/// - pkg pkg:[type]/[namespace/]name[@version] -> the package page on the type's registry
+/// - pkg [type]/[name][@version] -> the same, without the `pkg:` scheme
+/// - pkg [purl] docs|versions|source|deps|changelog -> that page
+/// - pkg:[type]/[name][@version] typed directly, as a prefix shorthand
+///
+/// Types: cargo, npm, pypi, gem, nuget, composer, golang, docker, brew and
+/// chocolatey (plus the command names, e.g. `go` or `choco`). Qualifiers
+/// (`?arch=...`) and subpaths (`#...`) are ignored. Anything else is sent
+/// to the default search engine.
+use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo, PrefixHandler};
+use crate::commands::package::{PackageQuery, PackageRegistry};
+use crate::commands::search::fallback_search_url;
+use crate::commands::{
+    BrewCommand, CargoCommand, ChocoCommand, DockerhubCommand, GopkgCommand, NpmCommand,
+    NugetCommand, PackagistCommand, PypiCommand, RubygemsCommand,
+};
+use crate::config::get_global_config;
+use percent_encoding::percent_decode_str;
+
+/// A purl type (and its aliases) and the registry that serves it.
+struct PurlType {
+    names: &'static [&'static str],
+    url: fn(&PackageQuery) -> String,
+}
+
+const PURL_TYPES: &[PurlType] = &[
+    PurlType {
+        names: &[CargoCommand::PURL_TYPE, "crates"],
+        url: CargoCommand::package_query_url,
+    },
+    PurlType {
+        names: &[NpmCommand::PURL_TYPE],
+        url: NpmCommand::package_query_url,
+    },
+    PurlType {
+        names: &[PypiCommand::PURL_TYPE, "pip"],
+        url: PypiCommand::package_query_url,
+    },
+    PurlType {
+        names: &[RubygemsCommand::PURL_TYPE, "gems", "rubygems"],
+        url: RubygemsCommand::package_query_url,
+    },
+    PurlType {
+        names: &[NugetCommand::PURL_TYPE],
+        url: NugetCommand::package_query_url,
+    },
+    PurlType {
+        names: &[PackagistCommand::PURL_TYPE, "packagist"],
+        url: PackagistCommand::package_query_url,
+    },
+    PurlType {
+        names: &[GopkgCommand::PURL_TYPE, "go"],
+        url: GopkgCommand::package_query_url,
+    },
+    PurlType {
+        names: &[DockerhubCommand::PURL_TYPE, "dockerhub"],
+        url: DockerhubCommand::package_query_url,
+    },
+    PurlType {
+        names: &[BrewCommand::PURL_TYPE, "homebrew"],
+        url: BrewCommand::package_query_url,
+    },
+    PurlType {
+        names: &[ChocoCommand::PURL_TYPE, "choco"],
+        url: ChocoCommand::package_query_url,
+    },
+];
+
+pub struct PkgCommand;
+
+impl PkgCommand {
+    /// Resolve a purl (with or without `pkg:`) plus an optional page word.
+    pub fn resolve(query: &str) -> Option<String> {
+        let mut words = query.split_whitespace();
+        let purl = words.next()?;
+        let page = words.next().unwrap_or_default();
+
+        let purl = purl
+            .strip_prefix("pkg:")
+            .unwrap_or(purl)
+            .trim_start_matches('/');
+        // Qualifiers and subpath don't change which page to open
+        let purl = purl.split(['?', '#']).next().unwrap_or_default();
+        let (purl_type, rest) = purl.split_once('/')?;
+        let purl_type = purl_type.to_ascii_lowercase();
+        let registry = PURL_TYPES
+            .iter()
+            .find(|registry| registry.names.contains(&purl_type.as_str()))?;
+
+        // purl percent-encodes names (`%40types/node`); decode before
+        // re-encoding for the registry's URL
+        let rest = percent_decode_str(rest).decode_utf8().ok()?;
+        // `@latest` marks an explicit package reference for the parser
+        let spec = if rest.get(1..).is_some_and(|name| name.contains('@')) {
+            rest.to_string()
+        } else {
+            format!("{}@latest", rest)
+        };
+        let query = format!("{} {}", spec, page);
+        let parsed = PackageQuery::parse(&query)?;
+        Some((registry.url)(&parsed))
+    }
+}
+
+impl BunnylolCommand for PkgCommand {
+    const BINDINGS: &'static [&'static str] = &["pkg", "purl"];
+
+    fn process_args(args: &str) -> String {
+        let query = Self::get_command_args(args);
+        if query.is_empty() {
+            return "https://github.com/package-url/purl-spec".to_string();
+        }
+        Self::resolve(query)
+            .unwrap_or_else(|| fallback_search_url(get_global_config().as_ref(), query))
+    }
+
+    fn get_info() -> BunnylolCommandInfo {
+        BunnylolCommandInfo::new(
+            Self::BINDINGS,
+            "Open a Package URL (purl) on its ecosystem's registry",
+            "pkg pkg:npm/react@18",
+        )
+        .with_examples(&[
+            "pkg pkg:cargo/serde",
+            "pkg pkg:golang/github.com/gorilla/mux@v1.8.1 versions",
+            "pkg pypi/django docs",
+        ])
+    }
+}
+
+/// `pkg:` typed directly: `pkg:npm/react@18` is `pkg pkg:npm/react@18`.
+pub struct PurlPrefix;
+
+impl PrefixHandler for PurlPrefix {
+    const PREFIX: &'static str = "pkg:";
+    const EXAMPLES: &'static [&'static str] = &["pkg:npm/react@18", "pkg:cargo/serde docs"];
+
+    fn process_prefix(full_args: &str) -> String {
+        PkgCommand::process_args(&format!("pkg {}", full_args))
+    }
+
+    fn get_info() -> BunnylolCommandInfo {
+        BunnylolCommandInfo::new(
+            &["pkg:<type>/<name>"],
+            "Open a Package URL (purl) on its ecosystem's registry",
+            &Self::EXAMPLES.join("  or  "),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pkg(query: &str) -> String {
+        PkgCommand::process_args(&format!("pkg {}", query))
+    }
+
+    #[test]
+    fn test_pkg_dispatches_by_type() {
+        assert_eq!(
+            pkg("pkg:npm/react@18"),
+            "https://www.npmjs.com/package/react/v/18"
+        );
+        assert_eq!(pkg("pkg:cargo/serde"), "https://crates.io/crates/serde");
+        assert_eq!(
+            pkg("pkg:pypi/django@5.0"),
+            "https://pypi.org/project/django/5.0/"
+        );
+        assert_eq!(
+            pkg("pkg:gem/rails@7.1.0"),
+            "https://rubygems.org/gems/rails/versions/7.1.0"
+        );
+        assert_eq!(
+            pkg("pkg:nuget/Newtonsoft.Json@13.0.3"),
+            "https://www.nuget.org/packages/Newtonsoft.Json/13.0.3"
+        );
+        assert_eq!(
+            pkg("pkg:composer/laravel/framework"),
+            "https://packagist.org/packages/laravel/framework"
+        );
+        assert_eq!(
+            pkg("pkg:golang/github.com/gorilla/mux@v1.8.1"),
+            "https://pkg.go.dev/github.com/gorilla/mux@v1.8.1"
+        );
+        assert_eq!(
+            pkg("pkg:docker/library/nginx"),
+            "https://hub.docker.com/_/nginx"
+        );
+        assert_eq!(
+            pkg("pkg:brew/wget"),
+            "https://formulae.brew.sh/formula/wget"
+        );
+        assert_eq!(
+            pkg("pkg:chocolatey/git"),
+            "https://community.chocolatey.org/packages/git"
+        );
+    }
+
+    #[test]
+    fn test_pkg_namespaces_qualifiers_and_pages() {
+        assert_eq!(
+            pkg("pkg:npm/%40types/node@20.1.0"),
+            "https://www.npmjs.com/package/@types/node/v/20.1.0"
+        );
+        assert_eq!(
+            pkg("pkg:npm/@types/node"),
+            "https://www.npmjs.com/package/@types/node"
+        );
+        assert_eq!(
+            pkg("pkg:cargo/serde@1.0.200?arch=x86_64#src"),
+            "https://crates.io/crates/serde/1.0.200"
+        );
+        assert_eq!(pkg("cargo/tokio docs"), "https://docs.rs/tokio/latest");
+        assert_eq!(
+            pkg("pkg:golang/golang.org/x/net versions"),
+            "https://pkg.go.dev/golang.org/x/net?tab=versions"
+        );
+    }
+
+    #[test]
+    fn test_pkg_falls_back_to_search() {
+        assert_eq!(pkg(""), "https://github.com/package-url/purl-spec");
+        assert!(pkg("pkg:maven/org.apache/commons").contains("search?q="));
+        assert!(pkg("react hooks guide").contains("search?q="));
+    }
+
+    #[test]
+    fn test_purl_prefix() {
+        assert!(PurlPrefix::matches("pkg:npm/react"));
+        assert!(!PurlPrefix::matches("pkg:"));
+        assert!(!PurlPrefix::matches("pkg"));
+        assert_eq!(
+            PurlPrefix::process_prefix("pkg:cargo/serde docs"),
+            "https://docs.rs/serde/latest"
+        );
+    }
+}
@@ -0,0 +1,414 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+/// Interchangeable sites, grouped by category (search engines, stock quote
+/// sites, maps, video, encyclopedias, shopping).
+///
+/// Each category has built-in providers, the first of which is the default.
+/// `[providers] <category> = "<name>"` picks another one (`maps = "osm"`),
+/// and `[providers.custom.<name>]` adds URL templates of your own. The
+/// commands for a category (`stock`, `maps`, `yt`, `wiki`, `az` and the
+/// search fallback) open the preferred provider.
+use crate::config::BunnylolConfig;
+use crate::utils::url_encoding::{encode_url, encode_url_special_char};
+
+/// A group of interchangeable providers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProviderCategory {
+    Search,
+    Stock,
+    Maps,
+    Video,
+    Encyclopedia,
+    Shopping,
+}
+
+/// How a query is escaped before it replaces `{}` in a provider's URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryEncoding {
+    /// [`encode_url`]: spaces, quotes, `&`, `=`, `+` and `#`
+    Url,
+    /// [`encode_url_special_char`]: everything but ASCII letters and digits
+    Strict,
+    /// Inserted as typed
+    Raw,
+}
+
+/// A built-in provider.
+struct BuiltinProvider {
+    aliases: &'static [&'static str],
+    homepage: &'static str,
+    url_template: &'static str,
+    encoding: QueryEncoding,
+}
+
+const SEARCH: &[BuiltinProvider] = &[
+    BuiltinProvider {
+        aliases: &["google"],
+        homepage: "https://www.google.com/",
+        url_template: "https://www.google.com/search?q={}",
+        encoding: QueryEncoding::Url,
+    },
+    BuiltinProvider {
+        aliases: &["ddg", "duckduckgo"],
+        homepage: "https://duckduckgo.com/",
+        url_template: "https://duckduckgo.com/?q={}",
+        encoding: QueryEncoding::Url,
+    },
+    BuiltinProvider {
+        aliases: &["bing"],
+        homepage: "https://www.bing.com/",
+        url_template: "https://www.bing.com/search?q={}",
+        encoding: QueryEncoding::Url,
+    },
+];
+
+const STOCK: &[BuiltinProvider] = &[
+    BuiltinProvider {
+        aliases: &["yahoo"],
+        homepage: "https://finance.yahoo.com/",
+        url_template: "https://finance.yahoo.com/quote/{}/",
+        encoding: QueryEncoding::Strict,
+    },
+    BuiltinProvider {
+        aliases: &["finviz"],
+        homepage: "https://finviz.com/",
+        url_template: "https://finviz.com/quote.ashx?t={}",
+        encoding: QueryEncoding::Raw,
+    },
+    BuiltinProvider {
+        aliases: &["tradingview", "tv"],
+        homepage: "https://www.tradingview.com/",
+        url_template: "https://www.tradingview.com/symbols/{}/",
+        encoding: QueryEncoding::Raw,
+    },
+    BuiltinProvider {
+        aliases: &["google", "gf"],
+        homepage: "https://www.google.com/finance/",
+        url_template: "https://www.google.com/finance/quote/{}",
+        encoding: QueryEncoding::Raw,
+    },
+    BuiltinProvider {
+        aliases: &["investing", "inv"],
+        homepage: "https://www.investing.com/",
+        url_template: "https://www.investing.com/search/?q={}",
+        encoding: QueryEncoding::Strict,
+    },
+];
+
+const MAPS: &[BuiltinProvider] = &[
+    BuiltinProvider {
+        aliases: &["google"],
+        homepage: "https://www.google.com/maps",
+        url_template: "https://www.google.com/maps/search/{}/",
+        encoding: QueryEncoding::Url,
+    },
+    BuiltinProvider {
+        aliases: &["openstreetmap", "osm"],
+        homepage: "https://www.openstreetmap.org/",
+        url_template: "https://www.openstreetmap.org/search?query={}",
+        encoding: QueryEncoding::Url,
+    },
+    BuiltinProvider {
+        aliases: &["apple"],
+        homepage: "https://maps.apple.com/",
+        url_template: "https://maps.apple.com/?q={}",
+        encoding: QueryEncoding::Url,
+    },
+    BuiltinProvider {
+        aliases: &["bing"],
+        homepage: "https://www.bing.com/maps",
+        url_template: "https://www.bing.com/maps?q={}",
+        encoding: QueryEncoding::Url,
+    },
+];
+
+const VIDEO: &[BuiltinProvider] = &[
+    BuiltinProvider {
+        aliases: &["youtube"],
+        homepage: "https://youtube.com/",
+        url_template: "https://www.youtube.com/results?search_query={}",
+        encoding: QueryEncoding::Url,
+    },
+    BuiltinProvider {
+        aliases: &["vimeo"],
+        homepage: "https://vimeo.com/",
+        url_template: "https://vimeo.com/search?q={}",
+        encoding: QueryEncoding::Url,
+    },
+];
+
+const ENCYCLOPEDIA: &[BuiltinProvider] = &[
+    BuiltinProvider {
+        aliases: &["wikipedia"],
+        homepage: "https://en.wikipedia.org/",
+        url_template: "https://en.wikipedia.org/w/index.php?search={}&title=Special%3ASearch&ns0=1",
+        encoding: QueryEncoding::Url,
+    },
+    BuiltinProvider {
+        aliases: &["britannica"],
+        homepage: "https://www.britannica.com/",
+        url_template: "https://www.britannica.com/search?query={}",
+        encoding: QueryEncoding::Url,
+    },
+];
+
+const SHOPPING: &[BuiltinProvider] = &[
+    BuiltinProvider {
+        aliases: &["amazon"],
+        homepage: "https://amazon.com/",
+        url_template: "https://www.amazon.com/s?k={}",
+        encoding: QueryEncoding::Url,
+    },
+    BuiltinProvider {
+        aliases: &["ebay"],
+        homepage: "https://www.ebay.com/",
+        url_template: "https://www.ebay.com/sch/i.html?_nkw={}",
+        encoding: QueryEncoding::Url,
+    },
+];
+
+impl ProviderCategory {
+    pub const ALL: [Self; 6] = [
+        Self::Search,
+        Self::Stock,
+        Self::Maps,
+        Self::Video,
+        Self::Encyclopedia,
+        Self::Shopping,
+    ];
+
+    /// The category's key in `[providers]`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Search => "search",
+            Self::Stock => "stock",
+            Self::Maps => "maps",
+            Self::Video => "video",
+            Self::Encyclopedia => "encyclopedia",
+            Self::Shopping => "shopping",
+        }
+    }
+
+    pub fn parse(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.key() == key)
+    }
+
+    fn builtins(self) -> &'static [BuiltinProvider] {
+        match self {
+            Self::Search => SEARCH,
+            Self::Stock => STOCK,
+            Self::Maps => MAPS,
+            Self::Video => VIDEO,
+            Self::Encyclopedia => ENCYCLOPEDIA,
+            Self::Shopping => SHOPPING,
+        }
+    }
+
+    /// Name of the default provider (`google`, `yahoo`, `youtube`, ...).
+    pub fn default_name(self) -> &'static str {
+        self.builtins()[0].aliases[0]
+    }
+}
+
+/// A provider resolved from the built-ins or `[providers.custom]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provider {
+    pub category: ProviderCategory,
+    /// Canonical name: a built-in's first alias, or the custom table name
+    pub name: String,
+    pub homepage: String,
+    /// Query URL, with `{}` for the encoded query
+    pub url_template: String,
+    pub encoding: QueryEncoding,
+}
+
+impl Provider {
+    fn builtin(category: ProviderCategory, builtin: &BuiltinProvider) -> Self {
+        Provider {
+            category,
+            name: builtin.aliases[0].to_string(),
+            homepage: builtin.homepage.to_string(),
+            url_template: builtin.url_template.to_string(),
+            encoding: builtin.encoding,
+        }
+    }
+
+    /// Whether this is the category's default provider, the site its
+    /// commands open without `[providers]` config.
+    pub fn is_default(&self) -> bool {
+        self.name == self.category.default_name()
+    }
+
+    /// The URL for `query`, or the homepage when it's empty.
+    pub fn url(&self, query: &str) -> String {
+        if query.is_empty() {
+            return self.homepage.clone();
+        }
+        let query = match self.encoding {
+            QueryEncoding::Url => encode_url(query),
+            QueryEncoding::Strict => encode_url_special_char(query),
+            QueryEncoding::Raw => query.to_string(),
+        };
+        self.url_template.replace("{}", &query)
+    }
+}
+
+/// The built-in provider of `category` with the alias `name`.
+pub fn find_builtin(category: ProviderCategory, name: &str) -> Option<Provider> {
+    let name = name.to_lowercase();
+    category
+        .builtins()
+        .iter()
+        .find(|builtin| builtin.aliases.contains(&name.as_str()))
+        .map(|builtin| Provider::builtin(category, builtin))
+}
+
+/// Names of the built-in providers of `category`, for messages.
+pub fn builtin_names(category: ProviderCategory) -> Vec<&'static str> {
+    category
+        .builtins()
+        .iter()
+        .map(|builtin| builtin.aliases[0])
+        .collect()
+}
+
+/// The provider of `category` named `name`: a built-in alias, or a
+/// `[providers.custom]` entry of that category.
+pub fn find(
+    category: ProviderCategory,
+    name: &str,
+    config: Option<&BunnylolConfig>,
+) -> Option<Provider> {
+    find_builtin(category, name).or_else(|| {
+        let custom = config?.providers.custom.get(name)?;
+        (custom.category == category.key()).then(|| Provider {
+            category,
+            name: name.to_string(),
+            homepage: custom.homepage(),
+            url_template: custom.url.clone(),
+            encoding: QueryEncoding::Url,
+        })
+    })
+}
+
+/// The provider configured for `category`: `[providers] <category>`, then
+/// the legacy `default_search` / `stock_provider` keys, then the default.
+pub fn preferred(category: ProviderCategory, config: Option<&BunnylolConfig>) -> Provider {
+    let configured = config.and_then(|config| {
+        config.providers.preferred(category).or(match category {
+            ProviderCategory::Search => Some(config.default_search.as_str()),
+            ProviderCategory::Stock => Some(config.stock_provider.as_str()),
+            _ => None,
+        })
+    });
+    match configured {
+        Some(name) => find_or_default(category, name, config),
+        None => default(category),
+    }
+}
+
+/// [`find`], falling back to the category's default with a warning.
+pub fn find_or_default(
+    category: ProviderCategory,
+    name: &str,
+    config: Option<&BunnylolConfig>,
+) -> Provider {
+    find(category, name, config).unwrap_or_else(|| {
+        eprintln!(
+            "Warning: Unknown {} provider '{}', using {} as fallback",
+            category.key(),
+            name,
+            category.default_name()
+        );
+        default(category)
+    })
+}
+
+/// The category's default provider.
+pub fn default(category: ProviderCategory) -> Provider {
+    Provider::builtin(category, &category.builtins()[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(body: &str) -> BunnylolConfig {
+        toml::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_lookup_by_alias() {
+        let tv = find_builtin(ProviderCategory::Stock, "TV").unwrap();
+        assert_eq!(tv.name, "tradingview");
+        assert_eq!(tv.url("AAPL"), "https://www.tradingview.com/symbols/AAPL/");
+        assert_eq!(
+            find_builtin(ProviderCategory::Maps, "osm")
+                .unwrap()
+                .url("Berlin Mitte"),
+            "https://www.openstreetmap.org/search?query=Berlin%20Mitte"
+        );
+        // Aliases are per category
+        assert_eq!(find_builtin(ProviderCategory::Maps, "finviz"), None);
+    }
+
+    #[test]
+    fn test_url_encoding_and_homepage() {
+        let yahoo = default(ProviderCategory::Stock);
+        assert_eq!(
+            yahoo.url("BRK.B"),
+            "https://finance.yahoo.com/quote/BRK%2EB/"
+        );
+        assert_eq!(yahoo.url(""), "https://finance.yahoo.com/");
+        assert!(yahoo.is_default());
+        assert!(
+            !find_builtin(ProviderCategory::Stock, "finviz")
+                .unwrap()
+                .is_default()
+        );
+    }
+
+    #[test]
+    fn test_preferred_provider() {
+        assert_eq!(preferred(ProviderCategory::Video, None).name, "youtube");
+
+        let cfg = config(
+            r#"
+default_search = "bing"
+stock_provider = "finviz"
+
+[providers]
+stock = "tv"
+video = "invidious"
+
+[providers.custom.invidious]
+category = "video"
+url = "https://yewtu.be/search?q={}"
+"#,
+        );
+        let video = preferred(ProviderCategory::Video, Some(&cfg));
+        assert_eq!(video.url("rust"), "https://yewtu.be/search?q=rust");
+        assert_eq!(video.url(""), "https://yewtu.be/");
+        // `[providers]` wins over the legacy keys, which still apply alone
+        assert_eq!(
+            preferred(ProviderCategory::Stock, Some(&cfg)).name,
+            "tradingview"
+        );
+        assert_eq!(preferred(ProviderCategory::Search, Some(&cfg)).name, "bing");
+        // A custom provider only serves its own category
+        assert_eq!(find(ProviderCategory::Maps, "invidious", Some(&cfg)), None);
+    }
+
+    #[test]
+    fn test_unknown_provider_falls_back_to_default() {
+        assert_eq!(
+            find_or_default(ProviderCategory::Search, "altavista", None).name,
+            "google"
+        );
+    }
+}
@@ -8,9 +8,13 @@
 /// PyPI command handler
 /// Supports:
 /// - pypi/pip -> https://pypi.org
-/// - pypi [search terms] -> https://pypi.org/search/?q=[search terms]
+/// - pypi [search terms] -> https://pypi.org/search/?q=[search terms] (two or more words)
+/// - pypi [project] -> https://pypi.org/project/[project]/
+/// - pypi [project]@[version] -> https://pypi.org/project/[project]/[version]/
+/// - pypi [project][@version] versions|deps -> release history / deps.dev
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::build_search_url;
+use crate::commands::package::{PackagePage, PackageRegistry, deps_dev_url};
+use crate::utils::url_encoding::{build_path_url, build_search_url};
 
 pub struct PypiCommand;
 
@@ -21,6 +25,8 @@ impl BunnylolCommand for PypiCommand {
         let query = Self::get_command_args(args);
         if query.is_empty() {
             "https://pypi.org".to_string()
+        } else if let Some(url) = Self::route_package(query) {
+            url
         } else {
             build_search_url("https://pypi.org/search/", "q", query)
         }
@@ -32,6 +38,27 @@ impl BunnylolCommand for PypiCommand {
             "Navigate to pypi.org or search for Python packages",
             "pypi requests",
         )
+        .with_examples(&["pypi django@5.0", "pypi requests versions"])
+    }
+}
+
+impl PackageRegistry for PypiCommand {
+    const PURL_TYPE: &'static str = "pypi";
+
+    fn package_url(name: &str, version: Option<&str>) -> String {
+        let path = match version {
+            Some(version) => format!("{}/{}/", name, version),
+            None => format!("{}/", name),
+        };
+        build_path_url("https://pypi.org/project", &path)
+    }
+
+    fn page_url(name: &str, version: Option<&str>, page: PackagePage) -> Option<String> {
+        match page {
+            PackagePage::Versions => Some(format!("{}#history", Self::package_url(name, None))),
+            PackagePage::Deps => Some(deps_dev_url("pypi", name, version)),
+            PackagePage::Docs | PackagePage::Source | PackagePage::Changelog => None,
+        }
     }
 }
 
@@ -49,11 +76,31 @@ mod tests {
     fn test_pypi_command_search() {
         assert_eq!(
             PypiCommand::process_args("pypi requests"),
-            "https://pypi.org/search/?q=requests"
+            "https://pypi.org/project/requests/"
         );
         assert_eq!(
             PypiCommand::process_args("pip django rest framework"),
             "https://pypi.org/search/?q=django%20rest%20framework"
         );
     }
+
+    #[test]
+    fn test_pypi_package_routes() {
+        assert_eq!(
+            PypiCommand::process_args("pypi django@5.0"),
+            "https://pypi.org/project/django/5.0/"
+        );
+        assert_eq!(
+            PypiCommand::process_args("pip requests versions"),
+            "https://pypi.org/project/requests/#history"
+        );
+        assert_eq!(
+            PypiCommand::process_args("pypi requests deps"),
+            "https://deps.dev/pypi/requests"
+        );
+        assert_eq!(
+            PypiCommand::process_args("pypi requests changelog"),
+            "https://pypi.org/project/requests/"
+        );
+    }
 }
@@ -12,23 +12,75 @@
 /// - python tutorial -> https://docs.python.org/3/tutorial/
 /// - python library -> https://docs.python.org/3/library/
 /// - python reference -> https://docs.python.org/3/reference/
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+/// - python [version] ... / py[version] ... -> the same pages for that version (py3.9 asyncio)
+///
+/// The default version is configurable with `[commands.python] version = "3.9"`, and
+/// `[commands.python] mirror = "..."` opens a local copy of the default version's
+/// HTML docs.
+use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo, PrefixHandler};
+use crate::config::{CommandConfig, get_global_config};
+use crate::docs_mirror;
 use crate::utils::url_encoding::build_search_url;
 
 pub struct PythonCommand;
 
+impl PythonCommand {
+    /// Docs version used when neither the input nor the config names one
+    pub const DEFAULT_VERSION: &'static str = "3";
+
+    /// Normalize a docs version ("3", "3.9", "2.7"), or `None` if `version`
+    /// isn't one.
+    pub fn parse_version(version: &str) -> Option<String> {
+        let mut parts = version.split('.');
+        let major = parts.next()?;
+        let minor = parts.next();
+        let valid = matches!(major, "2" | "3")
+            && parts.next().is_none()
+            && minor.is_none_or(|minor| {
+                (1..=2).contains(&minor.len()) && minor.chars().all(|c| c.is_ascii_digit())
+            });
+        valid.then(|| version.to_string())
+    }
+
+    /// Build the URL for `query` (the input after the command word). A
+    /// leading version word overrides the configured default version.
+    pub fn process_query(query: &str, settings: Option<&CommandConfig>) -> String {
+        let (first, rest) = query.split_once(' ').unwrap_or((query, ""));
+        let (version, query, pinned) = match Self::parse_version(first) {
+            Some(version) => (version, rest.trim_start(), true),
+            None => (
+                settings
+                    .and_then(|s| s.version.as_deref())
+                    .and_then(Self::parse_version)
+                    .unwrap_or_else(|| Self::DEFAULT_VERSION.to_string()),
+                query,
+                false,
+            ),
+        };
+        let base = format!("https://docs.python.org/{}", version);
+        let url = match query {
+            "" => format!("{}/", base),
+            "tutorial" => format!("{}/tutorial/", base),
+            "library" | "lib" => format!("{}/library/", base),
+            "reference" | "ref" => format!("{}/reference/", base),
+            _ => build_search_url(&format!("{}/search.html", base), "q", query),
+        };
+        // The mirror holds the default version's docs only
+        if pinned {
+            url
+        } else {
+            docs_mirror::local_url(settings, &base, &url).unwrap_or(url)
+        }
+    }
+}
+
 impl BunnylolCommand for PythonCommand {
     const BINDINGS: &'static [&'static str] = &["python", "pydocs", "py"];
 
     fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
-        match query {
-            "" => "https://docs.python.org/3/".to_string(),
-            "tutorial" => "https://docs.python.org/3/tutorial/".to_string(),
-            "library" | "lib" => "https://docs.python.org/3/library/".to_string(),
-            "reference" | "ref" => "https://docs.python.org/3/reference/".to_string(),
-            _ => build_search_url("https://docs.python.org/3/search.html", "q", query),
-        }
+        let config = get_global_config();
+        let settings = config.as_ref().and_then(|c| c.command_settings("python"));
+        Self::process_query(Self::get_command_args(args), settings)
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -37,6 +89,40 @@ impl BunnylolCommand for PythonCommand {
             "Navigate to Python documentation or search for Python resources",
             "python list",
         )
+        .with_examples(&["py 3.9 asyncio", "python library"])
+    }
+}
+
+/// `py3.9 asyncio` / `python3.12 typing`: the version glued to the command word.
+pub struct PythonVersionPrefix;
+
+impl PrefixHandler for PythonVersionPrefix {
+    const PREFIX: &'static str = "py";
+    const EXAMPLES: &'static [&'static str] = &["py3.9 asyncio", "python3.12 typing"];
+
+    fn process_prefix(full_args: &str) -> String {
+        let (command, rest) = full_args.split_once(' ').unwrap_or((full_args, ""));
+        let version = command
+            .strip_prefix("python")
+            .or_else(|| command.strip_prefix("py"))
+            .unwrap_or_default();
+        PythonCommand::process_args(&format!("python {} {}", version, rest))
+    }
+
+    fn matches(command: &str) -> bool {
+        command
+            .strip_prefix("python")
+            .or_else(|| command.strip_prefix("py"))
+            .and_then(PythonCommand::parse_version)
+            .is_some()
+    }
+
+    fn get_info() -> BunnylolCommandInfo {
+        BunnylolCommandInfo::new(
+            &["py<version>", "python<version>"],
+            "Open the Python documentation for a specific version",
+            &Self::EXAMPLES.join("  or  "),
+        )
     }
 }
 
@@ -103,4 +189,60 @@ mod tests {
             "https://docs.python.org/3/reference/"
         );
     }
+
+    #[test]
+    fn test_python_command_version() {
+        assert_eq!(
+            PythonCommand::process_args("py 3.9 asyncio"),
+            "https://docs.python.org/3.9/search.html?q=asyncio"
+        );
+        assert_eq!(
+            PythonCommand::process_args("python 3.12 library"),
+            "https://docs.python.org/3.12/library/"
+        );
+        assert_eq!(
+            PythonCommand::process_args("python 2.7"),
+            "https://docs.python.org/2.7/"
+        );
+        // Not versions: searched as usual
+        assert_eq!(
+            PythonCommand::process_args("python 3.x"),
+            "https://docs.python.org/3/search.html?q=3.x"
+        );
+    }
+
+    #[test]
+    fn test_python_configured_version() {
+        let settings = CommandConfig {
+            version: Some("3.9".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            PythonCommand::process_query("tutorial", Some(&settings)),
+            "https://docs.python.org/3.9/tutorial/"
+        );
+        assert_eq!(
+            PythonCommand::process_query("3.11 tutorial", Some(&settings)),
+            "https://docs.python.org/3.11/tutorial/"
+        );
+    }
+
+    #[test]
+    fn test_python_version_prefix() {
+        assert!(PythonVersionPrefix::matches("py3.9"));
+        assert!(PythonVersionPrefix::matches("python3.12"));
+        assert!(PythonVersionPrefix::matches("py3"));
+        assert!(!PythonVersionPrefix::matches("py"));
+        assert!(!PythonVersionPrefix::matches("pypi"));
+        assert!(!PythonVersionPrefix::matches("pydocs"));
+        assert!(!PythonVersionPrefix::matches("python"));
+        assert_eq!(
+            PythonVersionPrefix::process_prefix("py3.9 asyncio"),
+            "https://docs.python.org/3.9/search.html?q=asyncio"
+        );
+        assert_eq!(
+            PythonVersionPrefix::process_prefix("python3.11"),
+            "https://docs.python.org/3.11/"
+        );
+    }
 }
@@ -13,8 +13,9 @@
 /// - r r/[subreddit] [search terms] -> https://reddit.com/r/[subreddit]/search/?q=[search terms]
 /// - r/[subreddit] -> https://www.reddit.com/r/[subreddit]/  (prefix shorthand)
 /// - r/[subreddit] [search terms] -> https://www.reddit.com/r/[subreddit]/search/?q=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::build_search_url;
+/// - u/[user] -> https://www.reddit.com/user/[user]/  (prefix shorthand)
+use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo, PrefixHandler};
+use crate::utils::url_encoding::{build_path_url, build_search_url};
 
 pub struct RedditCommand;
 
@@ -58,7 +59,7 @@ impl BunnylolCommand for RedditCommand {
 }
 
 impl RedditCommand {
-    /// Handle the `r/SUBREDDITNAME` prefix shorthand (called from the command registry).
+    /// Handle the `r/SUBREDDITNAME` prefix shorthand (see [`SubredditPrefix`]).
     /// - `r/myog` -> https://www.reddit.com/r/myog/
     /// - `r/myog search terms` -> https://www.reddit.com/r/myog/search/?q=search%20terms
     pub fn process_subreddit_prefix(full_args: &str) -> String {
@@ -87,6 +88,48 @@ impl RedditCommand {
     }
 }
 
+/// `r/SUBREDDIT` prefix shorthand for [`RedditCommand`]
+pub struct SubredditPrefix;
+
+impl PrefixHandler for SubredditPrefix {
+    const PREFIX: &'static str = "r/";
+    const EXAMPLES: &'static [&'static str] = &["r/rust", "r/rust async await"];
+
+    fn process_prefix(full_args: &str) -> String {
+        RedditCommand::process_subreddit_prefix(full_args)
+    }
+
+    fn get_info() -> BunnylolCommandInfo {
+        BunnylolCommandInfo::new(
+            &["r/<subreddit>"],
+            "Open a subreddit, or search within it",
+            &Self::EXAMPLES.join("  or  "),
+        )
+    }
+}
+
+/// `u/USER` prefix shorthand for Reddit user profiles
+pub struct RedditUserPrefix;
+
+impl PrefixHandler for RedditUserPrefix {
+    const PREFIX: &'static str = "u/";
+    const EXAMPLES: &'static [&'static str] = &["u/spez"];
+
+    fn process_prefix(full_args: &str) -> String {
+        let word = full_args.split_whitespace().next().unwrap_or(full_args);
+        let user = word.strip_prefix(Self::PREFIX).unwrap_or(word);
+        format!("{}/", build_path_url("https://www.reddit.com/user", user))
+    }
+
+    fn get_info() -> BunnylolCommandInfo {
+        BunnylolCommandInfo::new(
+            &["u/<user>"],
+            "Open a Reddit user's profile",
+            Self::EXAMPLES[0],
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,4 +178,28 @@ mod tests {
             "https://www.reddit.com/r/rust/search/?q=async%20await"
         );
     }
+
+    #[test]
+    fn test_subreddit_prefix_handler() {
+        assert!(SubredditPrefix::matches("r/rust"));
+        assert!(!SubredditPrefix::matches("r/"));
+        assert_eq!(
+            SubredditPrefix::process_prefix("r/rust"),
+            "https://www.reddit.com/r/rust/"
+        );
+    }
+
+    #[test]
+    fn test_reddit_user_prefix() {
+        assert!(RedditUserPrefix::matches("u/spez"));
+        assert!(!RedditUserPrefix::matches("u/"));
+        assert_eq!(
+            RedditUserPrefix::process_prefix("u/spez"),
+            "https://www.reddit.com/user/spez/"
+        );
+        assert_eq!(
+            RedditUserPrefix::process_prefix("u/spez ignored"),
+            "https://www.reddit.com/user/spez/"
+        );
+    }
 }
@@ -8,9 +8,13 @@
 /// RubyGems command handler
 /// Supports:
 /// - rubygems/gem/gems -> https://rubygems.org
-/// - rubygems [search terms] -> https://rubygems.org/search?query=[search terms]
+/// - rubygems [search terms] -> https://rubygems.org/search?query=[search terms] (two or more words)
+/// - gem [name] -> https://rubygems.org/gems/[name]
+/// - gem [name]@[version] -> https://rubygems.org/gems/[name]/versions/[version]
+/// - gem [name][@version] docs|versions -> rubydoc.info / version list
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use crate::commands::package::{PackagePage, PackageRegistry};
+use crate::utils::url_encoding::{build_path_url, encode_url};
 
 pub struct RubygemsCommand;
 
@@ -21,6 +25,8 @@ impl BunnylolCommand for RubygemsCommand {
         let query = Self::get_command_args(args);
         if query.is_empty() {
             "https://rubygems.org".to_string()
+        } else if let Some(url) = Self::route_package(query) {
+            url
         } else {
             format!("https://rubygems.org/search?query={}", encode_url(query))
         }
@@ -32,6 +38,30 @@ impl BunnylolCommand for RubygemsCommand {
             "Navigate to rubygems.org or search for Ruby gems",
             "gem rails",
         )
+        .with_examples(&["gem rails@7.1.0", "gem devise docs"])
+    }
+}
+
+impl PackageRegistry for RubygemsCommand {
+    const PURL_TYPE: &'static str = "gem";
+
+    fn package_url(name: &str, version: Option<&str>) -> String {
+        let path = match version {
+            Some(version) => format!("{}/versions/{}", name, version),
+            None => name.to_string(),
+        };
+        build_path_url("https://rubygems.org/gems", &path)
+    }
+
+    fn page_url(name: &str, version: Option<&str>, page: PackagePage) -> Option<String> {
+        match page {
+            PackagePage::Docs => Some(build_path_url(
+                "https://www.rubydoc.info/gems",
+                &version.map_or_else(|| name.to_string(), |v| format!("{}/{}", name, v)),
+            )),
+            PackagePage::Versions => Some(format!("{}/versions", Self::package_url(name, None))),
+            PackagePage::Source | PackagePage::Deps | PackagePage::Changelog => None,
+        }
     }
 }
 
@@ -56,11 +86,27 @@ mod tests {
     fn test_rubygems_command_search() {
         assert_eq!(
             RubygemsCommand::process_args("gem rails"),
-            "https://rubygems.org/search?query=rails"
+            "https://rubygems.org/gems/rails"
         );
         assert_eq!(
             RubygemsCommand::process_args("rubygems devise authentication"),
             "https://rubygems.org/search?query=devise%20authentication"
         );
     }
+
+    #[test]
+    fn test_rubygems_package_routes() {
+        assert_eq!(
+            RubygemsCommand::process_args("gem rails@7.1.0"),
+            "https://rubygems.org/gems/rails/versions/7.1.0"
+        );
+        assert_eq!(
+            RubygemsCommand::process_args("gem devise docs"),
+            "https://www.rubydoc.info/gems/devise"
+        );
+        assert_eq!(
+            RubygemsCommand::process_args("gem rails versions"),
+            "https://rubygems.org/gems/rails/versions"
+        );
+    }
 }
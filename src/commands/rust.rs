@@ -9,25 +9,78 @@
 /// Supports:
 /// - rust -> https://doc.rust-lang.org/stable/std/index.html
 /// - rust [search terms] -> https://doc.rust-lang.org/stable/std/index.html?search=[search terms]
+/// - rust [version] [search terms] -> the same for that release (rust 1.70 Vec) or
+///   channel (rust nightly)
+///
+/// The default version is configurable with `[commands.rust] version = "1.70"`, and
+/// `[commands.rust] mirror = "..."` opens a local copy (e.g. from `rustup doc`) of
+/// the default version's docs.
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::config::{CommandConfig, get_global_config};
+use crate::docs_mirror;
 use crate::utils::url_encoding::build_search_url;
 
 pub struct RustCommand;
 
+impl RustCommand {
+    /// Docs version used when neither the input nor the config names one
+    pub const DEFAULT_VERSION: &'static str = "stable";
+
+    /// Normalize a docs version: a channel ("stable", "beta", "nightly") or
+    /// a release ("1.70" becomes "1.70.0"). `None` if `version` isn't one.
+    pub fn parse_version(version: &str) -> Option<String> {
+        if matches!(version, "stable" | "beta" | "nightly") {
+            return Some(version.to_string());
+        }
+        let parts: Vec<&str> = version.split('.').collect();
+        let numeric = parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+        match parts.as_slice() {
+            ["1", minor] if numeric => Some(format!("1.{}.0", minor)),
+            ["1", _, _] if numeric => Some(version.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Build the URL for `query` (the input after the command word). A
+    /// leading version word overrides the configured default version.
+    pub fn process_query(query: &str, settings: Option<&CommandConfig>) -> String {
+        let (first, rest) = query.split_once(' ').unwrap_or((query, ""));
+        let (version, query, pinned) = match Self::parse_version(first) {
+            Some(version) => (version, rest.trim_start(), true),
+            None => (
+                settings
+                    .and_then(|s| s.version.as_deref())
+                    .and_then(Self::parse_version)
+                    .unwrap_or_else(|| Self::DEFAULT_VERSION.to_string()),
+                query,
+                false,
+            ),
+        };
+        let root = format!("https://doc.rust-lang.org/{}/", version);
+        let index = format!("{}std/index.html", root);
+        let url = if query.is_empty() {
+            index
+        } else {
+            build_search_url(&index, "search", query)
+        };
+        // The mirror holds the default version's docs only
+        if pinned {
+            url
+        } else {
+            docs_mirror::local_url(settings, &root, &url).unwrap_or(url)
+        }
+    }
+}
+
 impl BunnylolCommand for RustCommand {
     const BINDINGS: &'static [&'static str] = &["rust"];
 
     fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
-        if query.is_empty() {
-            "https://doc.rust-lang.org/stable/std/index.html".to_string()
-        } else {
-            build_search_url(
-                "https://doc.rust-lang.org/stable/std/index.html",
-                "search",
-                query,
-            )
-        }
+        let config = get_global_config();
+        let settings = config.as_ref().and_then(|c| c.command_settings("rust"));
+        Self::process_query(Self::get_command_args(args), settings)
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -36,6 +89,7 @@ impl BunnylolCommand for RustCommand {
             "Navigate to Rust documentation or search Rust std docs",
             "rust HashMap",
         )
+        .with_examples(&["rust 1.70 Vec", "rust nightly"])
     }
 }
 
@@ -62,4 +116,41 @@ mod tests {
             "https://doc.rust-lang.org/stable/std/index.html?search=Vec%20String"
         );
     }
+
+    #[test]
+    fn test_rust_command_version() {
+        assert_eq!(
+            RustCommand::process_args("rust 1.70 Vec"),
+            "https://doc.rust-lang.org/1.70.0/std/index.html?search=Vec"
+        );
+        assert_eq!(
+            RustCommand::process_args("rust 1.75.1 HashMap"),
+            "https://doc.rust-lang.org/1.75.1/std/index.html?search=HashMap"
+        );
+        assert_eq!(
+            RustCommand::process_args("rust nightly"),
+            "https://doc.rust-lang.org/nightly/std/index.html"
+        );
+        // Not versions: searched as usual
+        assert_eq!(
+            RustCommand::process_args("rust 2.0"),
+            "https://doc.rust-lang.org/stable/std/index.html?search=2.0"
+        );
+    }
+
+    #[test]
+    fn test_rust_configured_version() {
+        let settings = CommandConfig {
+            version: Some("1.70".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            RustCommand::process_query("Vec", Some(&settings)),
+            "https://doc.rust-lang.org/1.70.0/std/index.html?search=Vec"
+        );
+        assert_eq!(
+            RustCommand::process_query("beta Vec", Some(&settings)),
+            "https://doc.rust-lang.org/beta/std/index.html?search=Vec"
+        );
+    }
 }
@@ -6,17 +6,57 @@
  */
 
 /// Search engine fallback URL builder
-/// Used when no command matches the input, routing to the configured default search engine
-use crate::utils::url_encoding::build_search_url;
+/// Used when no command matches the input, routing to the preferred `search`
+/// provider (`[providers] search`, else `default_search`)
+use crate::commands::Locale;
+use crate::commands::providers::{self, Provider, ProviderCategory};
+use crate::config::BunnylolConfig;
 
 /// Build a search URL for the given engine and query string.
 /// Falls back to Google for any unrecognized engine name.
 pub fn search_url(engine: &str, query: &str) -> String {
-    match engine {
-        "ddg" | "duckduckgo" => build_search_url("https://duckduckgo.com/", "q", query),
-        "bing" => build_search_url("https://www.bing.com/search", "q", query),
-        _ => build_search_url("https://www.google.com/search", "q", query),
-    }
+    localized_search_url(engine, query, None)
+}
+
+/// [`search_url`] with the engine's language and region parameters for
+/// `locale`: `hl`/`gl` for Google, `kl` for DuckDuckGo (regions only),
+/// `setlang`/`cc` for Bing.
+pub fn localized_search_url(engine: &str, query: &str, locale: Option<&Locale>) -> String {
+    let provider = providers::find_builtin(ProviderCategory::Search, engine)
+        .unwrap_or_else(|| providers::default(ProviderCategory::Search));
+    provider_search_url(&provider, query, locale)
+}
+
+/// The search fallback: `query` on the preferred search provider, in the
+/// configured locale.
+pub fn fallback_search_url(config: Option<&BunnylolConfig>, query: &str) -> String {
+    let provider = providers::preferred(ProviderCategory::Search, config);
+    provider_search_url(&provider, query, Locale::configured(config, None).as_ref())
+}
+
+/// `query` on a search `provider`. Locale parameters are only known for the
+/// built-in engines.
+pub fn provider_search_url(provider: &Provider, query: &str, locale: Option<&Locale>) -> String {
+    let url = provider.url(query);
+    let Some(locale) = locale.filter(|_| !query.is_empty()) else {
+        return url;
+    };
+    let region = locale.region.as_deref();
+    let params = match provider.name.as_str() {
+        "ddg" => region
+            .map(|region| format!("&kl={}-{}", region.to_ascii_lowercase(), locale.language))
+            .unwrap_or_default(),
+        "bing" => {
+            let country = region.map(|r| format!("&cc={}", r)).unwrap_or_default();
+            format!("&setlang={}{}", locale.tag(), country)
+        }
+        "google" => {
+            let country = region.map(|r| format!("&gl={}", r)).unwrap_or_default();
+            format!("&hl={}{}", locale.language, country)
+        }
+        _ => String::new(),
+    };
+    url + &params
 }
 
 #[cfg(test)]
@@ -51,9 +91,69 @@ mod tests {
         assert!(url.starts_with("https://www.bing.com/search?q="));
     }
 
+    #[test]
+    fn test_localized_search_url() {
+        let de = Locale::parse("de-DE");
+        assert_eq!(
+            localized_search_url("google", "wetter", de.as_ref()),
+            "https://www.google.com/search?q=wetter&hl=de&gl=DE"
+        );
+        assert_eq!(
+            localized_search_url("ddg", "wetter", de.as_ref()),
+            "https://duckduckgo.com/?q=wetter&kl=de-de"
+        );
+        assert_eq!(
+            localized_search_url("bing", "wetter", de.as_ref()),
+            "https://www.bing.com/search?q=wetter&setlang=de-DE&cc=DE"
+        );
+        // DuckDuckGo only has regional settings
+        assert_eq!(
+            localized_search_url("ddg", "tenki", Locale::parse("ja").as_ref()),
+            "https://duckduckgo.com/?q=tenki"
+        );
+        assert_eq!(
+            localized_search_url("google", "hello", None),
+            search_url("google", "hello")
+        );
+    }
+
     #[test]
     fn test_search_url_unknown_defaults_to_google() {
         let url = search_url("unknown_engine", "test query");
         assert!(url.starts_with("https://www.google.com/search?q="));
     }
+
+    #[test]
+    fn test_fallback_uses_preferred_provider() {
+        let config: BunnylolConfig = toml::from_str(
+            r#"
+default_search = "ddg"
+locale = "de-DE"
+
+[providers]
+search = "lite"
+
+[providers.custom.lite]
+category = "search"
+url = "https://lite.duckduckgo.com/lite/?q={}"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            fallback_search_url(Some(&config), "rust lang"),
+            "https://lite.duckduckgo.com/lite/?q=rust%20lang"
+        );
+        let legacy = BunnylolConfig {
+            default_search: "bing".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            fallback_search_url(Some(&legacy), "rust"),
+            "https://www.bing.com/search?q=rust"
+        );
+        assert_eq!(
+            fallback_search_url(None, "rust"),
+            "https://www.google.com/search?q=rust"
+        );
+    }
 }
@@ -0,0 +1,105 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+/// `@handle` prefix shorthand for social profiles
+/// Supports:
+/// - @[handle] -> https://twitter.com/[handle]
+/// - @[handle] ig|instagram -> https://www.instagram.com/[handle]
+/// - @[handle] threads -> https://www.threads.net/@[handle]
+/// - @[handle] gh|github -> https://github.com/[handle]
+/// - @[handle] fb|facebook -> https://www.facebook.com/[handle]
+/// - @[handle] yt|youtube -> https://www.youtube.com/@[handle]
+/// - @[handle] tw|twitter|x -> https://twitter.com/[handle]
+use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo, PrefixHandler};
+use crate::commands::{
+    FacebookCommand, GitHubCommand, InstagramCommand, ThreadsCommand, TwitterCommand,
+};
+use crate::utils::url_encoding::build_path_url;
+
+pub struct SocialHandlePrefix;
+
+impl PrefixHandler for SocialHandlePrefix {
+    const PREFIX: &'static str = "@";
+    const EXAMPLES: &'static [&'static str] = &["@zuck", "@zuck ig", "@facebook gh"];
+
+    fn process_prefix(full_args: &str) -> String {
+        let mut words = full_args.split_whitespace();
+        let word = words.next().unwrap_or(full_args);
+        let handle = word.strip_prefix(Self::PREFIX).unwrap_or(word);
+
+        match words
+            .next()
+            .map(|platform| platform.to_lowercase())
+            .as_deref()
+        {
+            Some("ig" | "instagram") => InstagramCommand::process_args(&format!("ig @{}", handle)),
+            Some("threads") => ThreadsCommand::process_args(&format!("threads @{}", handle)),
+            Some("gh" | "github") => GitHubCommand::process_args(&format!("gh @{}", handle)),
+            Some("fb" | "facebook") => FacebookCommand::process_args(&format!("fb {}", handle)),
+            Some("yt" | "youtube") => {
+                build_path_url("https://www.youtube.com", &format!("@{}", handle))
+            }
+            // X/Twitter is where `@handle` comes from, so it's the default
+            _ => TwitterCommand::process_args(&format!("tw @{}", handle)),
+        }
+    }
+
+    fn get_info() -> BunnylolCommandInfo {
+        BunnylolCommandInfo::new(
+            &["@<handle>"],
+            "Open a social profile on X/Twitter, or on ig, threads, gh, fb or yt",
+            &Self::EXAMPLES.join("  or  "),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_social_handle_defaults_to_twitter() {
+        assert_eq!(
+            SocialHandlePrefix::process_prefix("@zuck"),
+            "https://twitter.com/zuck"
+        );
+        assert_eq!(
+            SocialHandlePrefix::process_prefix("@zuck unknown"),
+            "https://twitter.com/zuck"
+        );
+    }
+
+    #[test]
+    fn test_social_handle_platforms() {
+        assert_eq!(
+            SocialHandlePrefix::process_prefix("@zuck ig"),
+            "https://www.instagram.com/zuck"
+        );
+        assert_eq!(
+            SocialHandlePrefix::process_prefix("@zuck threads"),
+            "https://www.threads.net/@zuck"
+        );
+        assert_eq!(
+            SocialHandlePrefix::process_prefix("@facebook GH"),
+            "https://github.com/facebook"
+        );
+        assert_eq!(
+            SocialHandlePrefix::process_prefix("@zuck fb"),
+            "https://www.facebook.com/zuck"
+        );
+        assert_eq!(
+            SocialHandlePrefix::process_prefix("@mkbhd yt"),
+            "https://www.youtube.com/@mkbhd"
+        );
+    }
+
+    #[test]
+    fn test_social_handle_requires_a_handle() {
+        assert!(SocialHandlePrefix::matches("@zuck"));
+        assert!(!SocialHandlePrefix::matches("@"));
+    }
+}
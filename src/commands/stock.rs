@@ -5,86 +5,21 @@
  * LICENSE file in the root directory of this source tree.
  */
 
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo, PrefixHandler};
+use crate::commands::providers::{self, Provider, ProviderCategory};
 use crate::config::get_global_config;
-use crate::utils::url_encoding::encode_url_special_char;
-use std::collections::HashMap;
-use std::sync::LazyLock;
-
-/// Stock provider configuration
-struct StockInfoProvider {
-    aliases: &'static [&'static str],
-    homepage: &'static str,
-    ticker_url_template: &'static str,
-    needs_encoding: bool, // Whether to percent-encode the ticker
-}
-
-/// supported stock providers
-static PROVIDERS: &[StockInfoProvider] = &[
-    StockInfoProvider {
-        aliases: &["yahoo"],
-        homepage: "https://finance.yahoo.com/",
-        ticker_url_template: "https://finance.yahoo.com/quote/{}/",
-        needs_encoding: true,
-    },
-    StockInfoProvider {
-        aliases: &["finviz"],
-        homepage: "https://finviz.com/",
-        ticker_url_template: "https://finviz.com/quote.ashx?t={}",
-        needs_encoding: false,
-    },
-    StockInfoProvider {
-        aliases: &["tradingview", "tv"],
-        homepage: "https://www.tradingview.com/",
-        ticker_url_template: "https://www.tradingview.com/symbols/{}/",
-        needs_encoding: false,
-    },
-    StockInfoProvider {
-        aliases: &["google", "gf"],
-        homepage: "https://www.google.com/finance/",
-        ticker_url_template: "https://www.google.com/finance/quote/{}",
-        needs_encoding: false,
-    },
-    StockInfoProvider {
-        aliases: &["investing", "inv"],
-        homepage: "https://www.investing.com/",
-        ticker_url_template: "https://www.investing.com/search/?q={}",
-        needs_encoding: true,
-    },
-];
-
-/// lookup table (alias -> provider) for stocks
-static PROVIDER_LOOKUP: LazyLock<HashMap<&'static str, &'static StockInfoProvider>> =
-    LazyLock::new(|| {
-        let mut map = HashMap::new();
-        for p in PROVIDERS {
-            for &alias in p.aliases {
-                map.insert(alias, p);
-            }
-        }
-        map
-    });
 
+/// Stock quote command, opening the preferred `stock` provider (see
+/// [`providers`]): `[providers] stock`, else `stock_provider`.
 pub struct StockCommand;
 
 impl StockCommand {
-    fn get_provider(name: &str) -> &'static StockInfoProvider {
-        match PROVIDER_LOOKUP.get(name).copied() {
-            Some(provider) => provider,
-            None => {
-                eprintln!(
-                    "Warning: Unknown stock provider '{}', using yahoo as fallback",
-                    name
-                );
-                &PROVIDERS[0] // Default to yahoo (first provider)
-            }
-        }
+    fn get_provider(name: &str) -> Provider {
+        providers::find_or_default(ProviderCategory::Stock, name, get_global_config().as_ref())
     }
 
     fn configured_provider() -> String {
-        get_global_config()
-            .map(|cfg| cfg.stock_provider)
-            .unwrap_or_else(|| "yahoo".to_string())
+        providers::preferred(ProviderCategory::Stock, get_global_config().as_ref()).name
     }
 
     /// Process a ticker with $ prefix (e.g., "$META")
@@ -97,8 +32,7 @@ impl StockCommand {
     fn process_ticker_with_provider(ticker_with_dollar: &str, provider_name: &str) -> String {
         if ticker_with_dollar.len() <= 1 {
             // No ticker - return provider homepage
-            let provider = Self::get_provider(provider_name);
-            return provider.homepage.to_string();
+            return Self::get_provider(provider_name).homepage;
         }
 
         let ticker = &ticker_with_dollar[1..];
@@ -107,15 +41,7 @@ impl StockCommand {
 
     /// Build stock URL for a specific provider
     fn build_url_for_provider(ticker: &str, provider_name: &str) -> String {
-        let provider = Self::get_provider(provider_name);
-
-        let ticker_str = if provider.needs_encoding {
-            encode_url_special_char(ticker)
-        } else {
-            ticker.to_string()
-        };
-
-        provider.ticker_url_template.replace("{}", &ticker_str)
+        Self::get_provider(provider_name).url(ticker)
     }
 
     /// Testable version of process_args that takes an explicit provider name
@@ -123,8 +49,7 @@ impl StockCommand {
         let query = Self::get_command_args(args);
 
         if query.is_empty() {
-            let provider = Self::get_provider(provider_name);
-            return provider.homepage.to_string();
+            return Self::get_provider(provider_name).homepage;
         }
 
         let (provider_override, ticker) = Self::parse_provider_and_ticker(query);
@@ -140,7 +65,13 @@ impl StockCommand {
         if parts.len() >= 2 {
             let potential_provider = parts[0].to_lowercase();
 
-            if PROVIDER_LOOKUP.contains_key(potential_provider.as_str()) {
+            if providers::find(
+                ProviderCategory::Stock,
+                &potential_provider,
+                get_global_config().as_ref(),
+            )
+            .is_some()
+            {
                 // Return provider and rest of query, ticker starts after first whitespace + provider length
                 let ticker_start = query
                     .find(char::is_whitespace)
@@ -164,9 +95,31 @@ impl BunnylolCommand for StockCommand {
 
     fn get_info() -> BunnylolCommandInfo {
         BunnylolCommandInfo::new(
-            &["stock", "stocks", "finance", "$<ticker>"],
+            Self::BINDINGS,
             "Look up stock prices on Yahoo Finance, Finviz, TradingView, Google Finance, or Investing.com",
-            "stock META  or  stock finviz META  or  $META",
+            "stock META  or  stock finviz META",
+        )
+    }
+}
+
+/// `$TICKER` prefix shorthand for [`StockCommand`], using the configured
+/// stock provider (e.g. `$META` -> https://finance.yahoo.com/quote/META/)
+pub struct StockTickerPrefix;
+
+impl PrefixHandler for StockTickerPrefix {
+    const PREFIX: &'static str = "$";
+    const EXAMPLES: &'static [&'static str] = &["$META", "$AAPL"];
+
+    fn process_prefix(full_args: &str) -> String {
+        let ticker = full_args.split_whitespace().next().unwrap_or(full_args);
+        StockCommand::process_ticker(ticker)
+    }
+
+    fn get_info() -> BunnylolCommandInfo {
+        BunnylolCommandInfo::new(
+            &["$<ticker>"],
+            "Stock ticker shorthand, opened with your configured stock provider",
+            &Self::EXAMPLES.join("  or  "),
         )
     }
 }
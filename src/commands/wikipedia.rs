@@ -5,25 +5,48 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use crate::commands::Locale;
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::providers::{self, ProviderCategory};
+use crate::config::{CommandConfig, get_global_config};
 use crate::utils::url_encoding::encode_url;
 
 pub struct WikipediaCommand;
 
-impl BunnylolCommand for WikipediaCommand {
-    const BINDINGS: &'static [&'static str] = &["wiki", "wikipedia"];
-
-    fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
+impl WikipediaCommand {
+    /// Build the URL for `query` (the input after the command word) on the
+    /// Wikipedia for its inline `xx:` language (`fr:Paris`), else the
+    /// configured locale's language, else English. With another
+    /// `[providers] encyclopedia`, the query goes to that site instead.
+    pub fn process_query(query: &str, settings: Option<&CommandConfig>) -> String {
+        let config = get_global_config();
+        let provider = providers::preferred(ProviderCategory::Encyclopedia, config.as_ref());
+        if !provider.is_default() {
+            return provider.url(query);
+        }
+        let (locale, query) = Locale::resolve(query, config.as_ref(), settings);
+        let language = locale.map_or_else(|| "en".to_string(), |locale| locale.language);
         if query.is_empty() {
-            return "https://en.wikipedia.org/".to_string();
+            return format!("https://{}.wikipedia.org/", language);
         }
         let encoded_query = encode_url(query);
         format!(
-            "https://en.wikipedia.org/w/index.php?search={}&title=Special%3ASearch&ns0=1",
-            encoded_query
+            "https://{}.wikipedia.org/w/index.php?search={}&title=Special%3ASearch&ns0=1",
+            language, encoded_query
         )
     }
+}
+
+impl BunnylolCommand for WikipediaCommand {
+    const BINDINGS: &'static [&'static str] = &["wiki", "wikipedia"];
+
+    fn process_args(args: &str) -> String {
+        let config = get_global_config();
+        let settings = config
+            .as_ref()
+            .and_then(|c| c.command_settings("wikipedia"));
+        Self::process_query(Self::get_command_args(args), settings)
+    }
 
     fn get_info() -> BunnylolCommandInfo {
         BunnylolCommandInfo::new(
@@ -31,6 +54,7 @@ impl BunnylolCommand for WikipediaCommand {
             "Search on Wikipedia",
             "wiki rust programming",
         )
+        .with_examples(&["wiki fr:Paris"])
     }
 }
 
@@ -69,4 +93,24 @@ mod tests {
             "https://en.wikipedia.org/"
         );
     }
+
+    #[test]
+    fn test_wikipedia_language() {
+        assert_eq!(
+            WikipediaCommand::process_args("wiki fr:Paris"),
+            "https://fr.wikipedia.org/w/index.php?search=Paris&title=Special%3ASearch&ns0=1"
+        );
+        let settings = CommandConfig {
+            locale: Some("de-DE".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            WikipediaCommand::process_query("", Some(&settings)),
+            "https://de.wikipedia.org/"
+        );
+        assert_eq!(
+            WikipediaCommand::process_query("ja: Tokyo Tower", Some(&settings)),
+            "https://ja.wikipedia.org/w/index.php?search=Tokyo%20Tower&title=Special%3ASearch&ns0=1"
+        );
+    }
 }
@@ -9,7 +9,12 @@
 /// Supports:
 /// - yt/youtube -> https://youtube.com/
 /// - yt [search terms] -> https://www.youtube.com/results?search_query=[search terms]
+///
+/// With another `[providers] video` (e.g. an Invidious instance), `yt` and
+/// searches open that site; `studio` and `subs` stay on YouTube.
 use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::providers::{self, ProviderCategory};
+use crate::config::get_global_config;
 use crate::utils::url_encoding::build_search_url;
 
 pub struct YouTubeCommand;
@@ -19,14 +24,13 @@ impl BunnylolCommand for YouTubeCommand {
 
     fn process_args(args: &str) -> String {
         let query = Self::get_command_args(args);
-        if query.is_empty() {
-            "https://youtube.com/".to_string()
-        } else if query == "studio" {
-            "https://studio.youtube.com/".to_string()
-        } else if query == "subscriptions" || query == "subs" {
-            "https://www.youtube.com/feed/subscriptions".to_string()
-        } else {
-            build_search_url("https://www.youtube.com/results", "search_query", query)
+        let provider = providers::preferred(ProviderCategory::Video, get_global_config().as_ref());
+        match query {
+            "studio" => "https://studio.youtube.com/".to_string(),
+            "subscriptions" | "subs" => "https://www.youtube.com/feed/subscriptions".to_string(),
+            _ if !provider.is_default() => provider.url(query),
+            "" => "https://youtube.com/".to_string(),
+            _ => build_search_url("https://www.youtube.com/results", "search_query", query),
         }
     }
 
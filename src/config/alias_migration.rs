@@ -33,13 +33,16 @@ pub(super) fn migrate_aliases_to_user_bindings(
 }
 
 #[derive(Debug, Clone, Copy)]
-struct TomlTableSection {
-    header_start: usize,
-    body_start: usize,
-    end: usize,
+pub(super) struct TomlTableSection {
+    pub(super) header_start: usize,
+    pub(super) body_start: usize,
+    pub(super) end: usize,
 }
 
-fn find_toml_table_section(contents: &str, table_name: &str) -> Option<TomlTableSection> {
+pub(super) fn find_toml_table_section(
+    contents: &str,
+    table_name: &str,
+) -> Option<TomlTableSection> {
     let sections = toml_table_headers(contents);
 
     sections
@@ -56,7 +59,7 @@ fn find_toml_table_section(contents: &str, table_name: &str) -> Option<TomlTable
         })
 }
 
-fn toml_table_headers(contents: &str) -> Vec<(&str, usize, usize)> {
+pub(super) fn toml_table_headers(contents: &str) -> Vec<(&str, usize, usize)> {
     let mut sections = Vec::new();
     let mut offset = 0;
 
@@ -118,7 +121,7 @@ fn find_top_level_aliases_key(contents: &str) -> Option<(usize, usize)> {
     None
 }
 
-fn toml_line_key_is(trimmed_line: &str, expected: &str) -> bool {
+pub(super) fn toml_line_key_is(trimmed_line: &str, expected: &str) -> bool {
     let Some((raw_key, _)) = trimmed_line.split_once('=') else {
         return false;
     };
@@ -126,12 +129,15 @@ fn toml_line_key_is(trimmed_line: &str, expected: &str) -> bool {
     raw_key == expected || raw_key == format!("\"{}\"", expected).as_str()
 }
 
-fn toml_line_is_comment_or_blank(line: &str) -> bool {
+pub(super) fn toml_line_is_comment_or_blank(line: &str) -> bool {
     let trimmed = line.trim();
     trimmed.is_empty() || trimmed.starts_with('#')
 }
 
-fn toml_section_lines(contents: &str, section: TomlTableSection) -> Vec<(usize, usize, &str)> {
+pub(super) fn toml_section_lines(
+    contents: &str,
+    section: TomlTableSection,
+) -> Vec<(usize, usize, &str)> {
     let body = &contents[section.body_start..section.end];
     let mut offset = section.body_start;
     let mut lines = Vec::new();
@@ -146,7 +152,10 @@ fn toml_section_lines(contents: &str, section: TomlTableSection) -> Vec<(usize,
     lines
 }
 
-fn toml_section_last_entry_end(contents: &str, section: TomlTableSection) -> Option<usize> {
+pub(super) fn toml_section_last_entry_end(
+    contents: &str,
+    section: TomlTableSection,
+) -> Option<usize> {
     toml_section_lines(contents, section)
         .into_iter()
         .filter(|(_, _, line)| !toml_line_is_comment_or_blank(line))
@@ -292,7 +301,7 @@ fn migrate_aliases_to_user_bindings_toml(
     Some(migrated)
 }
 
-fn write_config_atomically(config_path: &Path, contents: &str) -> Result<(), String> {
+pub(super) fn write_config_atomically(config_path: &Path, contents: &str) -> Result<(), String> {
     let parent = config_path
         .parent()
         .ok_or_else(|| format!("Config path {:?} has no parent directory", config_path))?;
@@ -0,0 +1,583 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use argon2::password_hash::SaltString;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::BunnylolConfig;
+use super::user_bindings::{escape_toml_string, format_toml_key};
+
+/// What a signed-in user (or an anonymous request) may do on the server.
+/// Each role includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Resolve queries, open the landing page and docs mirrors.
+    Resolve,
+    /// Also read the server's command history.
+    History,
+    /// Also edit the config (`/api/user_bindings`).
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Resolve => "resolve",
+            Role::History => "history",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+/// Which routes need a signed-in user (`[auth] protect`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protect {
+    /// Only config editing. Anyone may resolve and read history.
+    Admin,
+    /// History and config editing. Anyone may resolve.
+    #[default]
+    History,
+    /// Everything except `/health`.
+    All,
+}
+
+impl Protect {
+    /// The role of a request without (valid) credentials.
+    pub fn anonymous_role(self) -> Option<Role> {
+        match self {
+            Protect::Admin => Some(Role::History),
+            Protect::History => Some(Role::Resolve),
+            Protect::All => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Protect::Admin => "admin",
+            Protect::History => "history",
+            Protect::All => "all",
+        }
+    }
+}
+
+/// Who may use the server (`[auth]`).
+///
+/// ```toml
+/// [auth]
+/// protect = "all"     # "admin", "history" (the default) or "all"
+///
+/// [auth.tokens]       # Authorization: Bearer <token>
+/// deploy-bot = { token = "0a1b2c...", role = "admin" }
+///
+/// [auth.users]        # HTTP basic auth; hashes from `bunnylol hash-password`
+/// alice = { password_hash = "$argon2id$v=19$...", role = "history" }
+///
+/// [auth.proxy]        # identity from a reverse proxy such as oauth2-proxy
+/// header = "X-Auth-Request-Email"
+/// trusted = ["127.0.0.1", "172.16.0.0/12"]
+/// default_role = "resolve"
+/// roles = { "alice@corp.example" = "admin" }
+/// ```
+///
+/// `[server] admin_token` keeps working as an admin bearer token.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuthConfig {
+    /// Which routes need a signed-in user.
+    #[serde(default)]
+    pub protect: Protect,
+
+    /// Static bearer tokens, by name.
+    #[serde(default)]
+    pub tokens: HashMap<String, TokenCredential>,
+
+    /// HTTP basic auth users, by user name.
+    #[serde(default)]
+    pub users: HashMap<String, UserCredential>,
+
+    /// Identity headers set by a trusted reverse proxy.
+    #[serde(default)]
+    pub proxy: Option<ProxyAuth>,
+}
+
+/// A `[auth.tokens]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenCredential {
+    pub token: String,
+    pub role: Role,
+}
+
+/// A `[auth.users]` entry. `password_hash` is an Argon2 PHC string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserCredential {
+    pub password_hash: String,
+    pub role: Role,
+}
+
+/// `[auth.proxy]`: trust `header` as the user name on requests from a
+/// `trusted` address. An empty `trusted` means `[server] trusted_proxies`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyAuth {
+    pub header: String,
+    #[serde(default)]
+    pub trusted: Vec<IpRange>,
+    #[serde(default = "default_proxy_role")]
+    pub default_role: Role,
+    #[serde(default)]
+    pub roles: HashMap<String, Role>,
+}
+
+fn default_proxy_role() -> Role {
+    Role::Resolve
+}
+
+/// Who opened a connection to the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Peer {
+    Ip(IpAddr),
+    /// A process connected to the server's Unix domain socket.
+    Unix,
+}
+
+impl From<IpAddr> for Peer {
+    fn from(ip: IpAddr) -> Self {
+        Peer::Ip(ip)
+    }
+}
+
+impl std::fmt::Display for Peer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Peer::Ip(ip) => write!(f, "{}", ip),
+            Peer::Unix => write!(f, "unix"),
+        }
+    }
+}
+
+/// An IP address, a network in CIDR notation ("10.0.0.0/8"), or "unix" for
+/// clients of the server's Unix domain socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    /// `None` for "unix".
+    net: Option<(IpAddr, u8)>,
+}
+
+impl IpRange {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let Some((addr, prefix)) = self.net else {
+            return false;
+        };
+        match (addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+
+    pub fn contains_peer(&self, peer: Peer) -> bool {
+        match peer {
+            Peer::Ip(ip) => self.contains(ip),
+            Peer::Unix => self.net.is_none(),
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "unix" {
+            return Ok(IpRange { net: None });
+        }
+        let invalid = || format!("'{}' is not an IP address, CIDR network or \"unix\"", s);
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr = IpAddr::from_str(addr)
+            .map_err(|_| invalid())?
+            .to_canonical();
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse()
+                .ok()
+                .filter(|prefix| *prefix <= max)
+                .ok_or_else(invalid)?,
+            None => max,
+        };
+        Ok(IpRange {
+            net: Some((addr, prefix)),
+        })
+    }
+}
+
+impl std::fmt::Display for IpRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((addr, prefix)) = self.net else {
+            return write!(f, "unix");
+        };
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix == max {
+            write!(f, "{}", addr)
+        } else {
+            write!(f, "{}/{}", addr, prefix)
+        }
+    }
+}
+
+impl Serialize for IpRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IpRange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+fn sorted_keys<V>(map: &HashMap<String, V>) -> Vec<&String> {
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+    keys
+}
+
+/// Compare without returning early, so response times don't leak how much
+/// of a secret was right.
+pub(crate) fn secrets_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Hash `password` for a `[auth.users]` entry.
+pub fn hash_password(password: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Failed to hash password: {}", e))
+}
+
+impl AuthConfig {
+    /// Whether any way to sign in is configured.
+    pub fn has_credentials(&self) -> bool {
+        !self.tokens.is_empty() || !self.users.is_empty() || self.proxy.is_some()
+    }
+
+    /// The name and role of the `[auth.tokens]` entry for `token`.
+    pub fn token_identity(&self, token: &str) -> Option<(&str, Role)> {
+        // Check every entry so the time taken doesn't depend on which matched
+        self.tokens
+            .iter()
+            .filter(|(_, credential)| secrets_match(token, &credential.token))
+            .map(|(name, credential)| (name.as_str(), credential.role))
+            .last()
+    }
+
+    /// The role of `user` if `password` is theirs.
+    pub fn check_password(&self, user: &str, password: &str) -> Option<Role> {
+        let credential = self.users.get(user)?;
+        let hash = PasswordHash::new(&credential.password_hash).ok()?;
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .ok()
+            .map(|_| credential.role)
+    }
+
+    /// The role of a user named by the proxy header on a request from `peer`,
+    /// or `None` if `peer` isn't a trusted proxy. `trusted_proxies` (from
+    /// `[server]`) is used when `[auth.proxy] trusted` is empty.
+    pub fn proxy_role(
+        &self,
+        peer: impl Into<Peer>,
+        user: &str,
+        trusted_proxies: &[IpRange],
+    ) -> Option<Role> {
+        let peer = peer.into();
+        let proxy = self.proxy.as_ref()?;
+        let trusted = if proxy.trusted.is_empty() {
+            trusted_proxies
+        } else {
+            &proxy.trusted
+        };
+        if user.is_empty() || !trusted.iter().any(|range| range.contains_peer(peer)) {
+            return None;
+        }
+        Some(proxy.roles.get(user).copied().unwrap_or(proxy.default_role))
+    }
+
+    pub(super) fn to_toml(&self) -> String {
+        let mut sections = vec![format!("[auth]\nprotect = \"{}\"", self.protect.as_str())];
+        if !self.tokens.is_empty() {
+            let lines: Vec<String> = sorted_keys(&self.tokens)
+                .into_iter()
+                .map(|name| {
+                    let credential = &self.tokens[name];
+                    format!(
+                        "{} = {{ token = \"{}\", role = \"{}\" }}",
+                        format_toml_key(name),
+                        escape_toml_string(&credential.token),
+                        credential.role.as_str()
+                    )
+                })
+                .collect();
+            sections.push(format!("[auth.tokens]\n{}", lines.join("\n")));
+        }
+        if !self.users.is_empty() {
+            let lines: Vec<String> = sorted_keys(&self.users)
+                .into_iter()
+                .map(|name| {
+                    let credential = &self.users[name];
+                    format!(
+                        "{} = {{ password_hash = \"{}\", role = \"{}\" }}",
+                        format_toml_key(name),
+                        escape_toml_string(&credential.password_hash),
+                        credential.role.as_str()
+                    )
+                })
+                .collect();
+            sections.push(format!("[auth.users]\n{}", lines.join("\n")));
+        }
+        if let Some(proxy) = &self.proxy {
+            let trusted: Vec<String> = proxy
+                .trusted
+                .iter()
+                .map(|range| format!("\"{}\"", range))
+                .collect();
+            let mut lines = vec![
+                "[auth.proxy]".to_string(),
+                format!("header = \"{}\"", escape_toml_string(&proxy.header)),
+            ];
+            if !trusted.is_empty() {
+                lines.push(format!("trusted = [{}]", trusted.join(", ")));
+            }
+            lines.push(format!(
+                "default_role = \"{}\"",
+                proxy.default_role.as_str()
+            ));
+            if !proxy.roles.is_empty() {
+                let roles: Vec<String> = sorted_keys(&proxy.roles)
+                    .into_iter()
+                    .map(|user| {
+                        format!(
+                            "{} = \"{}\"",
+                            format_toml_key(user),
+                            proxy.roles[user].as_str()
+                        )
+                    })
+                    .collect();
+                lines.push(format!("roles = {{ {} }}", roles.join(", ")));
+            }
+            sections.push(lines.join("\n"));
+        }
+        sections.join("\n\n")
+    }
+}
+
+impl BunnylolConfig {
+    /// Check `[auth]` credentials. Called when the config is loaded; an
+    /// error here is fatal.
+    pub fn validate_auth(&self) -> Result<(), String> {
+        for (name, credential) in &self.auth.tokens {
+            if credential.token.len() < 16 {
+                return Err(format!(
+                    "[auth.tokens] '{}' must be at least 16 characters long",
+                    name
+                ));
+            }
+        }
+        for (user, credential) in &self.auth.users {
+            if user.contains(':') {
+                return Err(format!(
+                    "[auth.users] user name '{}' can't contain ':'",
+                    user
+                ));
+            }
+            PasswordHash::new(&credential.password_hash).map_err(|_| {
+                format!(
+                    "[auth.users] '{}' password_hash must be an Argon2 hash from `bunnylol hash-password`",
+                    user
+                )
+            })?;
+        }
+        if let Some(proxy) = &self.auth.proxy {
+            if !is_header_name(&proxy.header) {
+                return Err(format!(
+                    "[auth.proxy] header '{}' is not a valid header name",
+                    proxy.header
+                ));
+            }
+            if proxy.trusted.is_empty() && self.server.trusted_proxies.is_empty() {
+                return Err(
+                    "[auth.proxy] trusted must list the proxy's addresses (or set [server] trusted_proxies); otherwise anyone could send the header"
+                        .to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_range_contains() {
+        let range: IpRange = "172.16.0.0/12".parse().unwrap();
+        assert!(range.contains("172.20.1.2".parse().unwrap()));
+        assert!(range.contains("::ffff:172.20.1.2".parse().unwrap()));
+        assert!(!range.contains("172.32.0.1".parse().unwrap()));
+        assert!(!range.contains("::1".parse().unwrap()));
+
+        let single: IpRange = "::1".parse().unwrap();
+        assert!(single.contains("::1".parse().unwrap()));
+        assert_eq!(single.to_string(), "::1");
+        let all: IpRange = "0.0.0.0/0".parse().unwrap();
+        assert!(all.contains("8.8.8.8".parse().unwrap()));
+
+        for invalid in ["localhost", "10.0.0.0/33", "10.0.0.0/", "::1/129"] {
+            assert!(invalid.parse::<IpRange>().is_err(), "{}", invalid);
+        }
+
+        // Unix socket clients are only trusted when listed
+        let unix: IpRange = "unix".parse().unwrap();
+        assert_eq!(unix.to_string(), "unix");
+        assert!(unix.contains_peer(Peer::Unix));
+        assert!(!unix.contains("127.0.0.1".parse().unwrap()));
+        assert!(!single.contains_peer(Peer::Unix));
+    }
+
+    #[test]
+    fn test_credentials_and_roles() {
+        let hash = hash_password("hunter2").unwrap();
+        let cfg: BunnylolConfig = toml::from_str(&format!(
+            r#"
+            [auth]
+            protect = "all"
+
+            [auth.tokens]
+            bot = {{ token = "0123456789abcdef", role = "admin" }}
+
+            [auth.users]
+            alice = {{ password_hash = "{}", role = "history" }}
+
+            [auth.proxy]
+            header = "X-Forwarded-User"
+            trusted = ["127.0.0.1"]
+            roles = {{ bob = "admin" }}
+            "#,
+            hash
+        ))
+        .unwrap();
+        cfg.validate_auth().unwrap();
+        let auth = &cfg.auth;
+
+        assert_eq!(auth.protect.anonymous_role(), None);
+        assert_eq!(
+            auth.token_identity("0123456789abcdef"),
+            Some(("bot", Role::Admin))
+        );
+        assert_eq!(auth.token_identity("0123456789abcdeX"), None);
+        assert_eq!(auth.check_password("alice", "hunter2"), Some(Role::History));
+        assert_eq!(auth.check_password("alice", "hunter3"), None);
+        assert_eq!(auth.check_password("mallory", "hunter2"), None);
+
+        let proxy: IpAddr = "127.0.0.1".parse().unwrap();
+        assert_eq!(auth.proxy_role(proxy, "bob", &[]), Some(Role::Admin));
+        assert_eq!(auth.proxy_role(proxy, "carol", &[]), Some(Role::Resolve));
+        assert_eq!(
+            auth.proxy_role("10.0.0.1".parse::<IpAddr>().unwrap(), "bob", &[]),
+            None
+        );
+
+        assert!(Role::Admin > Role::History && Role::History > Role::Resolve);
+
+        let parsed: BunnylolConfig = toml::from_str(&auth.to_toml()).unwrap();
+        assert_eq!(&parsed.auth, auth);
+    }
+
+    #[test]
+    fn test_validate_auth() {
+        for (body, expected) in [
+            (
+                "[auth.tokens]\nbot = { token = \"short\", role = \"admin\" }",
+                "at least 16 characters",
+            ),
+            (
+                "[auth.users]\nalice = { password_hash = \"hunter2\", role = \"admin\" }",
+                "Argon2 hash",
+            ),
+            (
+                "[auth.proxy]\nheader = \"X-User\"\ntrusted = []",
+                "trusted must list",
+            ),
+            (
+                "[auth.proxy]\nheader = \"X User\"\ntrusted = [\"::1\"]",
+                "not a valid header name",
+            ),
+        ] {
+            let cfg: BunnylolConfig = toml::from_str(body).unwrap();
+            let err = cfg.validate_auth().unwrap_err();
+            assert!(err.contains(expected), "{}: {}", body, err);
+        }
+
+        // `trusted` falls back to [server] trusted_proxies
+        let cfg: BunnylolConfig = toml::from_str(
+            "[server]\ntrusted_proxies = [\"10.0.0.0/8\"]\n[auth.proxy]\nheader = \"X-User\"",
+        )
+        .unwrap();
+        cfg.validate_auth().unwrap();
+        let proxy: IpAddr = "10.1.2.3".parse().unwrap();
+        assert_eq!(
+            cfg.auth
+                .proxy_role(proxy, "alice", &cfg.server.trusted_proxies),
+            Some(Role::Resolve)
+        );
+        assert_eq!(cfg.auth.proxy_role(proxy, "alice", &[]), None);
+
+        assert!(toml::from_str::<BunnylolConfig>("[auth]\nprotect = \"everything\"").is_err());
+        assert!(
+            toml::from_str::<BunnylolConfig>(
+                "[auth.proxy]\nheader = \"X-User\"\ntrusted = [\"proxy.local\"]"
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,475 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use serde::Serialize;
+
+use super::alias_migration::{
+    find_toml_table_section, toml_line_is_comment_or_blank, toml_line_key_is,
+    toml_section_last_entry_end, toml_section_lines, toml_table_headers, write_config_atomically,
+};
+use super::user_bindings::{format_toml_key, format_user_binding_toml};
+use super::{
+    BindingConflict, BunnylolConfig, ConfigReloader, UserBinding, fold_aliases_into_user_bindings,
+    init_global_config, shadowed_aliases,
+};
+
+/// A change to one `[user_bindings]` entry, made through the admin API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindingEdit {
+    /// Add a binding; fails if the name is taken.
+    Create(UserBinding),
+    /// Add or replace a binding.
+    Replace(UserBinding),
+    /// Remove a binding; fails if there is none.
+    Delete,
+}
+
+/// Why an edit to `[user_bindings]` was not made. Nothing is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindingEditError {
+    /// The server was started without a config file.
+    NoConfigFile,
+    /// The file changed since it was read. Holds its current ETag.
+    Stale(String),
+    /// `Create` for a name that already has a binding.
+    Exists,
+    /// `Delete` for a name without a binding.
+    NotFound,
+    /// The edited config is invalid, or the binding would be shadowed by a
+    /// built-in.
+    Invalid(String),
+    /// Reading or writing the file failed.
+    Io(String),
+}
+
+impl std::fmt::Display for BindingEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BindingEditError::NoConfigFile => write!(f, "the server has no config file to edit"),
+            BindingEditError::Stale(_) => write!(
+                f,
+                "config.toml changed since it was read; reload the bindings and try again"
+            ),
+            BindingEditError::Exists => write!(f, "a binding with this name already exists"),
+            BindingEditError::NotFound => write!(f, "no binding with this name"),
+            BindingEditError::Invalid(e) | BindingEditError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// The `[user_bindings]` written in `config.toml` (legacy `[aliases]` are not
+/// included) and the ETag of the file they were read from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserBindingsSnapshot {
+    pub etag: String,
+    pub bindings: BTreeMap<String, UserBinding>,
+}
+
+/// A strong HTTP ETag for the contents of a config file (the first 16 hex
+/// digits of its SHA-256, quoted). Edits must name the ETag they were
+/// based on.
+pub fn config_etag(contents: &str) -> String {
+    let hash = crate::utils::sha256_hex(contents.as_bytes());
+    format!("\"{}\"", &hash[..16])
+}
+
+impl ConfigReloader {
+    /// Read the bindings in the config file along with its ETag.
+    pub fn user_bindings_snapshot(&self) -> Result<UserBindingsSnapshot, BindingEditError> {
+        let path = self
+            .config_path
+            .as_ref()
+            .ok_or(BindingEditError::NoConfigFile)?;
+        let contents = fs::read_to_string(path).map_err(|e| {
+            BindingEditError::Io(format!("Failed to read config file {:?}: {}", path, e))
+        })?;
+        let config = BunnylolConfig::parse(&contents, path).map_err(BindingEditError::Invalid)?;
+        Ok(UserBindingsSnapshot {
+            etag: config_etag(&contents),
+            bindings: config.user_bindings.into_iter().collect(),
+        })
+    }
+
+    /// Apply `edit` to the `name` entry of `[user_bindings]`, if the config
+    /// file still has the ETag `if_match`.
+    ///
+    /// Only that entry's line changes; comments and every other setting are
+    /// kept. The result is validated like a hand edit, and rejected if
+    /// `conflicts` reports `name` (a built-in would shadow it). The file is
+    /// replaced atomically and the new config is live on return.
+    pub fn edit_user_binding(
+        &self,
+        if_match: &str,
+        name: &str,
+        edit: BindingEdit,
+        conflicts: impl FnOnce(&BunnylolConfig) -> Vec<BindingConflict>,
+    ) -> Result<UserBindingsSnapshot, BindingEditError> {
+        let _guard = self.edit_lock.lock().expect("config edit lock poisoned");
+        let path = self
+            .config_path
+            .as_ref()
+            .ok_or(BindingEditError::NoConfigFile)?;
+        let contents = fs::read_to_string(path).map_err(|e| {
+            BindingEditError::Io(format!("Failed to read config file {:?}: {}", path, e))
+        })?;
+        let etag = config_etag(&contents);
+        if if_match != etag {
+            return Err(BindingEditError::Stale(etag));
+        }
+        let mut expected = BunnylolConfig::parse(&contents, path)
+            .map_err(BindingEditError::Invalid)?
+            .user_bindings;
+
+        let binding = match edit {
+            BindingEdit::Create(_) if expected.contains_key(name) => {
+                return Err(BindingEditError::Exists);
+            }
+            BindingEdit::Create(binding) | BindingEdit::Replace(binding) => {
+                validate_binding_name(name).map_err(BindingEditError::Invalid)?;
+                expected.insert(name.to_string(), binding.clone());
+                Some(binding)
+            }
+            BindingEdit::Delete => {
+                expected.remove(name).ok_or(BindingEditError::NotFound)?;
+                None
+            }
+        };
+
+        let updated = edit_user_binding_toml(&contents, name, binding.as_ref())
+            .map_err(BindingEditError::Invalid)?;
+        let mut config =
+            BunnylolConfig::parse(&updated, path).map_err(BindingEditError::Invalid)?;
+        if config.user_bindings != expected {
+            return Err(BindingEditError::Invalid(format!(
+                "'{}' can't be edited in place in {}; edit the file by hand",
+                name,
+                path.display()
+            )));
+        }
+        config.dropped_aliases = shadowed_aliases(&config);
+        fold_aliases_into_user_bindings(&mut config);
+        if binding.is_some() && conflicts(&config).iter().any(|c| c.name == name) {
+            return Err(BindingEditError::Invalid(format!(
+                "'{}' is a built-in command, which wins over a binding; set \"override\": true to shadow it",
+                name
+            )));
+        }
+
+        write_config_atomically(path, &updated).map_err(BindingEditError::Io)?;
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+        *self.config.write().expect("config reloader lock poisoned") = config.clone();
+        *self
+            .modified
+            .write()
+            .expect("config reloader lock poisoned") = modified;
+        init_global_config(config);
+
+        println!("Updated user binding '{}' in {}", name, path.display());
+        Ok(UserBindingsSnapshot {
+            etag: config_etag(&updated),
+            bindings: expected.into_iter().collect(),
+        })
+    }
+}
+
+/// Names are matched against the first word of a query, so they can't be
+/// empty or contain whitespace.
+pub(super) fn validate_binding_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!(
+            "binding name {:?} must be a single word without spaces",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Set (`Some`) or remove (`None`) the `name` line of `[user_bindings]` in
+/// `contents`, leaving every other line alone. The section is created
+/// before the first table if it doesn't exist.
+fn edit_user_binding_toml(
+    contents: &str,
+    name: &str,
+    binding: Option<&UserBinding>,
+) -> Result<String, String> {
+    let key = format_toml_key(name);
+    if find_toml_table_section(contents, &format!("user_bindings.{}", key)).is_some() {
+        return Err(format!(
+            "'{}' is written as a [user_bindings.{}] table; edit the file by hand",
+            name, key
+        ));
+    }
+    let entry = binding.map(|binding| format_user_binding_toml(name, binding));
+    let mut updated = contents.to_string();
+
+    let Some(section) = find_toml_table_section(contents, "user_bindings") else {
+        let entry = entry.ok_or_else(|| format!("no binding named '{}'", name))?;
+        let first_table_start = toml_table_headers(contents)
+            .first()
+            .map(|(_, start, _)| *start)
+            .unwrap_or(contents.len());
+        let separator = if first_table_start == 0 || contents[..first_table_start].ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        updated.insert_str(
+            first_table_start,
+            &format!("{}[user_bindings]\n{}\n\n", separator, entry),
+        );
+        return Ok(updated);
+    };
+
+    let existing = toml_section_lines(contents, section)
+        .into_iter()
+        .find(|(_, _, line)| {
+            let trimmed = line.trim_start();
+            !toml_line_is_comment_or_blank(line)
+                && (toml_line_key_is(trimmed, name) || toml_line_key_is(trimmed, &key))
+        });
+    match (existing, entry) {
+        (Some((start, end, line)), Some(entry)) => {
+            let newline = if line.ends_with('\n') { "\n" } else { "" };
+            updated.replace_range(start..end, &format!("{}{}", entry, newline));
+        }
+        (Some((start, end, _)), None) => updated.replace_range(start..end, ""),
+        (None, Some(entry)) => {
+            let insertion_point =
+                toml_section_last_entry_end(contents, section).unwrap_or(section.body_start);
+            let prefix = if insertion_point == section.body_start
+                || contents[..insertion_point].ends_with('\n')
+            {
+                ""
+            } else {
+                "\n"
+            };
+            updated.insert_str(insertion_point, &format!("{}{}\n", prefix, entry));
+        }
+        (None, None) => return Err(format!("no binding named '{}'", name)),
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn url_binding(url: &str) -> UserBinding {
+        UserBinding::Url {
+            url: url.to_string(),
+            description: None,
+            override_builtin: false,
+        }
+    }
+
+    fn write_edit_test_config(test_name: &str, contents: &str) -> (PathBuf, PathBuf) {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "bunnylol-binding-edits-test-{}-{}-{}",
+            test_name,
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, contents).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn test_edit_user_binding_toml_keeps_comments_and_other_lines() {
+        let contents = r#"# my bunnylol config
+default_search = "ddg"
+
+[user_bindings]
+# calendar
+cal = { url = "https://calendar.google.com/" }
+jira = { url = "https://jira.example/browse/{}" } # tickets
+
+# history comment
+[history]
+enabled = false
+"#;
+        let replaced =
+            edit_user_binding_toml(contents, "cal", Some(&url_binding("https://cal.example/")))
+                .unwrap();
+        assert_eq!(
+            replaced,
+            contents.replace(
+                "cal = { url = \"https://calendar.google.com/\" }",
+                "cal = { url = \"https://cal.example/\" }"
+            )
+        );
+
+        let added = edit_user_binding_toml(
+            contents,
+            "wiki",
+            Some(&url_binding("https://wiki.example/")),
+        )
+        .unwrap();
+        assert!(added.contains(
+            "jira = { url = \"https://jira.example/browse/{}\" } # tickets\n\
+             wiki = { url = \"https://wiki.example/\" }\n\
+             \n# history comment\n[history]"
+        ));
+
+        let removed = edit_user_binding_toml(contents, "jira", None).unwrap();
+        assert_eq!(
+            removed,
+            contents.replace(
+                "jira = { url = \"https://jira.example/browse/{}\" } # tickets\n",
+                ""
+            )
+        );
+
+        assert!(edit_user_binding_toml(contents, "nope", None).is_err());
+    }
+
+    #[test]
+    fn test_edit_user_binding_toml_creates_section_before_first_table() {
+        let contents = "default_search = \"ddg\"\n\n[history]\nenabled = false\n";
+        let added =
+            edit_user_binding_toml(contents, "a b", Some(&url_binding("https://x.example/")))
+                .unwrap();
+        assert_eq!(
+            added,
+            "default_search = \"ddg\"\n\n[user_bindings]\n\"a b\" = { url = \"https://x.example/\" }\n\n[history]\nenabled = false\n"
+        );
+
+        let added =
+            edit_user_binding_toml("", "x", Some(&url_binding("https://x.example/"))).unwrap();
+        let config: BunnylolConfig = toml::from_str(&added).unwrap();
+        assert_eq!(config.user_bindings["x"], url_binding("https://x.example/"));
+    }
+
+    #[test]
+    fn test_config_etag_changes_with_contents() {
+        assert_eq!(config_etag("a = 1\n"), config_etag("a = 1\n"));
+        assert_ne!(config_etag("a = 1\n"), config_etag("a = 2\n"));
+        assert!(config_etag("").starts_with('"') && config_etag("").ends_with('"'));
+    }
+
+    #[test]
+    fn test_edit_user_binding_checks_etag_and_reloads() {
+        let (dir, path) = write_edit_test_config(
+            "reload",
+            "# keep me\n[user_bindings]\ncal = { url = \"https://cal.example/\" }\n",
+        );
+        let reloader = ConfigReloader::new_for_path(
+            BunnylolConfig::read_from_path(&path).unwrap(),
+            path.clone(),
+        );
+        let no_conflicts = |_: &BunnylolConfig| Vec::new();
+
+        let snapshot = reloader.user_bindings_snapshot().unwrap();
+        assert_eq!(snapshot.bindings.len(), 1);
+
+        assert!(matches!(
+            reloader.edit_user_binding("\"stale\"", "x", BindingEdit::Delete, no_conflicts),
+            Err(BindingEditError::Stale(etag)) if etag == snapshot.etag
+        ));
+        assert_eq!(
+            reloader.edit_user_binding(
+                &snapshot.etag,
+                "cal",
+                BindingEdit::Create(url_binding("https://other.example/")),
+                no_conflicts
+            ),
+            Err(BindingEditError::Exists)
+        );
+        assert_eq!(
+            reloader.edit_user_binding(&snapshot.etag, "nope", BindingEdit::Delete, no_conflicts),
+            Err(BindingEditError::NotFound)
+        );
+        assert!(matches!(
+            reloader.edit_user_binding(
+                &snapshot.etag,
+                "two words",
+                BindingEdit::Replace(url_binding("https://x.example/")),
+                no_conflicts
+            ),
+            Err(BindingEditError::Invalid(_))
+        ));
+
+        let created = reloader
+            .edit_user_binding(
+                &snapshot.etag,
+                "wiki",
+                BindingEdit::Create(url_binding("https://wiki.example/{}")),
+                no_conflicts,
+            )
+            .unwrap();
+        assert_ne!(created.etag, snapshot.etag);
+        assert_eq!(created.bindings.len(), 2);
+        assert_eq!(
+            reloader.current().user_bindings["wiki"],
+            url_binding("https://wiki.example/{}")
+        );
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# keep me\n"));
+        assert_eq!(config_etag(&written), created.etag);
+
+        // The old ETag is now stale.
+        assert!(matches!(
+            reloader.edit_user_binding(&snapshot.etag, "cal", BindingEdit::Delete, no_conflicts),
+            Err(BindingEditError::Stale(_))
+        ));
+        let deleted = reloader
+            .edit_user_binding(&created.etag, "cal", BindingEdit::Delete, no_conflicts)
+            .unwrap();
+        assert!(!deleted.bindings.contains_key("cal"));
+        assert!(!reloader.current().user_bindings.contains_key("cal"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_edit_user_binding_rejects_shadowed_binding() {
+        let (dir, path) = write_edit_test_config("conflict", "[history]\nenabled = false\n");
+        let reloader = ConfigReloader::new_for_path(
+            BunnylolConfig::read_from_path(&path).unwrap(),
+            path.clone(),
+        );
+        let etag = reloader.user_bindings_snapshot().unwrap().etag;
+        let builtin_gh = |config: &BunnylolConfig| {
+            config.validate_user_bindings_conflicts(&["gh"].into_iter().collect())
+        };
+
+        let err = reloader
+            .edit_user_binding(
+                &etag,
+                "gh",
+                BindingEdit::Replace(url_binding("https://gh.example/")),
+                builtin_gh,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("override"), "{}", err);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[history]\nenabled = false\n"
+        );
+
+        let overriding = UserBinding::Url {
+            url: "https://gh.example/".to_string(),
+            description: None,
+            override_builtin: true,
+        };
+        reloader
+            .edit_user_binding(&etag, "gh", BindingEdit::Replace(overriding), builtin_gh)
+            .unwrap();
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,443 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::BunnylolConfig;
+use super::user_bindings::escape_toml_string;
+
+/// Built-in commands that read settings from a `[commands.<name>]` table,
+/// and that named instances may use as their `type`.
+pub const CONFIGURABLE_COMMANDS: &[&str] = &[
+    "github",
+    "gitlab",
+    "python",
+    "rust",
+    "node",
+    "mdn",
+    "godocs",
+    "amazon",
+    "wikipedia",
+    "maps",
+];
+
+/// Configurable built-ins whose `host` can be changed.
+const HOSTED_COMMANDS: &[&str] = &["github", "gitlab"];
+
+/// Configurable built-ins that can open pages from a local `mirror`.
+const MIRRORED_COMMANDS: &[&str] = &["python", "rust", "node", "mdn", "godocs"];
+
+/// Configurable built-ins whose regional site or language can be changed.
+const LOCALIZED_COMMANDS: &[&str] = &["amazon", "wikipedia", "maps", "mdn"];
+
+/// Per-command settings from a `[commands.<name>]` table in the config file.
+///
+/// A table named after a configurable built-in changes that built-in. Any
+/// other name defines a **named instance**: an extra command word that
+/// behaves like the built-in given by `type`, with its own settings.
+///
+/// ```toml
+/// # `gh` opens GitHub Enterprise instead of github.com
+/// [commands.github]
+/// host = "github.corp.example"
+///
+/// # `ghe` opens GitHub Enterprise, `gh` keeps opening github.com
+/// [commands.ghe]
+/// type = "github"
+/// host = "github.corp.example"
+///
+/// # `py` opens the Python 3.9 docs unless a version is given (`py3.12 asyncio`)
+/// [commands.python]
+/// version = "3.9"
+///
+/// # `rust` opens the docs installed by `rustup doc` when the page exists
+/// [commands.rust]
+/// mirror = "~/.rustup/toolchains/stable-x86_64-unknown-linux-gnu/share/doc/rust/html"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CommandConfig {
+    /// The built-in a named instance behaves like (e.g. "github"). Only
+    /// valid on named instances.
+    #[serde(default, rename = "type")]
+    pub command_type: Option<String>,
+
+    /// Host to use instead of the command's public default, e.g.
+    /// "github.corp.example". `https://` is assumed unless a scheme is given,
+    /// and a path prefix is allowed ("https://example.com/gitlab").
+    #[serde(default)]
+    pub host: Option<String>,
+
+    /// Owner assumed when a GitHub deep link names only a repo, so
+    /// `gh repo#12` opens `<default_org>/repo#12`. GitHub only.
+    #[serde(default)]
+    pub default_org: Option<String>,
+
+    /// Documentation version opened when the input doesn't name one, e.g.
+    /// "3.9" for Python, "1.70" or "nightly" for Rust, "18" for Node.js.
+    /// Docs commands (python, rust, node) only.
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// Local copy of the docs (a directory, `~/` allowed) to open instead of
+    /// the online pages when it has the requested page. Docs commands
+    /// (python, rust, node, mdn, godocs) only.
+    #[serde(default)]
+    pub mirror: Option<String>,
+
+    /// Locale (`language[-REGION]`, e.g. "de-DE") overriding the global
+    /// `locale` for this command. Regional commands (amazon, wikipedia,
+    /// maps, mdn) only.
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+impl CommandConfig {
+    /// The configured `host` as a base URL with a scheme and no trailing
+    /// slash, or `None` to use the command's default.
+    pub fn base_url(&self) -> Option<String> {
+        let host = self.host.as_deref()?.trim().trim_end_matches('/');
+        if host.contains("://") {
+            Some(host.to_string())
+        } else {
+            Some(format!("https://{}", host))
+        }
+    }
+
+    /// The configured `mirror` directory, with a leading `~/` expanded.
+    pub fn mirror_root(&self) -> Option<PathBuf> {
+        let mirror = self.mirror.as_deref()?.trim();
+        match mirror.strip_prefix("~/") {
+            Some(rest) => Some(PathBuf::from(std::env::var_os("HOME")?).join(rest)),
+            None => Some(PathBuf::from(mirror)),
+        }
+    }
+}
+
+impl BunnylolConfig {
+    /// Check every `[commands.<name>]` table names a configurable built-in
+    /// (or sets a valid `type`) and has a usable `host`. Called when the
+    /// config is loaded; an error here is fatal.
+    pub fn validate_commands(&self) -> Result<(), String> {
+        let mut names: Vec<&String> = self.commands.keys().collect();
+        names.sort();
+        for name in names {
+            let settings = &self.commands[name];
+            let is_builtin = CONFIGURABLE_COMMANDS.contains(&name.as_str());
+            match &settings.command_type {
+                Some(kind) if is_builtin && kind != name => {
+                    return Err(format!(
+                        "[commands.{}] configures the built-in '{}' and cannot set type = \"{}\"",
+                        name, name, kind
+                    ));
+                }
+                Some(kind) if !CONFIGURABLE_COMMANDS.contains(&kind.as_str()) => {
+                    return Err(format!(
+                        "[commands.{}] has unknown type '{}' (expected one of: {})",
+                        name,
+                        kind,
+                        CONFIGURABLE_COMMANDS.join(", ")
+                    ));
+                }
+                None if !is_builtin => {
+                    return Err(format!(
+                        "[commands.{}] is not a configurable command (expected one of: {}); \
+                         set `type` to define a named instance",
+                        name,
+                        CONFIGURABLE_COMMANDS.join(", ")
+                    ));
+                }
+                _ => {}
+            }
+            if name.is_empty() || name.chars().any(char::is_whitespace) {
+                return Err(format!(
+                    "[commands.\"{}\"] names must be a single word",
+                    name
+                ));
+            }
+            let kind = settings.command_type.as_deref().unwrap_or(name);
+            if let Some(org) = &settings.default_org {
+                if kind != "github" {
+                    return Err(format!(
+                        "[commands.{}] sets default_org, which only GitHub supports",
+                        name
+                    ));
+                }
+                if org.is_empty() || org.contains('/') || org.chars().any(char::is_whitespace) {
+                    return Err(format!(
+                        "[commands.{}] has an invalid default_org '{}' (expected e.g. \"mycompany\")",
+                        name, org
+                    ));
+                }
+            }
+            if settings.host.is_some() && !HOSTED_COMMANDS.contains(&kind) {
+                return Err(format!(
+                    "[commands.{}] sets host, which only {} support",
+                    name,
+                    HOSTED_COMMANDS.join(" and ")
+                ));
+            }
+            if let Some(version) = &settings.version {
+                let valid = match kind {
+                    "python" => crate::commands::PythonCommand::parse_version(version).is_some(),
+                    "rust" => crate::commands::RustCommand::parse_version(version).is_some(),
+                    "node" => crate::commands::NodeCommand::parse_version(version).is_some(),
+                    _ => {
+                        return Err(format!(
+                            "[commands.{}] sets version, which only python, rust and node support",
+                            name
+                        ));
+                    }
+                };
+                if !valid {
+                    return Err(format!(
+                        "[commands.{}] has an invalid version '{}' for {}",
+                        name, version, kind
+                    ));
+                }
+            }
+            if let Some(mirror) = &settings.mirror {
+                if !MIRRORED_COMMANDS.contains(&kind) {
+                    return Err(format!(
+                        "[commands.{}] sets mirror, which only {} support",
+                        name,
+                        MIRRORED_COMMANDS.join(", ")
+                    ));
+                }
+                let mirror = mirror.trim();
+                if !(mirror.starts_with("~/") || std::path::Path::new(mirror).is_absolute()) {
+                    return Err(format!(
+                        "[commands.{}] has an invalid mirror '{}' (expected an absolute path or one starting with ~/)",
+                        name, mirror
+                    ));
+                }
+            }
+            if let Some(locale) = &settings.locale {
+                if !LOCALIZED_COMMANDS.contains(&kind) {
+                    return Err(format!(
+                        "[commands.{}] sets locale, which only {} support",
+                        name,
+                        LOCALIZED_COMMANDS.join(", ")
+                    ));
+                }
+                if crate::commands::Locale::parse(locale).is_none() {
+                    return Err(format!(
+                        "[commands.{}] has an invalid locale '{}' (expected e.g. \"de-DE\")",
+                        name, locale
+                    ));
+                }
+            }
+            if let Some(host) = &settings.host {
+                let host = host.trim();
+                let scheme_ok = !host.contains("://")
+                    || host.starts_with("https://")
+                    || host.starts_with("http://");
+                if host.is_empty() || host.chars().any(char::is_whitespace) || !scheme_ok {
+                    return Err(format!(
+                        "[commands.{}] has an invalid host '{}' (expected e.g. \"github.corp.example\")",
+                        name, host
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Settings for the built-in `command_type` (e.g. "github"), if its
+    /// `[commands.<type>]` table is present.
+    pub fn command_settings(&self, command_type: &str) -> Option<&CommandConfig> {
+        self.commands
+            .get(command_type)
+            .filter(|_| CONFIGURABLE_COMMANDS.contains(&command_type))
+    }
+
+    /// The named instance bound to `name` (e.g. "ghe"), with its type.
+    pub fn command_instance(&self, name: &str) -> Option<(&str, &CommandConfig)> {
+        if CONFIGURABLE_COMMANDS.contains(&name) {
+            return None;
+        }
+        let settings = self.commands.get(name)?;
+        Some((settings.command_type.as_deref()?, settings))
+    }
+
+    /// Names of all named instances, sorted.
+    pub fn command_instance_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .commands
+            .keys()
+            .map(String::as_str)
+            .filter(|name| self.command_instance(name).is_some())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+/// Format one `[commands.<name>]` table for `config.toml`.
+pub(super) fn format_command_config_toml(name: &str, settings: &CommandConfig) -> String {
+    let mut lines = vec![format!("[commands.{}]", name)];
+    if let Some(kind) = &settings.command_type {
+        lines.push(format!("type = \"{}\"", escape_toml_string(kind)));
+    }
+    if let Some(host) = &settings.host {
+        lines.push(format!("host = \"{}\"", escape_toml_string(host)));
+    }
+    if let Some(org) = &settings.default_org {
+        lines.push(format!("default_org = \"{}\"", escape_toml_string(org)));
+    }
+    if let Some(version) = &settings.version {
+        lines.push(format!("version = \"{}\"", escape_toml_string(version)));
+    }
+    if let Some(mirror) = &settings.mirror {
+        lines.push(format!("mirror = \"{}\"", escape_toml_string(mirror)));
+    }
+    if let Some(locale) = &settings.locale {
+        lines.push(format!("locale = \"{}\"", escape_toml_string(locale)));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml_body: &str) -> BunnylolConfig {
+        toml::from_str(toml_body).expect("valid TOML")
+    }
+
+    #[test]
+    fn test_base_url_defaults_to_https() {
+        let settings = CommandConfig {
+            command_type: None,
+            host: Some("github.corp.example/".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.base_url().as_deref(),
+            Some("https://github.corp.example")
+        );
+
+        let settings = CommandConfig {
+            command_type: None,
+            host: Some("http://gitlab.internal/gitlab".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.base_url().as_deref(),
+            Some("http://gitlab.internal/gitlab")
+        );
+        assert_eq!(CommandConfig::default().base_url(), None);
+    }
+
+    #[test]
+    fn test_builtin_settings_and_named_instances() {
+        let cfg = config(
+            r#"
+[commands.github]
+host = "github.corp.example"
+
+[commands.ghe]
+type = "github"
+host = "ghe.corp.example"
+"#,
+        );
+        assert!(cfg.validate_commands().is_ok());
+        assert_eq!(
+            cfg.command_settings("github")
+                .and_then(CommandConfig::base_url)
+                .as_deref(),
+            Some("https://github.corp.example")
+        );
+        assert!(cfg.command_settings("gitlab").is_none());
+        assert!(cfg.command_instance("github").is_none());
+        let (kind, settings) = cfg.command_instance("ghe").expect("ghe instance");
+        assert_eq!(kind, "github");
+        assert_eq!(settings.host.as_deref(), Some("ghe.corp.example"));
+        assert_eq!(cfg.command_instance_names(), vec!["ghe"]);
+    }
+
+    #[test]
+    fn test_validate_commands_rejects_bad_tables() {
+        let cases = [
+            (
+                "[commands.gh]\nhost = \"x.example\"",
+                "not a configurable command",
+            ),
+            (
+                "[commands.ghe]\ntype = \"bitbucket\"",
+                "unknown type 'bitbucket'",
+            ),
+            ("[commands.github]\ntype = \"gitlab\"", "cannot set type"),
+            (
+                "[commands.github]\nhost = \"ftp://x.example\"",
+                "invalid host",
+            ),
+            ("[commands.gitlab]\nhost = \"\"", "invalid host"),
+            (
+                "[commands.gitlab]\ndefault_org = \"infra\"",
+                "only GitHub supports",
+            ),
+            (
+                "[commands.github]\ndefault_org = \"a/b\"",
+                "invalid default_org",
+            ),
+            (
+                "[commands.python]\nhost = \"docs.example\"",
+                "only github and gitlab support",
+            ),
+            ("[commands.github]\nversion = \"3\"", "sets version"),
+            ("[commands.python]\nversion = \"3.x\"", "invalid version"),
+            ("[commands.rust]\nversion = \"2.0\"", "invalid version"),
+            ("[commands.node]\nversion = \"latest\"", "invalid version"),
+            ("[commands.github]\nmirror = \"/srv/docs\"", "sets mirror"),
+            ("[commands.mdn]\nmirror = \"docs/mdn\"", "invalid mirror"),
+            ("[commands.rust]\nlocale = \"de\"", "sets locale"),
+            ("[commands.amazon]\nlocale = \"german\"", "invalid locale"),
+        ];
+        for (body, expected) in cases {
+            let err = config(body).validate_commands().unwrap_err();
+            assert!(err.contains(expected), "{:?}: {}", body, err);
+        }
+    }
+
+    #[test]
+    fn test_unknown_command_settings_are_rejected_by_serde() {
+        let result: Result<BunnylolConfig, _> =
+            toml::from_str("[commands.github]\nhostname = \"x.example\"");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_write_then_parse_roundtrip_with_commands() {
+        let cfg = config(
+            r#"
+[commands.gitlab]
+host = "https://code.corp.example/gitlab"
+
+[commands.ghe]
+type = "github"
+host = "github.corp.example"
+default_org = "infra"
+
+[commands.rust]
+version = "1.70"
+mirror = "~/docs/rust"
+
+[commands.azjp]
+type = "amazon"
+locale = "ja-JP"
+"#,
+        );
+
+        let parsed: BunnylolConfig = toml::from_str(&cfg.to_toml_with_comments()).unwrap();
+        assert_eq!(parsed.commands, cfg.commands);
+        assert!(parsed.validate_commands().is_ok());
+    }
+}
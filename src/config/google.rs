@@ -0,0 +1,210 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::BunnylolConfig;
+use super::user_bindings::escape_toml_string;
+
+/// A Google account as written in the config: a signed-in account index
+/// (`1`, the `/u/1/` account) or a string, which is an email address or,
+/// for `account`, the name of an entry in `[google.accounts]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GoogleAccountRef {
+    Index(u32),
+    Name(String),
+}
+
+/// A resolved Google account, as Google's `/u/N/` and `authuser=` forms
+/// take it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoogleAccount {
+    Index(u32),
+    Email(String),
+}
+
+/// Account selection for the Google commands (`gmail`, `docs`, `gsheets`,
+/// `gslides`, `gchat`, `maps`).
+///
+/// ```toml
+/// [google]
+/// account = "work"              # default: a name below, an index or an email
+///
+/// [google.accounts]
+/// work = "me@corp.example"
+/// personal = 0
+/// ```
+///
+/// A query can pick an account with a leading `@name`, `@N` or `@email`
+/// (`gmail @personal is:unread`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GoogleConfig {
+    /// Account used when a query doesn't select one.
+    #[serde(default)]
+    pub account: Option<GoogleAccountRef>,
+
+    /// Named accounts for `account` and `@name` selectors.
+    #[serde(default)]
+    pub accounts: HashMap<String, GoogleAccountRef>,
+}
+
+impl GoogleConfig {
+    /// Resolve a selector (`work`, `1` or `me@corp.example`, without the
+    /// `@`). `None` for an unknown name.
+    pub fn resolve(&self, selector: &str) -> Option<GoogleAccount> {
+        if let Ok(index) = selector.parse() {
+            return Some(GoogleAccount::Index(index));
+        }
+        if selector.contains('@') {
+            return Some(GoogleAccount::Email(selector.to_string()));
+        }
+        match self.accounts.get(selector)? {
+            GoogleAccountRef::Index(index) => Some(GoogleAccount::Index(*index)),
+            GoogleAccountRef::Name(email) => Some(GoogleAccount::Email(email.clone())),
+        }
+    }
+
+    /// The default account, if one is configured.
+    pub fn default_account(&self) -> Option<GoogleAccount> {
+        match self.account.as_ref()? {
+            GoogleAccountRef::Index(index) => Some(GoogleAccount::Index(*index)),
+            GoogleAccountRef::Name(name) => self.resolve(name),
+        }
+    }
+
+    pub(super) fn to_toml(&self) -> String {
+        let value = |account: &GoogleAccountRef| match account {
+            GoogleAccountRef::Index(index) => index.to_string(),
+            GoogleAccountRef::Name(name) => format!("\"{}\"", escape_toml_string(name)),
+        };
+        let mut lines = vec!["[google]".to_string()];
+        if let Some(account) = &self.account {
+            lines.push(format!("account = {}", value(account)));
+        }
+        if !self.accounts.is_empty() {
+            let mut names: Vec<&String> = self.accounts.keys().collect();
+            names.sort();
+            lines.push(String::new());
+            lines.push("[google.accounts]".to_string());
+            for name in names {
+                lines.push(format!("{} = {}", name, value(&self.accounts[name])));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl BunnylolConfig {
+    /// Check `[google]` account names and references. Called when the
+    /// config is loaded; an error here is fatal.
+    pub fn validate_google(&self) -> Result<(), String> {
+        let google = &self.google;
+        for (name, account) in &google.accounts {
+            let valid_name = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+                && name.parse::<u32>().is_err();
+            if !valid_name {
+                return Err(format!(
+                    "[google.accounts] has an invalid name '{}' (expected a word like \"work\")",
+                    name
+                ));
+            }
+            if let GoogleAccountRef::Name(email) = account
+                && !email.contains('@')
+            {
+                return Err(format!(
+                    "[google.accounts] {} must be an account index or an email address, not '{}'",
+                    name, email
+                ));
+            }
+        }
+        if let Some(GoogleAccountRef::Name(name)) = &google.account
+            && google.resolve(name).is_none()
+        {
+            return Err(format!(
+                "[google] account '{}' is not an index, an email address or a name in [google.accounts]",
+                name
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(body: &str) -> BunnylolConfig {
+        toml::from_str(body).expect("valid TOML")
+    }
+
+    #[test]
+    fn test_resolve_accounts() {
+        let cfg = config(
+            r#"
+[google]
+account = "work"
+
+[google.accounts]
+work = "me@corp.example"
+personal = 0
+"#,
+        );
+        cfg.validate_google().unwrap();
+        let google = &cfg.google;
+        assert_eq!(
+            google.default_account(),
+            Some(GoogleAccount::Email("me@corp.example".to_string()))
+        );
+        assert_eq!(google.resolve("personal"), Some(GoogleAccount::Index(0)));
+        assert_eq!(google.resolve("2"), Some(GoogleAccount::Index(2)));
+        assert_eq!(
+            google.resolve("other@example.com"),
+            Some(GoogleAccount::Email("other@example.com".to_string()))
+        );
+        assert_eq!(google.resolve("nope"), None);
+        assert_eq!(GoogleConfig::default().default_account(), None);
+    }
+
+    #[test]
+    fn test_validate_google_rejects_bad_accounts() {
+        for (body, expected) in [
+            ("[google]\naccount = \"work\"", "not an index"),
+            (
+                "[google.accounts]\nwork = \"work\"",
+                "must be an account index",
+            ),
+            ("[google.accounts]\n\"a b\" = 1", "invalid name"),
+            ("[google.accounts]\n3 = 1", "invalid name"),
+        ] {
+            let err = config(body).validate_google().unwrap_err();
+            assert!(err.contains(expected), "{}: {}", body, err);
+        }
+        assert!(config("[google]\naccount = 1").validate_google().is_ok());
+    }
+
+    #[test]
+    fn test_google_to_toml_roundtrip() {
+        let cfg = config(
+            r#"
+[google]
+account = 1
+
+[google.accounts]
+work = "me@corp.example"
+"#,
+        );
+        let parsed: BunnylolConfig = toml::from_str(&cfg.google.to_toml()).unwrap();
+        assert_eq!(parsed.google, cfg.google);
+    }
+}
@@ -6,17 +6,49 @@
  */
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::{OnceLock, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 use std::time::SystemTime;
 
+use crate::links::ShortLink;
+
 mod alias_migration;
+mod auth;
+mod binding_edits;
+mod commands;
+mod google;
+mod patterns;
+mod personal_bindings;
+mod project;
+mod providers;
+mod rewrites;
+mod tls;
+mod url_policy;
 mod user_bindings;
 
-use user_bindings::format_user_binding_toml;
+pub(crate) use auth::secrets_match;
+pub use auth::{
+    AuthConfig, IpRange, Peer, Protect, ProxyAuth, Role, TokenCredential, UserCredential,
+    hash_password,
+};
+pub use binding_edits::{BindingEdit, BindingEditError, UserBindingsSnapshot, config_etag};
+use commands::format_command_config_toml;
+pub use commands::{CONFIGURABLE_COMMANDS, CommandConfig};
+pub use google::{GoogleAccount, GoogleAccountRef, GoogleConfig};
+use patterns::format_pattern_rule_toml;
+pub use patterns::{PatternDiagnostic, PatternRule};
+pub use personal_bindings::{PersonalBindings, PersonalOverlay};
+pub use project::{PROJECT_FILE_NAME, ProjectFile, ProjectOverlay, ProjectTrust, TrustState};
+pub use providers::{CustomProvider, ProvidersConfig};
+use rewrites::format_rewrite_rule_toml;
+pub use rewrites::{AppliedRewrite, RewriteRule};
+pub use tls::TlsConfig;
+pub use url_policy::UrlPolicy;
+pub(crate) use user_bindings::substitute_url_template;
 pub use user_bindings::{BindingConflict, ResolvedBinding, UserBinding};
+use user_bindings::{escape_toml_string, format_user_binding_toml};
 
 /// Global config snapshot used by command handlers that read config directly.
 static GLOBAL_CONFIG: OnceLock<RwLock<BunnylolConfig>> = OnceLock::new();
@@ -52,11 +84,23 @@ pub struct BunnylolConfig {
     #[serde(default = "default_search_engine")]
     pub default_search: String,
 
+    /// Locale for regional sites and languages (optional), as
+    /// `language[-REGION]`, e.g. "de-DE" or "ja-JP". Used by amazon,
+    /// wikipedia, maps, mdn and the search fallback; `[commands.<name>]
+    /// locale` overrides it per command.
+    #[serde(default)]
+    pub locale: Option<String>,
+
     /// Stock website provider
     /// Options: "yahoo" (default), "finviz", "tradingview", "google", "investing"
     #[serde(default = "default_stock_provider")]
     pub stock_provider: String,
 
+    /// Preferred provider per category and user-defined providers
+    /// (`[providers]`). See [`ProvidersConfig`].
+    #[serde(default)]
+    pub providers: ProvidersConfig,
+
     /// Custom command aliases
     #[serde(default)]
     pub aliases: HashMap<String, String>,
@@ -75,6 +119,55 @@ pub struct BunnylolConfig {
     #[serde(default)]
     pub user_bindings: HashMap<String, UserBinding>,
 
+    /// Legacy `[aliases]` entries that were dropped while migrating into
+    /// `[user_bindings]` because a binding of the same name already existed.
+    /// Kept (name -> aliased command) so `bunnylol explain` can report them.
+    #[serde(skip)]
+    pub dropped_aliases: HashMap<String, String>,
+
+    /// Ordered regex rules (`[[patterns]]`), evaluated in tier 1 after the
+    /// built-in prefixes like `$TICKER` and `r/sub`. See [`PatternRule`].
+    #[serde(default)]
+    pub patterns: Vec<PatternRule>,
+
+    /// Ordered URL rewrite rules (`[[rewrites]]`), applied to the final URL
+    /// of every resolution. See [`RewriteRule`].
+    #[serde(default)]
+    pub rewrites: Vec<RewriteRule>,
+
+    /// Per-command settings (`[commands.github] host = "..."`) and named
+    /// instances of configurable built-ins. See [`CommandConfig`].
+    #[serde(default)]
+    pub commands: HashMap<String, CommandConfig>,
+
+    /// The project-local `.bunnylol.toml` merged over this config by the
+    /// CLI, if any. Never read from or written to `config.toml`.
+    #[serde(skip)]
+    pub project: Option<ProjectOverlay>,
+
+    /// The signed-in user's personal bindings merged over this config by
+    /// the server, if any. Never read from or written to `config.toml`.
+    #[serde(skip)]
+    pub personal: Option<PersonalOverlay>,
+
+    /// Go-links that haven't expired, loaded from the data directory by the
+    /// CLI and the server (see [`crate::links`]). Never read from or written
+    /// to `config.toml`.
+    #[serde(skip)]
+    pub links: Arc<BTreeMap<String, ShortLink>>,
+
+    /// Google account selection (`[google]`). See [`GoogleConfig`].
+    #[serde(default)]
+    pub google: GoogleConfig,
+
+    /// Which resolved URLs may be opened (`[url_policy]`). See [`UrlPolicy`].
+    #[serde(default)]
+    pub url_policy: UrlPolicy,
+
+    /// Who may use the server (`[auth]`). See [`AuthConfig`].
+    #[serde(default)]
+    pub auth: AuthConfig,
+
     /// Command history settings
     #[serde(default)]
     pub history: HistoryConfig,
@@ -89,9 +182,21 @@ impl Default for BunnylolConfig {
         Self {
             browser: None,
             default_search: default_search_engine(),
+            locale: None,
             stock_provider: default_stock_provider(),
+            providers: ProvidersConfig::default(),
             aliases: HashMap::new(),
             user_bindings: HashMap::new(),
+            dropped_aliases: HashMap::new(),
+            patterns: Vec::new(),
+            rewrites: Vec::new(),
+            commands: HashMap::new(),
+            project: None,
+            personal: None,
+            links: Arc::default(),
+            google: GoogleConfig::default(),
+            url_policy: UrlPolicy::default(),
+            auth: AuthConfig::default(),
             history: HistoryConfig::default(),
             server: ServerConfig::default(),
         }
@@ -104,6 +209,8 @@ pub struct ConfigReloader {
     config: RwLock<BunnylolConfig>,
     config_path: Option<PathBuf>,
     modified: RwLock<Option<SystemTime>>,
+    /// Serializes edits made through the admin API. See `edit_user_binding`.
+    edit_lock: Mutex<()>,
 }
 
 impl ConfigReloader {
@@ -122,9 +229,21 @@ impl ConfigReloader {
             config: RwLock::new(config),
             config_path,
             modified: RwLock::new(modified),
+            edit_lock: Mutex::new(()),
         }
     }
 
+    /// The config file this reloader watches, if there is one.
+    pub fn config_path(&self) -> Option<&Path> {
+        self.config_path.as_deref()
+    }
+
+    /// A reloader for a config that isn't read from a file.
+    #[cfg(test)]
+    pub(crate) fn new_without_file(config: BunnylolConfig) -> Self {
+        Self::with_path(config, None)
+    }
+
     #[cfg(test)]
     fn new_for_path(config: BunnylolConfig, config_path: PathBuf) -> Self {
         Self::with_path(config, Some(config_path))
@@ -171,6 +290,17 @@ impl ConfigReloader {
     }
 }
 
+/// `[aliases]` entries that lose to a `[user_bindings]` entry of the same
+/// name, whether they are migrated on disk or folded in-memory.
+fn shadowed_aliases(config: &BunnylolConfig) -> HashMap<String, String> {
+    config
+        .aliases
+        .iter()
+        .filter(|(name, _)| config.user_bindings.contains_key(*name))
+        .map(|(name, command)| (name.clone(), command.clone()))
+        .collect()
+}
+
 /// Fold `[aliases]` entries into `[user_bindings]` in-memory as `Command`
 /// variants. Pure function — does not touch the on-disk file.
 ///
@@ -225,7 +355,8 @@ pub struct ServerConfig {
     #[serde(default = "default_port")]
     pub port: u16,
 
-    /// Address to bind to (127.0.0.1 for localhost, 0.0.0.0 for network)
+    /// Address to bind to (127.0.0.1 for localhost, 0.0.0.0 for network),
+    /// or a Unix domain socket as "unix:/path/to/bunnylol.sock"
     #[serde(default = "default_address")]
     pub address: String,
 
@@ -246,6 +377,28 @@ pub struct ServerConfig {
     /// If not set, defaults to http://localhost:{port}
     #[serde(default)]
     pub server_display_url: Option<String>,
+
+    /// Bearer token for the `/api/user_bindings` admin API and the
+    /// `/bindings/edit` page. The API is disabled while this is unset.
+    #[serde(default)]
+    pub admin_token: Option<String>,
+
+    /// Reverse proxies (IPs, CIDR ranges, or `"unix"` for a Unix socket)
+    /// whose `Forwarded`, `X-Forwarded-For` and `X-Real-IP` headers name the
+    /// real client. Those headers are ignored from every other address.
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpRange>,
+
+    /// Path the server is served under behind a reverse proxy, e.g.
+    /// "/bunny" for `https://tools.corp/bunny/`. Every route, and the
+    /// server's own redirects, are prefixed with it. Empty (the default)
+    /// serves from the root.
+    #[serde(default)]
+    pub base_path: String,
+
+    /// Serve HTTPS with this certificate (`[server.tls]`).
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
 }
 
 impl Default for ServerConfig {
@@ -255,6 +408,10 @@ impl Default for ServerConfig {
             address: default_address(),
             log_level: default_log_level(),
             server_display_url: None,
+            admin_token: None,
+            trusted_proxies: Vec::new(),
+            base_path: String::new(),
+            tls: None,
         }
     }
 }
@@ -267,8 +424,13 @@ impl ServerConfig {
     /// - Local addresses (localhost, 127.0.0.1, 0.0.0.0) → "http://localhost" (or IP)
     ///
     /// If server_display_url is not set, returns "http://localhost:{port}"
+    ///
+    /// With `[server.tls]`, local addresses and the fallback use https://.
+    ///
+    /// The base path is appended unless the URL already ends with it.
     pub fn get_display_url(&self) -> String {
-        match &self.server_display_url {
+        let local_scheme = if self.tls.is_some() { "https" } else { "http" };
+        let url = match &self.server_display_url {
             Some(url) => {
                 let url = url.trim();
                 // If URL already has a protocol, use as-is
@@ -280,8 +442,8 @@ impl ServerConfig {
                         || url.starts_with("127.0.0.1")
                         || url.starts_with("0.0.0.0")
                     {
-                        // Local addresses default to http://
-                        format!("http://{}", url)
+                        // Local addresses default to http:// (unless serving TLS)
+                        format!("{}://{}", local_scheme, url)
                     } else {
                         // Public domains default to https://
                         format!("https://{}", url)
@@ -290,8 +452,32 @@ impl ServerConfig {
             }
             None => {
                 // Fallback to localhost
-                format!("http://localhost:{}", self.port)
+                format!("{}://localhost:{}", local_scheme, self.port)
             }
+        };
+        let url = url.trim_end_matches('/');
+        if url.ends_with(self.base_path()) {
+            url.to_string()
+        } else {
+            format!("{}{}", url, self.base_path())
+        }
+    }
+
+    /// The socket path, if `address` is a Unix domain socket ("unix:/path").
+    pub fn unix_socket(&self) -> Option<&Path> {
+        self.address.strip_prefix("unix:").map(Path::new)
+    }
+
+    /// `base_path` without a trailing slash: "" or e.g. "/bunny".
+    pub fn base_path(&self) -> &str {
+        self.base_path.trim().trim_end_matches('/')
+    }
+
+    /// Where Rocket mounts the routes: the base path, or "/".
+    pub fn mount_point(&self) -> &str {
+        match self.base_path() {
+            "" => "/",
+            base_path => base_path,
         }
     }
 }
@@ -399,6 +585,24 @@ impl BunnylolConfig {
         Self::get_data_dir().map(|dir| dir.join("history"))
     }
 
+    /// Get the full path to the go-links file
+    /// Returns: $XDG_DATA_HOME/bunnylol/links.toml
+    pub fn get_links_path() -> Option<PathBuf> {
+        Self::get_data_dir().map(|dir| dir.join("links.toml"))
+    }
+
+    /// Load the go-links that haven't expired. A broken links file is a
+    /// warning: bindings still resolve without it.
+    pub fn load_links(&mut self) {
+        let Some(links) = crate::links::Links::new() else {
+            return;
+        };
+        match links.active() {
+            Ok(active) => self.links = Arc::new(active),
+            Err(e) => eprintln!("Warning: {}", e),
+        }
+    }
+
     /// Load configuration from the config file
     /// If the file doesn't exist, creates it with default configuration
     /// If the file exists but is invalid, returns an error
@@ -429,8 +633,9 @@ impl BunnylolConfig {
         let contents = fs::read_to_string(config_path)
             .map_err(|e| format!("Failed to read config file {:?}: {}", config_path, e))?;
 
-        let mut config: BunnylolConfig = toml::from_str(&contents)
-            .map_err(|e| format!("Failed to parse config file {:?}: {}", config_path, e))?;
+        let mut config = Self::parse(&contents, config_path)?;
+
+        let dropped_aliases = shadowed_aliases(&config);
 
         match alias_migration::migrate_aliases_to_user_bindings(config_path, &contents, &config) {
             Ok(Some(migrated_config)) => {
@@ -463,6 +668,32 @@ impl BunnylolConfig {
         // This is used if the file could not be rewritten or the aliases were
         // expressed in a TOML shape the section migrator does not rewrite.
         fold_aliases_into_user_bindings(&mut config);
+        config.dropped_aliases = dropped_aliases;
+
+        Ok(config)
+    }
+
+    /// Parse and validate the contents of `config_path`, without folding in
+    /// legacy `[aliases]`.
+    fn parse(contents: &str, config_path: &Path) -> Result<Self, String> {
+        let config: BunnylolConfig = toml::from_str(contents)
+            .map_err(|e| format!("Failed to parse config file {:?}: {}", config_path, e))?;
+        config
+            .validate()
+            .map_err(|e| format!("Invalid config file {:?}: {}", config_path, e))?;
+        Ok(config)
+    }
+
+    /// Parse a config file without touching it on disk. Legacy `[aliases]`
+    /// are folded into `[user_bindings]` in-memory only, so this is safe for
+    /// read-only uses such as `bunnylol test --config`.
+    pub fn read_from_path(config_path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(config_path)
+            .map_err(|e| format!("Failed to read config file {:?}: {}", config_path, e))?;
+
+        let mut config = Self::parse(&contents, config_path)?;
+        config.dropped_aliases = shadowed_aliases(&config);
+        fold_aliases_into_user_bindings(&mut config);
 
         Ok(config)
     }
@@ -482,6 +713,47 @@ impl BunnylolConfig {
         fs::write(path, toml_content).map_err(|e| format!("Failed to write config file: {}", e))
     }
 
+    /// Checks that can't be expressed in the serde schema. Errors are fatal
+    /// at load time, like a TOML syntax error.
+    fn validate(&self) -> Result<(), String> {
+        if let Some(locale) = &self.locale
+            && crate::commands::Locale::parse(locale).is_none()
+        {
+            return Err(format!(
+                "Invalid locale '{}' (expected e.g. \"de-DE\" or \"ja\")",
+                locale
+            ));
+        }
+        let base_path = self.server.base_path();
+        if !base_path.is_empty()
+            && (!base_path.starts_with('/')
+                || base_path.contains(|c: char| c.is_whitespace() || "?#%".contains(c)))
+        {
+            return Err(format!(
+                "Invalid [server] base_path '{}' (expected a path such as \"/bunny\")",
+                self.server.base_path
+            ));
+        }
+        if self
+            .server
+            .unix_socket()
+            .is_some_and(|path| path.as_os_str().is_empty())
+        {
+            return Err(
+                "Invalid [server] address 'unix:' (expected a socket path such as \"unix:/run/bunnylol/bunnylol.sock\")"
+                    .to_string(),
+            );
+        }
+        self.validate_tls()?;
+        self.validate_patterns()?;
+        self.validate_rewrites()?;
+        self.validate_url_policy()?;
+        self.validate_auth()?;
+        self.validate_commands()?;
+        self.validate_providers()?;
+        self.validate_google()
+    }
+
     /// Convert config to TOML string with helpful comments
     fn to_toml_with_comments(&self) -> String {
         let browser_line = match &self.browser {
@@ -514,10 +786,138 @@ impl BunnylolConfig {
                 .collect::<Vec<_>>()
                 .join("\n")
         };
+        let patterns_content = if self.patterns.is_empty() {
+            r#"# [[patterns]]
+# name = "jira"
+# pattern = '^[A-Z]+-\d+$'
+# url = "https://corp.atlassian.net/browse/$0"
+# examples = ["PROJ-123"]"#
+                .to_string()
+        } else {
+            self.patterns
+                .iter()
+                .map(format_pattern_rule_toml)
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+        let rewrites_content = if self.rewrites.is_empty() {
+            r#"# [[rewrites]]
+# name = "old-reddit"
+# host = '^(www\.)?reddit\.com$'
+# replace_host = "old.reddit.com"
+#
+# [[rewrites]]
+# name = "no-tracking"
+# strip_params = ["utm_*", "fbclid"]"#
+                .to_string()
+        } else {
+            self.rewrites
+                .iter()
+                .map(format_rewrite_rule_toml)
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+        let commands_content = if self.commands.is_empty() {
+            r#"# [commands.github]
+# host = "github.corp.example"
+# default_org = "mycompany"
+#
+# [commands.ghe]
+# type = "github"
+# host = "github.corp.example""#
+                .to_string()
+        } else {
+            let mut entries: Vec<(&String, &CommandConfig)> = self.commands.iter().collect();
+            entries.sort_by_key(|(k, _)| k.to_lowercase());
+            entries
+                .into_iter()
+                .map(|(k, v)| format_command_config_toml(k, v))
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+        let google_content = if self.google == GoogleConfig::default() {
+            r#"# [google]
+# account = "work"
+#
+# [google.accounts]
+# work = "me@corp.example"
+# personal = 0"#
+                .to_string()
+        } else {
+            self.google.to_toml()
+        };
+        let providers_content = if self.providers == ProvidersConfig::default() {
+            r#"# [providers]
+# maps = "osm"
+# video = "invidious"
+#
+# [providers.custom.invidious]
+# category = "video"
+# url = "https://yewtu.be/search?q={}""#
+                .to_string()
+        } else {
+            self.providers.to_toml()
+        };
+        let url_policy_content = if self.url_policy == UrlPolicy::default() {
+            r#"# [url_policy]
+# schemes = ["https", "http"]
+# allow_hosts = ["corp.example", "github.com"]
+# deny_hosts = ["evil.example"]
+# cli = false"#
+                .to_string()
+        } else {
+            self.url_policy.to_toml()
+        };
+        let auth_content = if self.auth == AuthConfig::default() {
+            r#"# [auth]
+# protect = "all"
+#
+# [auth.tokens]
+# deploy-bot = { token = "a-long-random-string", role = "admin" }
+#
+# [auth.users]
+# alice = { password_hash = "$argon2id$v=19$...", role = "history" }"#
+                .to_string()
+        } else {
+            self.auth.to_toml()
+        };
+        let locale_line = match &self.locale {
+            Some(locale) => format!("locale = \"{}\"", locale),
+            None => "# locale = \"de-DE\"".to_string(),
+        };
         let server_display_url_line = match &self.server.server_display_url {
             Some(url) => format!("server_display_url = \"{}\"", url),
             None => "# server_display_url = \"bunny.example.com\"".to_string(),
         };
+        let admin_token_line = match &self.server.admin_token {
+            Some(token) => format!("admin_token = \"{}\"", escape_toml_string(token)),
+            None => "# admin_token = \"a-long-random-string\"".to_string(),
+        };
+        let trusted_proxies_line = if self.server.trusted_proxies.is_empty() {
+            "# trusted_proxies = [\"127.0.0.1\", \"172.16.0.0/12\"]".to_string()
+        } else {
+            let ranges: Vec<String> = self
+                .server
+                .trusted_proxies
+                .iter()
+                .map(|range| format!("\"{}\"", range))
+                .collect();
+            format!("trusted_proxies = [{}]", ranges.join(", "))
+        };
+        let tls_content = match &self.server.tls {
+            Some(tls) => tls.to_toml(),
+            None => [
+                "# [server.tls]",
+                "# cert = \"/etc/bunnylol/tls/fullchain.pem\"",
+                "# key = \"/etc/bunnylol/tls/privkey.pem\"",
+                "# redirect_http_port = 80",
+            ]
+            .join("\n"),
+        };
+        let base_path_line = match self.server.base_path() {
+            "" => "# base_path = \"/bunny\"".to_string(),
+            base_path => format!("base_path = \"{}\"", escape_toml_string(base_path)),
+        };
 
         format!(
             r#"# Bunnylol Configuration File
@@ -535,10 +935,21 @@ impl BunnylolConfig {
 # Options: "google" (default), "ddg", "bing"
 default_search = "{}"
 
+# Locale for regional sites (amazon.de, de.wikipedia.org) and languages, as
+# language[-REGION]. Override per command with [commands.<name>] locale.
+{}
+
 # Stock website provider
 # Options: "yahoo" (default), "finviz", "tradingview", "google", "investing"
 stock_provider = "{}"
 
+# Preferred site per category: search, stock, maps ("google", "osm", "apple",
+# "bing"), video ("youtube", "vimeo"), encyclopedia ("wikipedia",
+# "britannica") and shopping ("amazon", "ebay"). `search` and `stock` override
+# default_search and stock_provider. [providers.custom.<name>] defines your
+# own: a `category`, a `url` with {{}} for the query and an optional `homepage`.
+{}
+
 # User-defined bindings. Two variants, both as inline tables:
 #
 #   # URL binding: maps a name to a URL (use {{}} as a placeholder for args).
@@ -556,12 +967,61 @@ stock_provider = "{}"
 [user_bindings]
 {}
 
+# Regex pattern rules, tried in order against the whole input right after the
+# built-in prefixes (`$TICKER`, `r/sub`, ...) before any binding. Templates can
+# use capture groups: $0 (whole match), $1..$N, ${{N}}, ${{name}}; `$$` is a
+# literal `$`. Set exactly one of `url` (captures are URL-encoded) or
+# `command` (dispatched once, like a command binding). Optional `examples`
+# are checked at startup for rules that an earlier rule would shadow.
+{}
+
+# URL rewrite rules, applied in order to every resolved URL (built-ins,
+# bindings, patterns and the search fallback). `host` and `path` are regexes
+# (omit both to match every URL). Actions: `replace_host` / `replace_path`
+# (with $1, ${{name}} captures), `strip_params` (`utm_*` matches a prefix),
+# `https = true`, and `url` to wrap the URL ({{}} is the encoded URL). Every
+# matching rule fires; `bunnylol explain` shows which.
+{}
+
+# Per-command settings. [commands.github] and [commands.gitlab] take a `host`
+# for GitHub Enterprise or a self-hosted GitLab; every sub-route uses it.
+# GitHub also takes `default_org`, so `gh repo#12` means `<default_org>/repo#12`.
+# [commands.python], [commands.rust] and [commands.node] take a default docs
+# `version` (e.g. "3.9", "1.70" or "nightly", "18"). Those and [commands.mdn]
+# and [commands.godocs] take a `mirror` directory of local docs, opened instead
+# of the online page when it has that page.
+# Any other table name defines a named instance with its own command word:
+# set `type` to the built-in it behaves like (e.g. `ghe` next to `gh`).
+{}
+
+# Google account for gmail, docs, gsheets, gslides, gchat and maps: an index
+# (the /u/N/ account), an email, or a name from [google.accounts]. A leading
+# `@name`, `@N` or `@email` picks one per query (`gmail @work is:unread`).
+{}
+
+# Which resolved URLs may be opened. The server refuses to redirect anywhere
+# else; `cli = true` applies it to the CLI too. `schemes` defaults to https and
+# http (add "file" for local docs mirrors in the CLI). Host entries match the
+# host and its subdomains; with `allow_hosts` set, every other host is refused.
+{}
+
+# Who may use the server. Roles: "resolve" (queries, landing page), "history"
+# (also /api/history) and "admin" (also config editing). `protect` picks what
+# needs a role: "admin", "history" (the default) or "all" (everything but
+# /health). Sign in with a bearer token from [auth.tokens], basic auth for
+# [auth.users] (hash passwords with `bunnylol hash-password`), or a header set
+# by a reverse proxy: [auth.proxy] header = "X-Auth-Request-Email", with the
+# proxy's addresses in `trusted` and `default_role` / `roles` per user.
+{}
+
 # Command history settings
 [history]
 enabled = {}
 max_entries = {}
 
 # Server configuration (for bunnylol serve)
+# address: IP to listen on, or a Unix domain socket such as
+#   "unix:/run/bunnylol/bunnylol.sock" (port is then unused)
 # server_display_url: Public-facing URL shown in the bindings page
 #   Smart defaults when protocol is omitted:
 #     - "bunny.example.com" → "https://bunny.example.com"
@@ -570,22 +1030,51 @@ max_entries = {}
 #     - "https://bunny.example.com" → used as-is
 #     - "http://bunny.local" → used as-is
 #   If not set, defaults to http://localhost:{{port}}
+# admin_token: Bearer token for the /api/user_bindings admin API and the
+#   /bindings/edit page, which edit [user_bindings] in this file. Unset
+#   (the default) disables both.
+# trusted_proxies: Addresses of reverse proxies (e.g. nginx, or the Docker
+#   network) whose X-Forwarded-For / Forwarded headers give the real client
+#   address for history. [auth.proxy] trusts them too unless it lists its own.
+#   "unix" trusts connections over a Unix socket address.
+# base_path: Serve under a sub-path of a reverse proxy, e.g. "/bunny" for
+#   https://tools.corp/bunny/. server_display_url gets it appended.
+# [server.tls]: Serve HTTPS with a PEM certificate chain and key, reloaded
+#   when the files change. redirect_http_port adds an HTTP listener that
+#   redirects to HTTPS.
 [server]
 port = {}
 address = "{}"
 log_level = "{}"
+{}
+{}
+{}
+{}
+
 {}
 "#,
             browser_line,
             self.default_search,
+            locale_line,
             self.stock_provider,
+            providers_content,
             user_bindings_content,
+            patterns_content,
+            rewrites_content,
+            commands_content,
+            google_content,
+            url_policy_content,
+            auth_content,
             self.history.enabled,
             self.history.max_entries,
             self.server.port,
             self.server.address,
             self.server.log_level,
             server_display_url_line,
+            admin_token_line,
+            trusted_proxies_line,
+            base_path_line,
+            tls_content,
         )
     }
 }
@@ -799,6 +1288,58 @@ mod tests {
         assert_eq!(config.get_display_url(), "http://0.0.0.0:8000");
     }
 
+    #[test]
+    fn test_get_display_url_includes_base_path() {
+        let mut config = server_config_with_display_url("tools.corp");
+        config.base_path = "/bunny/".to_string();
+        assert_eq!(config.base_path(), "/bunny");
+        assert_eq!(config.mount_point(), "/bunny");
+        assert_eq!(config.get_display_url(), "https://tools.corp/bunny");
+        config.server_display_url = Some("https://tools.corp/bunny/".to_string());
+        assert_eq!(config.get_display_url(), "https://tools.corp/bunny");
+        config.server_display_url = None;
+        assert_eq!(config.get_display_url(), "http://localhost:8000/bunny");
+        assert_eq!(ServerConfig::default().mount_point(), "/");
+    }
+
+    #[test]
+    fn test_get_display_url_defaults_to_https_with_tls() {
+        let mut config = server_config_with_display_url("localhost:8443");
+        config.tls = Some(TlsConfig {
+            cert: "cert.pem".into(),
+            key: "key.pem".into(),
+            redirect_http_port: None,
+        });
+        assert_eq!(config.get_display_url(), "https://localhost:8443");
+        config.server_display_url = Some("http://bunny.local".to_string());
+        assert_eq!(config.get_display_url(), "http://bunny.local");
+        config.server_display_url = None;
+        assert_eq!(config.get_display_url(), "https://localhost:8000");
+    }
+
+    #[test]
+    fn test_unix_socket_address() {
+        let mut config = BunnylolConfig::default();
+        assert_eq!(config.server.unix_socket(), None);
+        config.server.address = "unix:/run/bunnylol/bunnylol.sock".to_string();
+        assert_eq!(
+            config.server.unix_socket(),
+            Some(Path::new("/run/bunnylol/bunnylol.sock"))
+        );
+        assert!(config.validate().is_ok());
+        config.server.address = "unix:".to_string();
+        assert!(config.validate().unwrap_err().contains("socket path"));
+    }
+
+    #[test]
+    fn test_validate_rejects_relative_base_path() {
+        let mut config = BunnylolConfig::default();
+        config.server.base_path = "bunny".to_string();
+        assert!(config.validate().unwrap_err().contains("base_path"));
+        config.server.base_path = "/bunny".to_string();
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_generated_config_drops_restart_note_and_documents_user_bindings() {
         // After PR #48, hot reload is supported. The generated default config
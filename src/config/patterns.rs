@@ -0,0 +1,550 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use super::BunnylolConfig;
+use super::user_bindings::{ResolvedBinding, escape_toml_string};
+
+/// A user-defined regex rule from `[[patterns]]` in the config file.
+///
+/// Rules are evaluated in file order against the **whole input**, in tier 1
+/// right after built-in prefixes like `$TICKER` and `r/sub`. The first rule
+/// whose regex matches wins.
+///
+/// ```toml
+/// [[patterns]]
+/// name = "jira"
+/// pattern = '^[A-Z]+-\d+$'
+/// url = "https://corp.atlassian.net/browse/$0"
+/// examples = ["PROJ-123"]
+///
+/// [[patterns]]
+/// name = "issue"
+/// pattern = '^#(\d+)$'
+/// command = "gh mycompany/main-repo/issues/$1"
+/// ```
+///
+/// ## Templates
+///
+/// `url` and `command` templates may reference capture groups as `$0`
+/// (whole match), `$1`…`$N`, `${N}` or `${name}`. `$$` is a literal `$`.
+/// Captures are URL-encoded when substituted into a `url`; `command`
+/// templates receive them verbatim and are dispatched into the registry
+/// exactly once, like `Command` user bindings (user bindings and patterns
+/// are not re-entered).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatternRule {
+    /// Name used in diagnostics and listings. Defaults to `patterns[N]`.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Regular expression matched against the whole input.
+    pub pattern: String,
+    /// URL template. Exactly one of `url` or `command` must be set.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Command template. Exactly one of `url` or `command` must be set.
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Sample inputs this rule is meant to handle. Used to check rule
+    /// ordering at startup and shown in listings.
+    #[serde(default)]
+    pub examples: Vec<String>,
+
+    /// Compiled `pattern`, filled in by [`BunnylolConfig::validate_patterns`]
+    /// or on first use.
+    #[serde(skip)]
+    regex: OnceLock<Option<Regex>>,
+}
+
+impl PartialEq for PatternRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.pattern == other.pattern
+            && self.url == other.url
+            && self.command == other.command
+            && self.description == other.description
+            && self.examples == other.examples
+    }
+}
+
+impl Eq for PatternRule {}
+
+impl PatternRule {
+    /// Build a URL rule. Mostly useful for tests and programmatic configs.
+    pub fn url(name: &str, pattern: &str, url: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            pattern: pattern.to_string(),
+            url: Some(url.to_string()),
+            command: None,
+            description: None,
+            examples: Vec::new(),
+            regex: OnceLock::new(),
+        }
+    }
+
+    /// Build a command rule. Mostly useful for tests and programmatic configs.
+    pub fn command(name: &str, pattern: &str, command: &str) -> Self {
+        Self {
+            command: Some(command.to_string()),
+            url: None,
+            ..Self::url(name, pattern, "")
+        }
+    }
+
+    /// Display label: the rule's `name`, or `patterns[N]` (1-based).
+    pub fn label(&self, index: usize) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("patterns[{}]", index + 1))
+    }
+
+    /// The URL or command template, for display.
+    pub fn display_target(&self) -> &str {
+        self.url
+            .as_deref()
+            .or(self.command.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Short label for display ("URL" or "CMD").
+    pub fn kind_label(&self) -> &'static str {
+        if self.url.is_some() { "URL" } else { "CMD" }
+    }
+
+    /// The compiled regex, or `None` if `pattern` is invalid.
+    fn regex(&self) -> Option<&Regex> {
+        self.regex
+            .get_or_init(|| Regex::new(&self.pattern).ok())
+            .as_ref()
+    }
+
+    /// Whether this rule's regex matches `input`.
+    pub fn is_match(&self, input: &str) -> bool {
+        self.regex().is_some_and(|re| re.is_match(input))
+    }
+
+    /// Apply this rule to `input`. Returns `None` if the regex does not
+    /// match; otherwise the expanded URL or rewritten command.
+    pub fn apply(&self, input: &str) -> Option<ResolvedBinding> {
+        let caps = self.regex()?.captures(input)?;
+        Some(match (&self.url, &self.command) {
+            (Some(url), _) => ResolvedBinding::Url(expand_captures(url, &caps, true)),
+            (None, Some(command)) => {
+                ResolvedBinding::Command(expand_captures(command, &caps, false))
+            }
+            (None, None) => return None,
+        })
+    }
+
+    /// `$N=value` pairs for every participating capture group, for traces.
+    pub fn describe_captures(&self, input: &str) -> Vec<String> {
+        let Some(caps) = self.regex().and_then(|re| re.captures(input)) else {
+            return Vec::new();
+        };
+        let names: Vec<Option<&str>> = self.regex().unwrap().capture_names().collect();
+        caps.iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(i, m)| {
+                let m = m?;
+                let key = names[i].map_or_else(|| i.to_string(), str::to_string);
+                Some(format!("${}={:?}", key, m.as_str()))
+            })
+            .collect()
+    }
+}
+
+/// Expand `$0`, `$N`, `${N}`, `${name}` and `$$` in `template` from `caps`.
+/// Unknown groups expand to the empty string.
+fn expand_captures(template: &str, caps: &Captures, encode: bool) -> String {
+    let lookup = |key: &str| -> String {
+        let value = match key.parse::<usize>() {
+            Ok(i) => caps.get(i),
+            Err(_) => caps.name(key),
+        }
+        .map_or("", |m| m.as_str());
+        if encode {
+            crate::utils::url_encoding::encode_url(value)
+        } else {
+            value.to_string()
+        }
+    };
+
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        if let Some(stripped) = after.strip_prefix('$') {
+            out.push('$');
+            rest = stripped;
+        } else if let Some(braced) = after.strip_prefix('{')
+            && let Some(close) = braced.find('}')
+        {
+            out.push_str(&lookup(&braced[..close]));
+            rest = &braced[close + 1..];
+        } else {
+            let digits = after.bytes().take_while(u8::is_ascii_digit).count();
+            if digits == 0 {
+                out.push('$');
+            } else {
+                out.push_str(&lookup(&after[..digits]));
+            }
+            rest = &after[digits..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A problem with `[[patterns]]` that does not prevent loading, but means a
+/// rule or binding will not fire the way its author expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternDiagnostic {
+    /// Label of the rule the diagnostic is about.
+    pub rule: String,
+    pub message: String,
+}
+
+impl BunnylolConfig {
+    /// Check every `[[patterns]]` rule compiles and has exactly one target.
+    /// Called when the config is loaded; an error here is fatal, like a TOML
+    /// syntax error.
+    pub fn validate_patterns(&self) -> Result<(), String> {
+        for (i, rule) in self.patterns.iter().enumerate() {
+            let label = rule.label(i);
+            if let Err(e) = Regex::new(&rule.pattern) {
+                return Err(format!(
+                    "pattern rule '{}' has an invalid regex: {}",
+                    label, e
+                ));
+            }
+            if rule.url.is_some() == rule.command.is_some() {
+                return Err(format!(
+                    "pattern rule '{}' must set exactly one of `url` or `command`",
+                    label
+                ));
+            }
+            // Warm the compiled-regex cache so the first request doesn't pay for it.
+            rule.regex();
+        }
+        Ok(())
+    }
+
+    /// The first `[[patterns]]` rule matching `input`, with its index.
+    pub fn match_pattern(&self, input: &str) -> Option<(usize, &PatternRule)> {
+        self.patterns
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.is_match(input))
+    }
+
+    /// Ordering and conflict diagnostics for `[[patterns]]`:
+    ///
+    /// - a rule that matches a built-in command or user binding name (patterns
+    ///   run first, so that name becomes unreachable when typed on its own);
+    /// - an `examples` entry that its own rule doesn't match, or that a
+    ///   built-in prefix or an earlier rule claims first.
+    pub fn validate_patterns_conflicts(
+        &self,
+        builtin_names: &HashSet<&'static str>,
+        is_builtin_prefix: impl Fn(&str) -> bool,
+    ) -> Vec<PatternDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut builtins: Vec<&str> = builtin_names.iter().copied().collect();
+        builtins.sort_unstable();
+        let mut user_bindings: Vec<&String> = self.user_bindings.keys().collect();
+        user_bindings.sort();
+
+        for (i, rule) in self.patterns.iter().enumerate() {
+            let label = rule.label(i);
+
+            let shadowed_builtins: Vec<&str> = builtins
+                .iter()
+                .copied()
+                .filter(|name| rule.is_match(name))
+                .collect();
+            if !shadowed_builtins.is_empty() {
+                diagnostics.push(PatternDiagnostic {
+                    rule: label.clone(),
+                    message: format!(
+                        "matches built-in command{} {}; patterns run before built-ins",
+                        if shadowed_builtins.len() == 1 {
+                            ""
+                        } else {
+                            "s"
+                        },
+                        quote_list(&shadowed_builtins)
+                    ),
+                });
+            }
+
+            let shadowed_bindings: Vec<&str> = user_bindings
+                .iter()
+                .map(|name| name.as_str())
+                .filter(|name| rule.is_match(name))
+                .collect();
+            if !shadowed_bindings.is_empty() {
+                diagnostics.push(PatternDiagnostic {
+                    rule: label.clone(),
+                    message: format!(
+                        "matches user binding{} {}; patterns run before user bindings",
+                        if shadowed_bindings.len() == 1 {
+                            ""
+                        } else {
+                            "s"
+                        },
+                        quote_list(&shadowed_bindings)
+                    ),
+                });
+            }
+
+            for example in &rule.examples {
+                let command = crate::utils::get_command_from_query_string(example);
+                let message = if is_builtin_prefix(command) {
+                    format!(
+                        "example '{}' is claimed by a built-in prefix handler first",
+                        example
+                    )
+                } else if let Some((j, earlier)) = self.match_pattern(example)
+                    && j < i
+                {
+                    format!(
+                        "example '{}' is claimed by earlier rule '{}'; move this rule above it",
+                        example,
+                        earlier.label(j)
+                    )
+                } else if !rule.is_match(example) {
+                    format!("example '{}' does not match this rule", example)
+                } else {
+                    continue;
+                };
+                diagnostics.push(PatternDiagnostic {
+                    rule: label.clone(),
+                    message,
+                });
+            }
+        }
+
+        diagnostics
+    }
+}
+
+fn quote_list(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|n| format!("'{}'", n))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Format one `[[patterns]]` entry as TOML.
+pub(super) fn format_pattern_rule_toml(rule: &PatternRule) -> String {
+    let mut lines = vec!["[[patterns]]".to_string()];
+    if let Some(name) = &rule.name {
+        lines.push(format!("name = \"{}\"", escape_toml_string(name)));
+    }
+    lines.push(format!(
+        "pattern = \"{}\"",
+        escape_toml_string(&rule.pattern)
+    ));
+    if let Some(url) = &rule.url {
+        lines.push(format!("url = \"{}\"", escape_toml_string(url)));
+    }
+    if let Some(command) = &rule.command {
+        lines.push(format!("command = \"{}\"", escape_toml_string(command)));
+    }
+    if let Some(description) = &rule.description {
+        lines.push(format!(
+            "description = \"{}\"",
+            escape_toml_string(description)
+        ));
+    }
+    if !rule.examples.is_empty() {
+        let examples: Vec<String> = rule
+            .examples
+            .iter()
+            .map(|e| format!("\"{}\"", escape_toml_string(e)))
+            .collect();
+        lines.push(format!("examples = [{}]", examples.join(", ")));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_patterns(patterns: Vec<PatternRule>) -> BunnylolConfig {
+        BunnylolConfig {
+            patterns,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_patterns_in_file_order() {
+        let toml_str = r#"
+            [[patterns]]
+            name = "jira"
+            pattern = '^[A-Z]+-\d+$'
+            url = "https://corp.atlassian.net/browse/$0"
+            examples = ["PROJ-123"]
+
+            [[patterns]]
+            pattern = '^#(\d+)$'
+            command = "gh mycompany/repo/issues/$1"
+        "#;
+        let config: BunnylolConfig = toml::from_str(toml_str).unwrap();
+        config.validate_patterns().unwrap();
+
+        assert_eq!(config.patterns.len(), 2);
+        assert_eq!(config.patterns[0].label(0), "jira");
+        assert_eq!(config.patterns[1].label(1), "patterns[2]");
+        assert_eq!(config.patterns[1].kind_label(), "CMD");
+    }
+
+    #[test]
+    fn test_validate_patterns_rejects_bad_regex_and_targets() {
+        let bad_regex = config_with_patterns(vec![PatternRule::url("bad", "^(", "https://x")]);
+        assert!(
+            bad_regex
+                .validate_patterns()
+                .unwrap_err()
+                .contains("invalid regex")
+        );
+
+        let mut both = PatternRule::url("both", "^x$", "https://x");
+        both.command = Some("gh".to_string());
+        let no_target = PatternRule {
+            url: None,
+            ..PatternRule::url("none", "^x$", "")
+        };
+        for rule in [both, no_target] {
+            let config = config_with_patterns(vec![rule]);
+            assert!(
+                config
+                    .validate_patterns()
+                    .unwrap_err()
+                    .contains("exactly one of `url` or `command`")
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply_expands_and_encodes_captures() {
+        let rule = PatternRule::url(
+            "commit",
+            r"^(?P<sha>[0-9a-f]{7,40})( (.+))?$",
+            "https://github.com/org/repo/commit/${sha}?q=$3&cost=$$5",
+        );
+        assert_eq!(
+            rule.apply("abc1234 two words"),
+            Some(ResolvedBinding::Url(
+                "https://github.com/org/repo/commit/abc1234?q=two%20words&cost=$5".to_string()
+            ))
+        );
+        assert_eq!(rule.apply("not-a-sha"), None);
+
+        let rule = PatternRule::command("issue", r"^#(\d+)$", "gh org/repo#$1");
+        assert_eq!(
+            rule.apply("#42"),
+            Some(ResolvedBinding::Command("gh org/repo#42".to_string()))
+        );
+        assert_eq!(rule.describe_captures("#42"), vec![r#"$1="42""#]);
+    }
+
+    #[test]
+    fn test_match_pattern_first_rule_wins() {
+        let config = config_with_patterns(vec![
+            PatternRule::url("any-ticket", r"^[A-Z]+-\d+$", "https://a/$0"),
+            PatternRule::url("proj", r"^PROJ-\d+$", "https://b/$0"),
+        ]);
+        let (index, rule) = config.match_pattern("PROJ-1").unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(rule.label(index), "any-ticket");
+        assert!(config.match_pattern("proj-1").is_none());
+    }
+
+    #[test]
+    fn test_validate_patterns_conflicts_reports_shadowing_and_ordering() {
+        let mut word = PatternRule::url("word", r"^[a-z]+$", "https://a/$0");
+        word.examples = vec!["hello".to_string()];
+        let mut proj = PatternRule::url("proj", r"^PROJ-\d+$", "https://b/$0");
+        proj.examples = vec!["PROJ-1".to_string(), "nope".to_string()];
+        let mut dollar = PatternRule::url("dollar", r"^\$\w+$", "https://c/$0");
+        dollar.examples = vec!["$META".to_string()];
+        let mut ticket = PatternRule::url("ticket", r"^[A-Z]+-\d+$", "https://d/$0");
+        ticket.examples = vec!["PROJ-2".to_string()];
+
+        let mut config = config_with_patterns(vec![word, proj, dollar, ticket]);
+        config.user_bindings.insert(
+            "cal".to_string(),
+            crate::config::UserBinding::Url {
+                url: "https://calendar.google.com".to_string(),
+                description: None,
+                override_builtin: false,
+            },
+        );
+
+        let builtins: HashSet<&'static str> = ["gh", "ig", "r"].into_iter().collect();
+        let diagnostics = config.validate_patterns_conflicts(&builtins, |cmd| cmd.starts_with('$'));
+        let actual: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.rule.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            actual,
+            vec![
+                (
+                    "word",
+                    "matches built-in commands 'gh', 'ig', 'r'; patterns run before built-ins"
+                ),
+                (
+                    "word",
+                    "matches user binding 'cal'; patterns run before user bindings"
+                ),
+                (
+                    "proj",
+                    "example 'nope' is claimed by earlier rule 'word'; move this rule above it"
+                ),
+                (
+                    "dollar",
+                    "example '$META' is claimed by a built-in prefix handler first"
+                ),
+                (
+                    "ticket",
+                    "example 'PROJ-2' is claimed by earlier rule 'proj'; move this rule above it"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_write_then_parse_roundtrip_with_patterns() {
+        let mut rule = PatternRule::url("jira", r#"^[A-Z]+-\d+$"#, "https://x/$0");
+        rule.description = Some("Jira \"ticket\"".to_string());
+        rule.examples = vec!["PROJ-1".to_string()];
+        let config = config_with_patterns(vec![
+            rule,
+            PatternRule::command("issue", r"^#(\d+)$", "gh org/repo#$1"),
+        ]);
+
+        let parsed: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(parsed.patterns, config.patterns);
+    }
+}
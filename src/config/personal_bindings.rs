@@ -0,0 +1,309 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use super::alias_migration::write_config_atomically;
+use super::binding_edits::validate_binding_name;
+use super::user_bindings::{format_toml_key, format_user_binding_toml};
+use super::{
+    BindingConflict, BindingEdit, BindingEditError, BunnylolConfig, UserBinding,
+    UserBindingsSnapshot, config_etag,
+};
+
+type BindingsByUser = BTreeMap<String, BTreeMap<String, UserBinding>>;
+
+/// Bindings each signed-in user keeps for themselves on a shared server.
+///
+/// The server merges a user's bindings over `[user_bindings]` for their
+/// requests (see [`BunnylolConfig::merge_personal_bindings`]), so they win
+/// over team bindings of the same name and follow the same `override` rules
+/// against built-ins. Users manage them through `/api/personal_bindings`.
+///
+/// Stored in `$XDG_DATA_HOME/bunnylol/personal_bindings.toml`, one table
+/// per user. The server owns the file and rewrites it on every change.
+///
+/// ```toml
+/// ["alice@corp.example"]
+/// standup = { url = "https://meet.corp.example/alice-team" }
+/// ```
+#[derive(Debug, Default)]
+pub struct PersonalBindings {
+    store: Option<PathBuf>,
+    users: RwLock<BindingsByUser>,
+}
+
+/// Which bindings of a merged config are a user's personal ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PersonalOverlay {
+    /// The signed-in user.
+    pub user: String,
+    /// Names of their personal bindings.
+    pub bindings: Vec<String>,
+}
+
+impl PersonalBindings {
+    /// Load the bindings from the data directory. A missing file means no
+    /// bindings yet; an unreadable one is reported and left alone.
+    pub fn load() -> Self {
+        Self::load_from(
+            BunnylolConfig::get_data_dir().map(|dir| dir.join("personal_bindings.toml")),
+        )
+    }
+
+    fn load_from(store: Option<PathBuf>) -> Self {
+        let Some(path) = store else {
+            return Self::default();
+        };
+        let users = match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).map_err(|e| e.to_string()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BindingsByUser::new()),
+            Err(e) => Err(e.to_string()),
+        };
+        match users {
+            Ok(users) => Self {
+                store: Some(path),
+                users: RwLock::new(users),
+            },
+            Err(e) => {
+                // Keep the file for the user to fix rather than overwrite it
+                eprintln!(
+                    "Warning: Failed to read personal bindings {:?}, so they are read-only until the server restarts: {}",
+                    path, e
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// `user`'s bindings and their ETag.
+    pub fn snapshot(&self, user: &str) -> UserBindingsSnapshot {
+        let users = self.users.read().expect("personal bindings lock poisoned");
+        snapshot_of(users.get(user).cloned().unwrap_or_default())
+    }
+
+    /// Merge `user`'s bindings, if they have any, over `config`.
+    pub fn merge_into(&self, config: &mut BunnylolConfig, user: &str) {
+        let users = self.users.read().expect("personal bindings lock poisoned");
+        if let Some(bindings) = users.get(user).filter(|bindings| !bindings.is_empty()) {
+            config.merge_personal_bindings(user, bindings.clone());
+        }
+    }
+
+    /// Apply `edit` to `user`'s binding `name`, if their bindings still have
+    /// the ETag `if_match`. Rejected if `conflicts` reports `name` once the
+    /// bindings are merged over `base` (a built-in would shadow it).
+    pub fn edit(
+        &self,
+        user: &str,
+        if_match: &str,
+        name: &str,
+        edit: BindingEdit,
+        base: &BunnylolConfig,
+        conflicts: impl FnOnce(&BunnylolConfig) -> Vec<BindingConflict>,
+    ) -> Result<UserBindingsSnapshot, BindingEditError> {
+        let mut users = self.users.write().expect("personal bindings lock poisoned");
+        let mut bindings = users.get(user).cloned().unwrap_or_default();
+        let etag = snapshot_of(bindings.clone()).etag;
+        if if_match != etag {
+            return Err(BindingEditError::Stale(etag));
+        }
+
+        let added = match edit {
+            BindingEdit::Create(_) if bindings.contains_key(name) => {
+                return Err(BindingEditError::Exists);
+            }
+            BindingEdit::Create(binding) | BindingEdit::Replace(binding) => {
+                validate_binding_name(name).map_err(BindingEditError::Invalid)?;
+                bindings.insert(name.to_string(), binding);
+                true
+            }
+            BindingEdit::Delete => {
+                bindings.remove(name).ok_or(BindingEditError::NotFound)?;
+                false
+            }
+        };
+
+        let mut merged = base.clone();
+        merged.merge_personal_bindings(user, bindings.clone());
+        if added && conflicts(&merged).iter().any(|c| c.name == name) {
+            return Err(BindingEditError::Invalid(format!(
+                "'{}' is a built-in command, which wins over a binding; set \"override\": true to shadow it",
+                name
+            )));
+        }
+
+        let mut updated = users.clone();
+        if bindings.is_empty() {
+            updated.remove(user);
+        } else {
+            updated.insert(user.to_string(), bindings.clone());
+        }
+        self.save(&updated).map_err(BindingEditError::Io)?;
+        *users = updated;
+
+        println!("Updated personal binding '{}' for {}", name, user);
+        Ok(snapshot_of(bindings))
+    }
+
+    fn save(&self, users: &BindingsByUser) -> Result<(), String> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or("Could not determine the data directory, or the personal bindings file there is unreadable")?;
+        if let Some(parent) = store.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        }
+        let sections: Vec<String> = users
+            .iter()
+            .map(|(user, bindings)| {
+                let mut lines = vec![format!("[{}]", format_toml_key(user))];
+                lines.extend(bindings_toml(bindings));
+                lines.join("\n")
+            })
+            .collect();
+        write_config_atomically(store, &format!("{}\n", sections.join("\n\n")))
+    }
+}
+
+fn bindings_toml(bindings: &BTreeMap<String, UserBinding>) -> Vec<String> {
+    bindings
+        .iter()
+        .map(|(name, binding)| format_user_binding_toml(name, binding))
+        .collect()
+}
+
+/// The ETag covers only this user's bindings, so edits by other users
+/// don't make theirs stale.
+fn snapshot_of(bindings: BTreeMap<String, UserBinding>) -> UserBindingsSnapshot {
+    UserBindingsSnapshot {
+        etag: config_etag(&bindings_toml(&bindings).join("\n")),
+        bindings,
+    }
+}
+
+impl BunnylolConfig {
+    /// Merge a user's personal bindings over this config: they replace
+    /// `[user_bindings]` entries of the same name.
+    pub fn merge_personal_bindings(&mut self, user: &str, bindings: BTreeMap<String, UserBinding>) {
+        self.personal = Some(PersonalOverlay {
+            user: user.to_string(),
+            bindings: bindings.keys().cloned().collect(),
+        });
+        self.user_bindings.extend(bindings);
+    }
+
+    /// Whether the binding `name` is one of the signed-in user's personal
+    /// bindings rather than a `[user_bindings]` entry.
+    pub fn is_personal_binding(&self, name: &str) -> bool {
+        self.personal
+            .as_ref()
+            .is_some_and(|overlay| overlay.bindings.iter().any(|binding| binding == name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> UserBinding {
+        UserBinding::Url {
+            url: url.to_string(),
+            description: None,
+            override_builtin: false,
+        }
+    }
+
+    fn no_conflicts(_: &BunnylolConfig) -> Vec<BindingConflict> {
+        Vec::new()
+    }
+
+    #[test]
+    fn test_edits_are_saved_per_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("personal_bindings.toml");
+        let personal = PersonalBindings::load_from(Some(store.clone()));
+        let base = BunnylolConfig::default();
+
+        let empty = personal.snapshot("alice@corp.example");
+        assert!(empty.bindings.is_empty());
+        let alice = personal
+            .edit(
+                "alice@corp.example",
+                &empty.etag,
+                "standup",
+                BindingEdit::Create(url("https://meet.corp.example/alice")),
+                &base,
+                no_conflicts,
+            )
+            .unwrap();
+        // Bob's ETag is unaffected by Alice's edit
+        personal
+            .edit(
+                "bob",
+                &empty.etag,
+                "standup",
+                BindingEdit::Create(url("https://meet.corp.example/bob")),
+                &base,
+                no_conflicts,
+            )
+            .unwrap();
+        assert_eq!(
+            personal.edit(
+                "alice@corp.example",
+                &empty.etag,
+                "standup",
+                BindingEdit::Delete,
+                &base,
+                no_conflicts,
+            ),
+            Err(BindingEditError::Stale(alice.etag.clone()))
+        );
+
+        let reloaded = PersonalBindings::load_from(Some(store));
+        assert_eq!(reloaded.snapshot("alice@corp.example"), alice);
+        let mut config = base.clone();
+        config
+            .user_bindings
+            .insert("standup".to_string(), url("https://meet.corp.example/team"));
+        reloaded.merge_into(&mut config, "bob");
+        assert_eq!(
+            config.user_bindings["standup"],
+            url("https://meet.corp.example/bob")
+        );
+        assert!(config.is_personal_binding("standup"));
+        assert!(!base.is_personal_binding("standup"));
+    }
+
+    #[test]
+    fn test_edit_rejects_shadowed_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let personal = PersonalBindings::load_from(Some(dir.path().join("personal.toml")));
+        let etag = personal.snapshot("alice").etag;
+        let err = personal
+            .edit(
+                "alice",
+                &etag,
+                "gh",
+                BindingEdit::Replace(url("https://example.com")),
+                &BunnylolConfig::default(),
+                |config| {
+                    vec![BindingConflict {
+                        name: "gh".to_string(),
+                        target: config.user_bindings["gh"].display_target().to_string(),
+                    }]
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(err, BindingEditError::Invalid(_)), "{err:?}");
+        assert!(personal.snapshot("alice").bindings.is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::{BunnylolConfig, PatternRule, UserBinding};
+
+/// File name the CLI looks for in the working directory and its parents.
+pub const PROJECT_FILE_NAME: &str = ".bunnylol.toml";
+
+/// A project-local `.bunnylol.toml`, checked into a repository so everyone
+/// working in it gets the same shortcuts (dashboards, runbooks, on-call).
+///
+/// Only `[user_bindings]` and `[[patterns]]` are allowed. The CLI merges the
+/// file over the user config for one invocation, once the file is trusted
+/// (see [`ProjectTrust`]): its bindings replace user bindings of the same
+/// name and its patterns are evaluated before the user's.
+///
+/// ```toml
+/// # ~/src/payments/.bunnylol.toml
+/// [user_bindings]
+/// dash = { url = "https://grafana.corp.example/d/payments", description = "Payments dashboard" }
+/// oncall = { url = "https://oncall.corp.example/schedules/payments" }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectFile {
+    #[serde(default)]
+    pub user_bindings: HashMap<String, UserBinding>,
+    #[serde(default)]
+    pub patterns: Vec<PatternRule>,
+}
+
+/// Where the project-local entries of a merged config came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectOverlay {
+    /// The `.bunnylol.toml` that was merged.
+    pub path: PathBuf,
+    /// Names of the bindings it defined.
+    pub bindings: Vec<String>,
+    /// How many of the leading `patterns` it defined.
+    pub patterns: usize,
+}
+
+impl ProjectFile {
+    /// Find the nearest `.bunnylol.toml` in `start` or one of its parents.
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    /// Parse a project file and check its patterns compile.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let project: ProjectFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+        let as_config = BunnylolConfig {
+            patterns: project.patterns.clone(),
+            ..Default::default()
+        };
+        as_config.validate_patterns()?;
+        Ok(project)
+    }
+}
+
+impl BunnylolConfig {
+    /// Merge a project file over this config: its bindings replace user
+    /// bindings of the same name, and its patterns run before the user's.
+    pub fn merge_project_file(&mut self, path: &Path, project: ProjectFile) {
+        let mut bindings: Vec<String> = project.user_bindings.keys().cloned().collect();
+        bindings.sort();
+        self.user_bindings.extend(project.user_bindings);
+
+        let patterns = project.patterns.len();
+        self.patterns.splice(0..0, project.patterns);
+
+        self.project = Some(ProjectOverlay {
+            path: path.to_path_buf(),
+            bindings,
+            patterns,
+        });
+    }
+
+    /// The project file a binding came from, or `None` if it came from the
+    /// user config.
+    pub fn binding_source(&self, name: &str) -> Option<&Path> {
+        self.project
+            .as_ref()
+            .filter(|overlay| overlay.bindings.iter().any(|binding| binding == name))
+            .map(|overlay| overlay.path.as_path())
+    }
+}
+
+/// Project files the user has agreed to load, keyed by path and pinned to
+/// a hash of their contents, so an edited file has to be trusted again.
+///
+/// Stored one `<hash> <path>` per line in
+/// `$XDG_DATA_HOME/bunnylol/trusted_projects`.
+#[derive(Debug, Default)]
+pub struct ProjectTrust {
+    store: Option<PathBuf>,
+    entries: HashMap<PathBuf, String>,
+}
+
+/// Whether a project file may be loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustState {
+    Trusted,
+    /// Never trusted before
+    Unknown,
+    /// Trusted, but the contents changed since
+    Changed,
+}
+
+impl ProjectTrust {
+    /// Load the allowlist from the data directory. A missing or unreadable
+    /// file is an empty allowlist.
+    pub fn load() -> Self {
+        Self::load_from(BunnylolConfig::get_data_dir().map(|dir| dir.join("trusted_projects")))
+    }
+
+    fn load_from(store: Option<PathBuf>) -> Self {
+        let entries = store
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter_map(|line| line.split_once(' '))
+                    .map(|(hash, path)| (PathBuf::from(path), hash.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { store, entries }
+    }
+
+    pub fn state(&self, path: &Path, contents: &str) -> TrustState {
+        match self.entries.get(path) {
+            Some(hash) if *hash == content_hash(contents) => TrustState::Trusted,
+            Some(_) => TrustState::Changed,
+            None => TrustState::Unknown,
+        }
+    }
+
+    /// Trust `path` with its current `contents` and save the allowlist.
+    pub fn trust(&mut self, path: &Path, contents: &str) -> Result<(), String> {
+        self.entries
+            .insert(path.to_path_buf(), content_hash(contents));
+        self.save()
+    }
+
+    /// Forget `path`. Returns whether it was trusted.
+    pub fn revoke(&mut self, path: &Path) -> Result<bool, String> {
+        let removed = self.entries.remove(path).is_some();
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or("Could not determine the data directory")?;
+        if let Some(parent) = store.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        }
+        let mut lines: Vec<String> = self
+            .entries
+            .iter()
+            .map(|(path, hash)| format!("{} {}", hash, path.display()))
+            .collect();
+        lines.sort();
+        lines.push(String::new());
+        fs::write(store, lines.join("\n"))
+            .map_err(|e| format!("Failed to write {}: {}", store.display(), e))
+    }
+}
+
+/// SHA-256 of the file contents. A match loads the file without asking, so
+/// it must not be feasible to craft a different file with the same hash.
+fn content_hash(contents: &str) -> String {
+    crate::utils::sha256_hex(contents.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_finds_nearest_project_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("service/src");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(ProjectFile::discover(&nested), None);
+
+        fs::write(dir.path().join(PROJECT_FILE_NAME), "").unwrap();
+        assert_eq!(
+            ProjectFile::discover(&nested),
+            Some(dir.path().join(PROJECT_FILE_NAME))
+        );
+
+        fs::write(dir.path().join("service").join(PROJECT_FILE_NAME), "").unwrap();
+        assert_eq!(
+            ProjectFile::discover(&nested),
+            Some(dir.path().join("service").join(PROJECT_FILE_NAME))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_other_tables_and_bad_patterns() {
+        assert!(ProjectFile::parse("browser = \"firefox\"").is_err());
+        assert!(ProjectFile::parse("[[patterns]]\npattern = '('\nurl = \"x\"").is_err());
+        let project =
+            ProjectFile::parse("[user_bindings]\ndash = { url = \"https://grafana.example/d/x\" }")
+                .unwrap();
+        assert!(project.user_bindings.contains_key("dash"));
+    }
+
+    #[test]
+    fn test_merge_project_file_over_user_config() {
+        let mut config: BunnylolConfig = toml::from_str(
+            r#"
+[user_bindings]
+dash = { url = "https://grafana.example/home" }
+cal = { url = "https://calendar.example" }
+
+[[patterns]]
+name = "user"
+pattern = '^x$'
+url = "https://user.example"
+"#,
+        )
+        .unwrap();
+        let project = ProjectFile::parse(
+            r#"
+[user_bindings]
+dash = { url = "https://grafana.example/d/payments" }
+
+[[patterns]]
+name = "project"
+pattern = '^x$'
+url = "https://project.example"
+"#,
+        )
+        .unwrap();
+        let path = Path::new("/src/payments/.bunnylol.toml");
+        config.merge_project_file(path, project);
+
+        assert_eq!(
+            config.user_bindings["dash"].display_target(),
+            "https://grafana.example/d/payments"
+        );
+        assert_eq!(config.binding_source("dash"), Some(path));
+        assert_eq!(config.binding_source("cal"), None);
+        assert_eq!(config.patterns[0].name.as_deref(), Some("project"));
+        assert_eq!(config.patterns[1].name.as_deref(), Some("user"));
+        assert_eq!(config.project.as_ref().unwrap().patterns, 1);
+    }
+
+    #[test]
+    fn test_trust_is_pinned_to_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("trusted_projects");
+        let project = Path::new("/src/payments/.bunnylol.toml");
+
+        let mut trust = ProjectTrust::load_from(Some(store.clone()));
+        assert_eq!(trust.state(project, "a"), TrustState::Unknown);
+        trust.trust(project, "a").unwrap();
+
+        let mut trust = ProjectTrust::load_from(Some(store));
+        assert_eq!(trust.state(project, "a"), TrustState::Trusted);
+        assert_eq!(trust.state(project, "b"), TrustState::Changed);
+        assert!(trust.revoke(project).unwrap());
+        assert_eq!(trust.state(project, "a"), TrustState::Unknown);
+    }
+}
@@ -0,0 +1,272 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::BunnylolConfig;
+use super::user_bindings::escape_toml_string;
+use crate::commands::providers::{self, ProviderCategory};
+
+/// Preferred site per provider category, and user-defined providers.
+///
+/// ```toml
+/// [providers]
+/// maps = "osm"
+/// video = "invidious"
+///
+/// [providers.custom.invidious]
+/// category = "video"
+/// url = "https://yewtu.be/search?q={}"
+/// ```
+///
+/// `stock` and `search` fall back to the older top-level `stock_provider`
+/// and `default_search` keys.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProvidersConfig {
+    #[serde(default)]
+    pub search: Option<String>,
+
+    #[serde(default)]
+    pub stock: Option<String>,
+
+    #[serde(default)]
+    pub maps: Option<String>,
+
+    #[serde(default)]
+    pub video: Option<String>,
+
+    #[serde(default)]
+    pub encyclopedia: Option<String>,
+
+    #[serde(default)]
+    pub shopping: Option<String>,
+
+    /// User-defined providers, by name.
+    #[serde(default)]
+    pub custom: HashMap<String, CustomProvider>,
+}
+
+/// A user-defined provider: a URL template for one category.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomProvider {
+    /// One of "search", "stock", "maps", "video", "encyclopedia", "shopping".
+    pub category: String,
+
+    /// Query URL; `{}` is replaced by the URL-encoded query.
+    pub url: String,
+
+    /// Page opened without a query. Defaults to the root of `url`'s site.
+    #[serde(default)]
+    pub homepage: Option<String>,
+}
+
+impl CustomProvider {
+    /// `homepage`, else the scheme and host of `url` (`https://yewtu.be/`).
+    pub fn homepage(&self) -> String {
+        if let Some(homepage) = &self.homepage {
+            return homepage.clone();
+        }
+        let host_start = self.url.find("://").map_or(0, |i| i + 3);
+        let host_end = self.url[host_start..]
+            .find(['/', '?', '#'])
+            .map_or(self.url.len(), |i| host_start + i);
+        format!("{}/", &self.url[..host_end])
+    }
+}
+
+impl ProvidersConfig {
+    /// The provider name set for `category`, if any.
+    pub fn preferred(&self, category: ProviderCategory) -> Option<&str> {
+        match category {
+            ProviderCategory::Search => self.search.as_deref(),
+            ProviderCategory::Stock => self.stock.as_deref(),
+            ProviderCategory::Maps => self.maps.as_deref(),
+            ProviderCategory::Video => self.video.as_deref(),
+            ProviderCategory::Encyclopedia => self.encyclopedia.as_deref(),
+            ProviderCategory::Shopping => self.shopping.as_deref(),
+        }
+    }
+
+    pub(super) fn to_toml(&self) -> String {
+        let mut lines = vec!["[providers]".to_string()];
+        for category in ProviderCategory::ALL {
+            if let Some(name) = self.preferred(category) {
+                lines.push(format!(
+                    "{} = \"{}\"",
+                    category.key(),
+                    escape_toml_string(name)
+                ));
+            }
+        }
+        let mut names: Vec<&String> = self.custom.keys().collect();
+        names.sort();
+        for name in names {
+            let provider = &self.custom[name];
+            lines.push(String::new());
+            lines.push(format!("[providers.custom.{}]", name));
+            lines.push(format!(
+                "category = \"{}\"",
+                escape_toml_string(&provider.category)
+            ));
+            lines.push(format!("url = \"{}\"", escape_toml_string(&provider.url)));
+            if let Some(homepage) = &provider.homepage {
+                lines.push(format!("homepage = \"{}\"", escape_toml_string(homepage)));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl BunnylolConfig {
+    /// Check `[providers]`: custom provider templates, and that each
+    /// preferred provider exists in its category. Called when the config is
+    /// loaded; an error here is fatal.
+    pub fn validate_providers(&self) -> Result<(), String> {
+        for (name, provider) in &self.providers.custom {
+            let valid_name = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid_name {
+                return Err(format!(
+                    "[providers.custom] has an invalid name '{}' (expected a word like \"invidious\")",
+                    name
+                ));
+            }
+            let Some(category) = ProviderCategory::parse(&provider.category) else {
+                return Err(format!(
+                    "[providers.custom.{}] has unknown category '{}' (expected one of: {})",
+                    name,
+                    provider.category,
+                    ProviderCategory::ALL.map(ProviderCategory::key).join(", ")
+                ));
+            };
+            if providers::find_builtin(category, name).is_some() {
+                return Err(format!(
+                    "[providers.custom.{}] has the name of a built-in {} provider",
+                    name, provider.category
+                ));
+            }
+            for url in std::iter::once(&provider.url).chain(&provider.homepage) {
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    return Err(format!(
+                        "[providers.custom.{}] URL '{}' must start with http:// or https://",
+                        name, url
+                    ));
+                }
+            }
+            if !provider.url.contains("{}") {
+                return Err(format!(
+                    "[providers.custom.{}] url must contain {{}} for the query",
+                    name
+                ));
+            }
+        }
+        for category in ProviderCategory::ALL {
+            if let Some(name) = self.providers.preferred(category)
+                && providers::find(category, name, Some(self)).is_none()
+            {
+                return Err(format!(
+                    "[providers] {} = \"{}\" is not a {} provider (built-in: {})",
+                    category.key(),
+                    name,
+                    category.key(),
+                    providers::builtin_names(category).join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(body: &str) -> BunnylolConfig {
+        toml::from_str(body).expect("valid TOML")
+    }
+
+    #[test]
+    fn test_custom_provider_homepage_defaults_to_site_root() {
+        let provider = CustomProvider {
+            category: "video".to_string(),
+            url: "https://yewtu.be/search?q={}".to_string(),
+            homepage: None,
+        };
+        assert_eq!(provider.homepage(), "https://yewtu.be/");
+        let provider = CustomProvider {
+            homepage: Some("https://yewtu.be/feed/popular".to_string()),
+            ..provider
+        };
+        assert_eq!(provider.homepage(), "https://yewtu.be/feed/popular");
+    }
+
+    #[test]
+    fn test_validate_providers() {
+        let cfg = config(
+            r#"
+[providers]
+maps = "osm"
+video = "invidious"
+
+[providers.custom.invidious]
+category = "video"
+url = "https://yewtu.be/search?q={}"
+"#,
+        );
+        cfg.validate_providers().unwrap();
+
+        for (body, expected) in [
+            ("[providers]\nmaps = \"mapquest\"", "is not a maps provider"),
+            (
+                "[providers]\nvideo = \"wiki\"\n[providers.custom.wiki]\ncategory = \"encyclopedia\"\nurl = \"https://wiki.example/{}\"",
+                "is not a video provider",
+            ),
+            (
+                "[providers.custom.x]\ncategory = \"music\"\nurl = \"https://x.example/{}\"",
+                "unknown category",
+            ),
+            (
+                "[providers.custom.x]\ncategory = \"maps\"\nurl = \"https://x.example/\"",
+                "must contain {}",
+            ),
+            (
+                "[providers.custom.x]\ncategory = \"maps\"\nurl = \"x.example/{}\"",
+                "must start with http",
+            ),
+            (
+                "[providers.custom.osm]\ncategory = \"maps\"\nurl = \"https://x.example/{}\"",
+                "built-in maps provider",
+            ),
+        ] {
+            let err = config(body).validate_providers().unwrap_err();
+            assert!(err.contains(expected), "{}: {}", body, err);
+        }
+    }
+
+    #[test]
+    fn test_providers_to_toml_roundtrip() {
+        let cfg = config(
+            r#"
+[providers]
+stock = "finviz"
+
+[providers.custom.ddgl]
+category = "search"
+url = "https://lite.duckduckgo.com/lite/?q={}"
+homepage = "https://lite.duckduckgo.com/lite/"
+"#,
+        );
+        let parsed: BunnylolConfig = toml::from_str(&cfg.providers.to_toml()).unwrap();
+        assert_eq!(parsed.providers, cfg.providers);
+    }
+}
@@ -0,0 +1,433 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::BunnylolConfig;
+use super::user_bindings::escape_toml_string;
+use crate::utils::url_encoding::encode_url;
+
+/// A URL rewrite rule from `[[rewrites]]` in the config file.
+///
+/// Rewrites run on the final URL of every resolution (built-ins, user
+/// bindings, patterns and the search fallback), after all tiers. Every
+/// matching rule fires, in file order, each seeing the previous rule's
+/// output.
+///
+/// ```toml
+/// [[rewrites]]
+/// name = "old-reddit"
+/// host = '^(www\.)?reddit\.com$'
+/// replace_host = "old.reddit.com"
+///
+/// [[rewrites]]
+/// name = "no-tracking"
+/// strip_params = ["utm_*", "fbclid", "gclid"]
+///
+/// [[rewrites]]
+/// name = "proxy"
+/// host = '\.partner\.example$'
+/// url = "https://proxy.corp.example/fetch?u={}"
+/// ```
+///
+/// ## Matching
+///
+/// `host` and `path` are regexes matched against the URL's host (with any
+/// `:port`) and path. A rule with neither matches every URL with a
+/// `scheme://` prefix; relative URLs (the server's `/mirror/...`) are never
+/// rewritten.
+///
+/// ## Actions
+///
+/// Applied in this order: `replace_host` and `replace_path` replace the
+/// part their regex matched (`$1`, `${name}` refer to its captures; without
+/// a regex they replace the whole part), `strip_params` drops query
+/// parameters by name (`utm_*` matches a prefix), `https = true` upgrades
+/// `http://`, and `url` wraps the result, with `{}` for the URL-encoded URL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RewriteRule {
+    /// Name used in traces and diagnostics. Defaults to `rewrites[N]`.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Regex matched against the host.
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Regex matched against the path.
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub replace_host: Option<String>,
+    #[serde(default)]
+    pub replace_path: Option<String>,
+    /// Query parameter names to remove; a trailing `*` matches a prefix.
+    #[serde(default)]
+    pub strip_params: Vec<String>,
+    #[serde(default)]
+    pub https: bool,
+    /// Template wrapping the whole URL; `{}` is the URL-encoded URL.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Compiled `host` and `path`, filled in by
+    /// [`BunnylolConfig::validate_rewrites`] or on first use.
+    #[serde(skip)]
+    regexes: OnceLock<(Option<Regex>, Option<Regex>)>,
+}
+
+impl PartialEq for RewriteRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.host == other.host
+            && self.path == other.path
+            && self.replace_host == other.replace_host
+            && self.replace_path == other.replace_path
+            && self.strip_params == other.strip_params
+            && self.https == other.https
+            && self.url == other.url
+    }
+}
+
+impl Eq for RewriteRule {}
+
+/// A URL split into the parts rewrite rules look at.
+struct UrlParts<'a> {
+    scheme: &'a str,
+    host: &'a str,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> UrlParts<'a> {
+    /// Split `scheme://host/path?query#fragment`. `None` without `://`.
+    fn parse(url: &'a str) -> Option<Self> {
+        let (scheme, rest) = url.split_once("://")?;
+        if scheme.is_empty()
+            || !scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        {
+            return None;
+        }
+        let (rest, fragment) = match rest.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (rest, None),
+        };
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None),
+        };
+        let host_end = rest.find('/').unwrap_or(rest.len());
+        Some(UrlParts {
+            scheme,
+            host: &rest[..host_end],
+            path: &rest[host_end..],
+            query,
+            fragment,
+        })
+    }
+}
+
+impl RewriteRule {
+    /// A rule that only matches and replaces the host. Mostly useful for
+    /// tests and programmatic configs.
+    pub fn replace_host(name: &str, host: &str, replace_host: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            host: Some(host.to_string()),
+            path: None,
+            replace_host: Some(replace_host.to_string()),
+            replace_path: None,
+            strip_params: Vec::new(),
+            https: false,
+            url: None,
+            regexes: OnceLock::new(),
+        }
+    }
+
+    /// Display label: the rule's `name`, or `rewrites[N]` (1-based).
+    pub fn label(&self, index: usize) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("rewrites[{}]", index + 1))
+    }
+
+    /// The compiled `host` and `path` regexes; an invalid one is `None`,
+    /// and never matches.
+    fn regexes(&self) -> &(Option<Regex>, Option<Regex>) {
+        self.regexes.get_or_init(|| {
+            let compile = |re: &Option<String>| re.as_deref().and_then(|re| Regex::new(re).ok());
+            (compile(&self.host), compile(&self.path))
+        })
+    }
+
+    /// Apply this rule to `url`. `None` if it doesn't match or leaves the
+    /// URL unchanged.
+    pub fn apply(&self, url: &str) -> Option<String> {
+        let parts = UrlParts::parse(url)?;
+        let (host_re, path_re) = self.regexes();
+        let matches = |pattern: &Option<String>, re: &Option<Regex>, part: &str| {
+            pattern.is_none() || re.as_ref().is_some_and(|re| re.is_match(part))
+        };
+        if !matches(&self.host, host_re, parts.host) || !matches(&self.path, path_re, parts.path) {
+            return None;
+        }
+        let replace = |re: &Option<Regex>, part: &str, replacement: &Option<String>| match (
+            re,
+            replacement,
+        ) {
+            (_, None) => part.to_string(),
+            (Some(re), Some(replacement)) => re.replace(part, replacement.as_str()).into_owned(),
+            (None, Some(replacement)) => replacement.clone(),
+        };
+
+        let scheme = if self.https && parts.scheme == "http" {
+            "https"
+        } else {
+            parts.scheme
+        };
+        let host = replace(host_re, parts.host, &self.replace_host);
+        let path = replace(path_re, parts.path, &self.replace_path);
+        let query = parts.query.map(|query| {
+            query
+                .split('&')
+                .filter(|param| {
+                    let key = param.split_once('=').map_or(*param, |(key, _)| key);
+                    !self
+                        .strip_params
+                        .iter()
+                        .any(|strip| match strip.strip_suffix('*') {
+                            Some(prefix) => key.starts_with(prefix),
+                            None => key == strip,
+                        })
+                })
+                .collect::<Vec<_>>()
+                .join("&")
+        });
+
+        let mut rewritten = format!("{}://{}{}", scheme, host, path);
+        if let Some(query) = query.filter(|query| !query.is_empty()) {
+            rewritten.push('?');
+            rewritten.push_str(&query);
+        }
+        if let Some(fragment) = parts.fragment {
+            rewritten.push('#');
+            rewritten.push_str(fragment);
+        }
+        if let Some(template) = &self.url {
+            rewritten = template.replace("{}", &encode_url(&rewritten));
+        }
+        (rewritten != url).then_some(rewritten)
+    }
+}
+
+/// One `[[rewrites]]` rule that changed a URL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AppliedRewrite {
+    /// Label of the rule.
+    pub rule: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl BunnylolConfig {
+    /// Check every `[[rewrites]]` rule compiles and does something. Called
+    /// when the config is loaded; an error here is fatal.
+    pub fn validate_rewrites(&self) -> Result<(), String> {
+        for (i, rule) in self.rewrites.iter().enumerate() {
+            let label = rule.label(i);
+            for (key, pattern) in [("host", &rule.host), ("path", &rule.path)] {
+                if let Some(pattern) = pattern
+                    && let Err(e) = Regex::new(pattern)
+                {
+                    return Err(format!(
+                        "rewrite rule '{}' has an invalid `{}` regex: {}",
+                        label, key, e
+                    ));
+                }
+            }
+            if rule.replace_host.is_none()
+                && rule.replace_path.is_none()
+                && rule.strip_params.is_empty()
+                && !rule.https
+                && rule.url.is_none()
+            {
+                return Err(format!(
+                    "rewrite rule '{}' must set at least one of `replace_host`, `replace_path`, `strip_params`, `https` or `url`",
+                    label
+                ));
+            }
+            if let Some(url) = &rule.url
+                && !url.contains("{}")
+            {
+                return Err(format!(
+                    "rewrite rule '{}' has a `url` without {{}} for the URL",
+                    label
+                ));
+            }
+            // Warm the compiled-regex cache so the first request doesn't pay for it.
+            rule.regexes();
+        }
+        Ok(())
+    }
+
+    /// Run `url` through every matching `[[rewrites]]` rule in order,
+    /// returning the final URL and the rules that changed it.
+    pub fn apply_rewrites(&self, url: &str) -> (String, Vec<AppliedRewrite>) {
+        let mut url = url.to_string();
+        let mut applied = Vec::new();
+        for (i, rule) in self.rewrites.iter().enumerate() {
+            if let Some(rewritten) = rule.apply(&url) {
+                applied.push(AppliedRewrite {
+                    rule: rule.label(i),
+                    before: std::mem::replace(&mut url, rewritten.clone()),
+                    after: rewritten,
+                });
+            }
+        }
+        (url, applied)
+    }
+}
+
+/// Format one `[[rewrites]]` entry as TOML.
+pub(super) fn format_rewrite_rule_toml(rule: &RewriteRule) -> String {
+    let mut lines = vec!["[[rewrites]]".to_string()];
+    let fields = [
+        ("name", &rule.name),
+        ("host", &rule.host),
+        ("path", &rule.path),
+        ("replace_host", &rule.replace_host),
+        ("replace_path", &rule.replace_path),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            lines.push(format!("{} = \"{}\"", key, escape_toml_string(value)));
+        }
+    }
+    if !rule.strip_params.is_empty() {
+        let params: Vec<String> = rule
+            .strip_params
+            .iter()
+            .map(|p| format!("\"{}\"", escape_toml_string(p)))
+            .collect();
+        lines.push(format!("strip_params = [{}]", params.join(", ")));
+    }
+    if rule.https {
+        lines.push("https = true".to_string());
+    }
+    if let Some(url) = &rule.url {
+        lines.push(format!("url = \"{}\"", escape_toml_string(url)));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(body: &str) -> BunnylolConfig {
+        let config: BunnylolConfig = toml::from_str(body).expect("valid TOML");
+        config.validate_rewrites().expect("valid rewrites");
+        config
+    }
+
+    #[test]
+    fn test_host_and_path_replacement() {
+        let rule =
+            RewriteRule::replace_host("old-reddit", r"^(www\.)?reddit\.com$", "old.reddit.com");
+        assert_eq!(
+            rule.apply("https://www.reddit.com/r/rust?sort=new#top"),
+            Some("https://old.reddit.com/r/rust?sort=new#top".to_string())
+        );
+        assert_eq!(rule.apply("https://old.reddit.com/r/rust"), None);
+        assert_eq!(rule.apply("/mirror/abc/index.html"), None);
+
+        let rule = RewriteRule {
+            host: None,
+            replace_host: None,
+            path: Some(r"^/wiki/(.+)$".to_string()),
+            replace_path: Some("/w/index.php?title=$1".to_string()),
+            ..RewriteRule::replace_host("wiki", "", "")
+        };
+        assert_eq!(
+            rule.apply("https://en.wikipedia.org/wiki/Rust"),
+            Some("https://en.wikipedia.org/w/index.php?title=Rust".to_string())
+        );
+    }
+
+    #[test]
+    fn test_strip_params_https_and_wrap() {
+        let cfg = config(
+            r#"
+[[rewrites]]
+name = "no-tracking"
+strip_params = ["utm_*", "fbclid"]
+
+[[rewrites]]
+https = true
+
+[[rewrites]]
+name = "proxy"
+host = '\.partner\.example$'
+url = "https://proxy.corp.example/fetch?u={}"
+"#,
+        );
+        let (url, applied) =
+            cfg.apply_rewrites("http://shop.partner.example/a?utm_source=x&id=7&fbclid=y#b");
+        assert_eq!(
+            url,
+            "https://proxy.corp.example/fetch?u=https://shop.partner.example/a?id%3D7%23b"
+        );
+        let labels: Vec<&str> = applied.iter().map(|a| a.rule.as_str()).collect();
+        assert_eq!(labels, vec!["no-tracking", "rewrites[2]", "proxy"]);
+        assert_eq!(
+            applied[0].after,
+            "http://shop.partner.example/a?id=7#b".to_string()
+        );
+
+        // Dropping every parameter drops the `?`
+        let (url, _) = cfg.apply_rewrites("https://example.com/?utm_medium=email");
+        assert_eq!(url, "https://example.com/");
+    }
+
+    #[test]
+    fn test_validate_rewrites() {
+        for (body, expected) in [
+            (
+                "[[rewrites]]\nhost = \"(\"\nhttps = true",
+                "invalid `host` regex",
+            ),
+            ("[[rewrites]]\nhost = \"x\"", "must set at least one"),
+            ("[[rewrites]]\nurl = \"https://proxy/\"", "without {}"),
+        ] {
+            let cfg: BunnylolConfig = toml::from_str(body).unwrap();
+            let err = cfg.validate_rewrites().unwrap_err();
+            assert!(err.contains(expected), "{}: {}", body, err);
+        }
+    }
+
+    #[test]
+    fn test_format_rewrite_rule_roundtrip() {
+        let cfg = config(
+            r#"
+[[rewrites]]
+name = "nitter"
+host = '^(www\.)?twitter\.com$'
+replace_host = "nitter.net"
+strip_params = ["s", "t"]
+https = true
+"#,
+        );
+        let parsed: BunnylolConfig =
+            toml::from_str(&format_rewrite_rule_toml(&cfg.rewrites[0])).unwrap();
+        assert_eq!(parsed.rewrites, cfg.rewrites);
+    }
+}
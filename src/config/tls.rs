@@ -0,0 +1,161 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::BunnylolConfig;
+use super::user_bindings::escape_toml_string;
+
+/// HTTPS served by bunnylol itself (`[server.tls]`), for deployments
+/// without a reverse proxy to terminate TLS.
+///
+/// ```toml
+/// [server.tls]
+/// cert = "/etc/letsencrypt/live/bunny.example.com/fullchain.pem"
+/// key = "/etc/letsencrypt/live/bunny.example.com/privkey.pem"
+/// redirect_http_port = 80
+/// ```
+///
+/// `cert` is a PEM certificate chain and `key` its PEM private key (RSA,
+/// PKCS8 or SEC1). The server checks both files for changes and restarts
+/// its listener with a renewed certificate (on a Unix or systemd socket it
+/// swaps the certificate in place), keeping the old one if the new files
+/// don't load. With `redirect_http_port`, a plain HTTP listener on that port
+/// redirects every request to HTTPS on the configured host; it can't be
+/// used with a `unix:` address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// PEM certificate chain, leaf first.
+    pub cert: PathBuf,
+
+    /// PEM private key for `cert`.
+    pub key: PathBuf,
+
+    /// Port for an HTTP listener that redirects to HTTPS.
+    #[serde(default)]
+    pub redirect_http_port: Option<u16>,
+}
+
+impl TlsConfig {
+    /// The `[server.tls]` table, for config files written by bunnylol.
+    pub(super) fn to_toml(&self) -> String {
+        let mut lines = vec![
+            "[server.tls]".to_string(),
+            format!(
+                "cert = \"{}\"",
+                escape_toml_string(&self.cert.to_string_lossy())
+            ),
+            format!(
+                "key = \"{}\"",
+                escape_toml_string(&self.key.to_string_lossy())
+            ),
+        ];
+        if let Some(port) = self.redirect_http_port {
+            lines.push(format!("redirect_http_port = {}", port));
+        }
+        lines.join("\n")
+    }
+}
+
+impl BunnylolConfig {
+    pub(super) fn validate_tls(&self) -> Result<(), String> {
+        let Some(tls) = &self.server.tls else {
+            return Ok(());
+        };
+        if tls.cert.as_os_str().is_empty() || tls.key.as_os_str().is_empty() {
+            return Err("[server.tls] needs both cert and key paths".to_string());
+        }
+        if tls.redirect_http_port.is_some() && self.server.unix_socket().is_some() {
+            return Err(
+                "[server.tls] redirect_http_port needs a TCP address; remove it or set address to an IP"
+                    .to_string(),
+            );
+        }
+        if tls.redirect_http_port == Some(self.server.port) {
+            return Err(format!(
+                "[server.tls] redirect_http_port {} is the HTTPS port; pick another (usually 80)",
+                self.server.port
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> Result<BunnylolConfig, String> {
+        let config: BunnylolConfig = toml::from_str(toml).map_err(|e| e.to_string())?;
+        config.validate().map(|_| config)
+    }
+
+    #[test]
+    fn test_tls_round_trips_through_to_toml() {
+        let config = parse(
+            r#"
+            [server]
+            port = 443
+
+            [server.tls]
+            cert = "/etc/bunnylol/cert.pem"
+            key = "/etc/bunnylol/key.pem"
+            redirect_http_port = 80
+            "#,
+        )
+        .unwrap();
+        let tls = config.server.tls.clone().unwrap();
+        assert_eq!(tls.redirect_http_port, Some(80));
+
+        let reparsed = parse(&format!("[server]\nport = 443\n\n{}", tls.to_toml())).unwrap();
+        assert_eq!(reparsed.server.tls, Some(tls));
+    }
+
+    #[test]
+    fn test_tls_validation() {
+        let err = parse(
+            r#"
+            [server.tls]
+            cert = "/etc/bunnylol/cert.pem"
+            key = ""
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.contains("cert and key"), "{err}");
+
+        let err = parse(
+            r#"
+            [server]
+            port = 8443
+
+            [server.tls]
+            cert = "cert.pem"
+            key = "key.pem"
+            redirect_http_port = 8443
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.contains("redirect_http_port"), "{err}");
+
+        let err = parse(
+            r#"
+            [server]
+            address = "unix:/run/bunnylol/bunnylol.sock"
+
+            [server.tls]
+            cert = "cert.pem"
+            key = "key.pem"
+            redirect_http_port = 80
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.contains("TCP address"), "{err}");
+    }
+}
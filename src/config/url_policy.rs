@@ -0,0 +1,297 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use super::BunnylolConfig;
+use super::user_bindings::escape_toml_string;
+
+/// Which resolved URLs may be opened (`[url_policy]`).
+///
+/// The server checks every redirect against it, so a binding or rewrite
+/// can't turn a shared instance into an open redirector or hand browsers a
+/// `javascript:`, `data:` or `file:` URL. The CLI checks it too with
+/// `cli = true`.
+///
+/// ```toml
+/// [url_policy]
+/// schemes = ["https", "http"]       # the default
+/// allow_hosts = ["corp.example", "github.com"]
+/// deny_hosts = ["evil.example"]
+/// cli = true
+/// ```
+///
+/// A host entry matches that host and its subdomains. With `allow_hosts`
+/// set, every other host is refused; `deny_hosts` is checked first. Hosts
+/// are read the way browsers read them (`https:evil.example` is
+/// `evil.example`). URLs with control characters are always refused. Paths on the server itself
+/// (`/mirror/...`) are always allowed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UrlPolicy {
+    /// Allowed URL schemes, lowercase.
+    #[serde(default = "default_schemes")]
+    pub schemes: Vec<String>,
+
+    /// If non-empty, the only hosts (and their subdomains) allowed.
+    #[serde(default)]
+    pub allow_hosts: Vec<String>,
+
+    /// Hosts (and their subdomains) never allowed.
+    #[serde(default)]
+    pub deny_hosts: Vec<String>,
+
+    /// Also enforce the policy in the CLI. Add "file" to `schemes` to keep
+    /// opening local docs mirrors.
+    #[serde(default)]
+    pub cli: bool,
+}
+
+impl Default for UrlPolicy {
+    fn default() -> Self {
+        Self {
+            schemes: default_schemes(),
+            allow_hosts: Vec::new(),
+            deny_hosts: Vec::new(),
+            cli: false,
+        }
+    }
+}
+
+fn default_schemes() -> Vec<String> {
+    vec!["https".to_string(), "http".to_string()]
+}
+
+fn is_scheme(scheme: &str) -> bool {
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+}
+
+/// Whether `host` is `entry` or one of its subdomains.
+fn host_matches(host: &str, entry: &str) -> bool {
+    host == entry
+        || host
+            .strip_suffix(entry)
+            .is_some_and(|rest| rest.ends_with('.'))
+}
+
+impl UrlPolicy {
+    /// `Ok` if `url` may be opened, else why not.
+    pub fn check(&self, url: &str) -> Result<(), String> {
+        if url.chars().any(char::is_control) {
+            return Err("URL contains control characters".to_string());
+        }
+        // A path on this server. `//host` and `/\host` are other sites to a browser.
+        if url.starts_with('/') && !url[1..].starts_with(['/', '\\']) {
+            return Ok(());
+        }
+        // Parse the way a browser does, so `https:evil.example`,
+        // `https:\\evil.example` and `https://evil%2Eexample` all have a host
+        let parsed = url::Url::parse(url).map_err(|_| "not an absolute URL".to_string())?;
+        let scheme = parsed.scheme();
+        if !self.schemes.iter().any(|allowed| allowed == scheme) {
+            return Err(format!("scheme '{}:' is not allowed", scheme));
+        }
+
+        let host = match parsed.host() {
+            Some(url::Host::Domain(domain)) => Some(domain.trim_end_matches('.').to_string()),
+            Some(url::Host::Ipv4(ip)) => Some(ip.to_string()),
+            Some(url::Host::Ipv6(ip)) => Some(ip.to_string()),
+            None => None,
+        }
+        .filter(|host| !host.is_empty());
+        if let Some(host) = &host
+            && let Some(entry) = self
+                .deny_hosts
+                .iter()
+                .find(|entry| host_matches(host, entry))
+        {
+            return Err(format!("host '{}' is denied by '{}'", host, entry));
+        }
+        if !self.allow_hosts.is_empty()
+            && !host.as_ref().is_some_and(|host| {
+                self.allow_hosts
+                    .iter()
+                    .any(|entry| host_matches(host, entry))
+            })
+        {
+            return Err(format!(
+                "host '{}' is not in allow_hosts",
+                host.unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+
+    pub(super) fn to_toml(&self) -> String {
+        let list = |values: &[String]| {
+            values
+                .iter()
+                .map(|v| format!("\"{}\"", escape_toml_string(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut lines = vec![
+            "[url_policy]".to_string(),
+            format!("schemes = [{}]", list(&self.schemes)),
+        ];
+        if !self.allow_hosts.is_empty() {
+            lines.push(format!("allow_hosts = [{}]", list(&self.allow_hosts)));
+        }
+        if !self.deny_hosts.is_empty() {
+            lines.push(format!("deny_hosts = [{}]", list(&self.deny_hosts)));
+        }
+        if self.cli {
+            lines.push("cli = true".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+impl BunnylolConfig {
+    /// Check `[url_policy]` schemes and host entries. Called when the config
+    /// is loaded; an error here is fatal.
+    pub fn validate_url_policy(&self) -> Result<(), String> {
+        let policy = &self.url_policy;
+        if policy.schemes.is_empty() {
+            return Err("[url_policy] schemes must allow at least one scheme".to_string());
+        }
+        for scheme in &policy.schemes {
+            if !is_scheme(scheme) || scheme.chars().any(|c| c.is_ascii_uppercase()) {
+                return Err(format!(
+                    "[url_policy] scheme '{}' must be a lowercase scheme name like \"https\" (no ':' or '//')",
+                    scheme
+                ));
+            }
+        }
+        for (key, hosts) in [
+            ("allow_hosts", &policy.allow_hosts),
+            ("deny_hosts", &policy.deny_hosts),
+        ] {
+            for host in hosts {
+                let valid = !host.is_empty()
+                    && host
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-.".contains(c))
+                    && !host.starts_with('.')
+                    && !host.ends_with('.');
+                if !valid {
+                    return Err(format!(
+                        "[url_policy] {} entry '{}' must be a lowercase host name like \"example.com\"",
+                        key, host
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_allows_only_web_urls() {
+        let policy = UrlPolicy::default();
+        assert!(policy.check("https://github.com/facebook/react").is_ok());
+        assert!(policy.check("http://localhost:8000/").is_ok());
+        assert!(policy.check("/mirror/0123/std/index.html").is_ok());
+        for (url, expected) in [
+            ("javascript:alert(1)", "scheme 'javascript:'"),
+            ("JavaScript:alert(1)", "scheme 'javascript:'"),
+            ("data:text/html,<script>", "scheme 'data:'"),
+            ("file:///etc/passwd", "scheme 'file:'"),
+            ("//evil.example/", "not an absolute URL"),
+            ("/\\evil.example/", "not an absolute URL"),
+            ("github.com", "not an absolute URL"),
+            (
+                "https://example.com/\r\nSet-Cookie: x",
+                "control characters",
+            ),
+        ] {
+            let err = policy.check(url).unwrap_err();
+            assert!(err.contains(expected), "{}: {}", url, err);
+        }
+    }
+
+    #[test]
+    fn test_host_lists() {
+        let policy = UrlPolicy {
+            allow_hosts: vec!["corp.example".to_string(), "github.com".to_string()],
+            deny_hosts: vec!["secret.corp.example".to_string()],
+            ..Default::default()
+        };
+        assert!(policy.check("https://corp.example/").is_ok());
+        assert!(policy.check("https://wiki.corp.example:8443/x").is_ok());
+        assert!(policy.check("https://GitHub.com./facebook").is_ok());
+        for url in [
+            "https://evil.example/",
+            "https://notcorp.example/",
+            "https://corp.example@evil.example/",
+            "https://evil.example\\@corp.example/",
+            "https://api.secret.corp.example/",
+        ] {
+            assert!(policy.check(url).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_deny_hosts_sees_hosts_browsers_open() {
+        let policy = UrlPolicy {
+            deny_hosts: vec!["evil.example".to_string()],
+            ..Default::default()
+        };
+        assert!(policy.check("https://good.example/").is_ok());
+        for url in [
+            "https:evil.example",
+            "https:\\\\evil.example",
+            "https:/\\evil.example",
+            "https://evil%2Eexample",
+            "HTTPS://EVIL.example./",
+            "http://user@evil.example:8080/",
+        ] {
+            let err = policy.check(url).unwrap_err();
+            assert!(
+                err.contains("host 'evil.example' is denied"),
+                "{}: {}",
+                url,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_url_policy() {
+        for (body, expected) in [
+            ("[url_policy]\nschemes = []", "at least one scheme"),
+            (
+                "[url_policy]\nschemes = [\"https://\"]",
+                "lowercase scheme name",
+            ),
+            (
+                "[url_policy]\nallow_hosts = [\"https://corp.example\"]",
+                "allow_hosts entry",
+            ),
+            ("[url_policy]\ndeny_hosts = [\"\"]", "deny_hosts entry"),
+        ] {
+            let cfg: BunnylolConfig = toml::from_str(body).unwrap();
+            let err = cfg.validate_url_policy().unwrap_err();
+            assert!(err.contains(expected), "{}: {}", body, err);
+        }
+
+        let cfg: BunnylolConfig = toml::from_str(
+            "[url_policy]\nschemes = [\"https\", \"file\"]\ndeny_hosts = [\"evil.example\"]\ncli = true",
+        )
+        .unwrap();
+        cfg.validate_url_policy().unwrap();
+        let parsed: BunnylolConfig = toml::from_str(&cfg.url_policy.to_toml()).unwrap();
+        assert_eq!(parsed.url_policy, cfg.url_policy);
+    }
+}
@@ -8,6 +8,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::BunnylolConfig;
+use crate::explain::{MissingPlaceholder, MissingReason, TemplateSubstitution};
 
 /// A user-defined binding from `[user_bindings]` in the config file.
 ///
@@ -123,7 +124,7 @@ impl BunnylolConfig {
         let binding = self.user_bindings.get(name)?;
         let resolved = match binding {
             UserBinding::Url { url, .. } => {
-                ResolvedBinding::Url(apply_url_template(url, name, full_args))
+                ResolvedBinding::Url(apply_url_template(Some(self), url, name, full_args))
             }
             UserBinding::Command { command, .. } => ResolvedBinding::Command(command.clone()),
         };
@@ -140,7 +141,7 @@ impl BunnylolConfig {
     /// built-in and are not reported as conflicts.
     pub fn validate_user_bindings_conflicts(
         &self,
-        builtin_names: &std::collections::HashSet<&'static str>,
+        builtin_names: &std::collections::HashSet<&str>,
     ) -> Vec<BindingConflict> {
         let mut conflicts = Vec::new();
         for (name, binding) in &self.user_bindings {
@@ -158,17 +159,93 @@ impl BunnylolConfig {
 
 /// Apply a `{}` template substitution to a URL binding. `command` is stripped
 /// from the front of `full_args`, the remainder is URL-encoded, and
-/// substituted in. A template with no `{}` is returned as-is.
-fn apply_url_template(template: &str, command: &str, full_args: &str) -> String {
-    if !template.contains("{}") {
-        return template.to_string();
+/// substituted in. A template with no `{}` ignores the arguments. The
+/// `{repo}`, `{branch}` and `{sha}` placeholders are expanded either way;
+/// when one has no value, the full input is searched instead.
+fn apply_url_template(
+    config: Option<&BunnylolConfig>,
+    template: &str,
+    command: &str,
+    full_args: &str,
+) -> String {
+    substitute_url_template(config, template, command, full_args).url
+}
+
+/// Same as [`apply_url_template`], but keeps the intermediate values so
+/// `bunnylol explain` can show how the arguments were substituted.
+pub(crate) fn substitute_url_template(
+    config: Option<&BunnylolConfig>,
+    template: &str,
+    command: &str,
+    full_args: &str,
+) -> TemplateSubstitution {
+    let (arguments, encoded) = if template.contains("{}") {
+        let remainder = full_args
+            .strip_prefix(command)
+            .map(|s| s.trim_start())
+            .unwrap_or(full_args);
+        let encoded = crate::utils::url_encoding::encode_url(remainder);
+        (Some(remainder.to_string()), Some(encoded))
+    } else {
+        (None, None)
+    };
+    let (url, missing) = match expand_repo_placeholders(template) {
+        Ok(expanded) => (
+            expanded.replace("{}", encoded.as_deref().unwrap_or("")),
+            None,
+        ),
+        Err(missing) => (
+            crate::commands::fallback_search_url(config, full_args),
+            Some(missing),
+        ),
+    };
+    TemplateSubstitution {
+        template: template.to_string(),
+        arguments,
+        encoded,
+        missing,
+        url,
+    }
+}
+
+/// Expand `{repo}` (e.g. "facebook/react"), `{branch}` and `{sha}` from the
+/// CLI's [`RepoContext`](crate::repo_context::RepoContext). Outside a git
+/// checkout, and always on the server, they have no value: returns the
+/// first placeholder without one, and why, rather than a URL with an empty
+/// path segment.
+fn expand_repo_placeholders(template: &str) -> Result<String, MissingPlaceholder> {
+    use crate::repo_context::RepoContext;
+
+    let context = RepoContext::current();
+    let values = [
+        ("{repo}", MissingReason::NoRemote),
+        ("{branch}", MissingReason::DetachedHead),
+        ("{sha}", MissingReason::NoCommit),
+    ];
+    let mut expanded = template.to_string();
+    for (placeholder, unset) in values {
+        if !expanded.contains(placeholder) {
+            continue;
+        }
+        let value = context.as_ref().and_then(|context| match placeholder {
+            "{repo}" => context.repo(),
+            "{branch}" => context.branch.as_deref(),
+            _ => context.sha.as_deref(),
+        });
+        let Some(value) = value else {
+            let reason = match &context {
+                Some(_) => unset,
+                None if RepoContext::installed() => MissingReason::NoCheckout,
+                None => MissingReason::Server,
+            };
+            return Err(MissingPlaceholder {
+                placeholder: placeholder.to_string(),
+                reason,
+            });
+        };
+        expanded = expanded.replace(placeholder, &crate::utils::url_encoding::encode_url(value));
     }
-    let remainder = full_args
-        .strip_prefix(command)
-        .map(|s| s.trim_start())
-        .unwrap_or(full_args);
-    let encoded = crate::utils::url_encoding::encode_url(remainder);
-    template.replace("{}", &encoded)
+    Ok(expanded)
 }
 
 /// Format one `[user_bindings]` entry as its TOML inline-table representation.
@@ -205,7 +282,7 @@ pub(super) fn format_user_binding_toml(name: &str, binding: &UserBinding) -> Str
     format!("{} = {{ {} }}", format_toml_key(name), parts.join(", "))
 }
 
-fn format_toml_key(key: &str) -> String {
+pub(super) fn format_toml_key(key: &str) -> String {
     if !key.is_empty()
         && key
             .bytes()
@@ -217,7 +294,7 @@ fn format_toml_key(key: &str) -> String {
     }
 }
 
-fn escape_toml_string(s: &str) -> String {
+pub(super) fn escape_toml_string(s: &str) -> String {
     let mut escaped = String::new();
     for ch in s.chars() {
         match ch {
@@ -382,6 +459,96 @@ mod tests {
         assert_eq!(config.resolve_user_binding("nope", "nope"), None);
     }
 
+    #[test]
+    fn test_url_template_repo_placeholders() {
+        use crate::repo_context::{GitRemote, RepoContext};
+
+        let template = "https://ci.example.com/{repo}/tree/{branch}?sha={sha}&q={}";
+        let missing = |reason| {
+            Some(MissingPlaceholder {
+                placeholder: "{repo}".to_string(),
+                reason,
+            })
+        };
+        // On the server the full input is searched, not a broken URL
+        let substitution = substitute_url_template(None, template, "ci", "ci flaky");
+        assert_eq!(substitution.missing, missing(MissingReason::Server));
+        assert_eq!(
+            substitution.url,
+            crate::commands::fallback_search_url(None, "ci flaky")
+        );
+        assert_eq!(
+            apply_url_template(None, "https://ci.example.com/q={}", "ci", "ci flaky"),
+            "https://ci.example.com/q=flaky"
+        );
+
+        // The CLI outside a checkout
+        RepoContext::install(None);
+        let substitution = substitute_url_template(None, template, "ci", "ci flaky");
+        assert_eq!(substitution.missing, missing(MissingReason::NoCheckout));
+
+        // A checkout without a usable remote, on a detached HEAD, or before
+        // its first commit
+        let remote = GitRemote {
+            name: "origin".to_string(),
+            host: "github.com".to_string(),
+            path: "facebook/react".to_string(),
+        };
+        for (context, placeholder, reason) in [
+            (
+                RepoContext {
+                    remotes: vec![],
+                    branch: Some("main".to_string()),
+                    sha: Some("abc123".to_string()),
+                },
+                "{repo}",
+                MissingReason::NoRemote,
+            ),
+            (
+                RepoContext {
+                    remotes: vec![remote.clone()],
+                    branch: None,
+                    sha: Some("abc123".to_string()),
+                },
+                "{branch}",
+                MissingReason::DetachedHead,
+            ),
+            (
+                RepoContext {
+                    remotes: vec![remote.clone()],
+                    branch: Some("main".to_string()),
+                    sha: None,
+                },
+                "{sha}",
+                MissingReason::NoCommit,
+            ),
+        ] {
+            RepoContext::install(Some(context));
+            let missing = substitute_url_template(None, template, "ci", "ci flaky")
+                .missing
+                .unwrap();
+            assert_eq!(
+                (missing.placeholder.as_str(), missing.reason),
+                (placeholder, reason)
+            );
+        }
+
+        RepoContext::install(Some(RepoContext {
+            remotes: vec![remote],
+            branch: Some("fix/hooks".to_string()),
+            sha: Some("abc123".to_string()),
+        }));
+        assert_eq!(
+            apply_url_template(None, template, "ci", "ci flaky"),
+            "https://ci.example.com/facebook/react/tree/fix/hooks?sha=abc123&q=flaky"
+        );
+        assert_eq!(
+            apply_url_template(None, "https://ci.example.com/{repo}", "ci", "ci ignored"),
+            "https://ci.example.com/facebook/react"
+        );
+        RepoContext::install(None);
+    }
+
     #[test]
     fn test_resolve_user_binding_command_returns_rewritten_string() {
         let mut config = BunnylolConfig::default();
@@ -0,0 +1,193 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Local documentation mirrors for the docs commands (`rust`, `python`,
+//! `node`, `mdn`, `godocs`), set with `[commands.<name>] mirror = "<dir>"`.
+//!
+//! A docs command builds its online URL as usual and asks [`local_url`]
+//! whether the mirror has that page. If it does, the CLI opens a `file://`
+//! URL; the server, which can't hand browsers a path on its own disk,
+//! redirects to its `/mirror/<id>/<path>` route instead (see
+//! [`serve_mirrors`]). A missing mirror or page falls back to the online URL.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+
+use crate::config::{BunnylolConfig, CommandConfig};
+
+/// Characters escaped in a mirror path; `/` stays a separator.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+static SERVED: AtomicBool = AtomicBool::new(false);
+
+/// Route mirror hits through the server's `/mirror` route instead of
+/// `file://` URLs. Only the server calls this.
+pub fn serve_mirrors() {
+    SERVED.store(true, Ordering::Relaxed);
+}
+
+/// The mirror URL for `online_url` if `settings` has a mirror containing the
+/// page. `online_root` is the online URL that corresponds to the mirror's
+/// root directory; a page is looked up as a file, then as a directory with
+/// an `index.html`. Query strings and fragments (e.g. `?search=Vec`) are
+/// kept, since offline rustdoc and Sphinx pages handle them in the browser.
+pub fn local_url(
+    settings: Option<&CommandConfig>,
+    online_root: &str,
+    online_url: &str,
+) -> Option<String> {
+    let root = settings?.mirror_root()?;
+    let rest = online_url.strip_prefix(online_root.trim_end_matches('/'))?;
+    let split = rest.find(['?', '#']).unwrap_or(rest.len());
+    let (page, suffix) = rest.split_at(split);
+    let page = page.trim_start_matches('/');
+    if page.split('/').any(|segment| segment == "..") {
+        return None;
+    }
+    // `Web/API/fetch` may be saved as a file or as `Web/API/fetch/index.html`
+    let candidates = if page.is_empty() || page.ends_with('/') {
+        vec![format!("{}index.html", page)]
+    } else {
+        vec![page.to_string(), format!("{}/index.html", page)]
+    };
+    let page = candidates
+        .into_iter()
+        .find(|page| root.join(page).is_file())?;
+    Some(mirror_url(&root, &page, suffix))
+}
+
+/// The mirror URL of `page` (a path relative to `root`, which must exist),
+/// with `suffix` appended unchanged.
+pub fn mirror_url(root: &Path, page: &str, suffix: &str) -> String {
+    let page = utf8_percent_encode(page, PATH_SEGMENT);
+    if SERVED.load(Ordering::Relaxed) {
+        format!("/mirror/{}/{}{}", mirror_id(root), page, suffix)
+    } else {
+        let root = utf8_percent_encode(&root.to_string_lossy(), PATH_SEGMENT).to_string();
+        format!("file://{}/{}{}", root.trim_end_matches('/'), page, suffix)
+    }
+}
+
+/// Stable id of a mirror root in `/mirror/<id>/...` URLs, so the server
+/// doesn't publish paths on its disk (the first 16 hex digits of the path's
+/// SHA-256).
+pub fn mirror_id(root: &Path) -> String {
+    let mut id = crate::utils::sha256_hex(root.to_string_lossy().as_bytes());
+    id.truncate(16);
+    id
+}
+
+/// The configured mirror root whose id is `id`, for the server route.
+pub fn find_mirror(config: &BunnylolConfig, id: &str) -> Option<PathBuf> {
+    config
+        .commands
+        .values()
+        .filter_map(CommandConfig::mirror_root)
+        .find(|root| mirror_id(root) == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn mirrored(root: &Path) -> CommandConfig {
+        CommandConfig {
+            mirror: Some(root.to_string_lossy().to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_local_url_requires_the_page() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("std")).unwrap();
+        fs::write(dir.path().join("std/index.html"), "").unwrap();
+        let settings = mirrored(dir.path());
+        let root = "https://doc.rust-lang.org/stable/";
+
+        assert_eq!(
+            local_url(
+                Some(&settings),
+                root,
+                "https://doc.rust-lang.org/stable/std/index.html?search=Vec"
+            ),
+            Some(format!(
+                "file://{}/std/index.html?search=Vec",
+                dir.path().display()
+            ))
+        );
+        assert_eq!(
+            local_url(Some(&settings), root, "https://doc.rust-lang.org/stable/"),
+            None
+        );
+        assert_eq!(
+            local_url(Some(&settings), root, "https://example.com/std/index.html"),
+            None
+        );
+        assert_eq!(
+            local_url(
+                Some(&settings),
+                root,
+                "https://doc.rust-lang.org/stable/../std/index.html"
+            ),
+            None
+        );
+        assert_eq!(
+            local_url(
+                None,
+                root,
+                "https://doc.rust-lang.org/stable/std/index.html"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_directory_pages_use_index_html() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("index.html"), "").unwrap();
+        assert_eq!(
+            local_url(
+                Some(&mirrored(dir.path())),
+                "https://docs.python.org/3/",
+                "https://docs.python.org/3/"
+            ),
+            Some(format!("file://{}/index.html", dir.path().display()))
+        );
+    }
+
+    #[test]
+    fn test_find_mirror_by_id() {
+        let config: BunnylolConfig = toml::from_str(
+            r#"
+[commands.python]
+mirror = "/srv/docs/python"
+"#,
+        )
+        .unwrap();
+        let id = mirror_id(Path::new("/srv/docs/python"));
+        assert_eq!(id.len(), 16);
+        assert_eq!(
+            find_mirror(&config, &id),
+            Some(PathBuf::from("/srv/docs/python"))
+        );
+        assert_eq!(find_mirror(&config, "0000000000000000"), None);
+    }
+}
@@ -0,0 +1,316 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Resolution traces for `bunnylol explain` and the server's `explain=1` view.
+//!
+//! A [`ResolutionTrace`] records what each tier of
+//! [`BunnylolCommandRegistry::process_command`](crate::BunnylolCommandRegistry::process_command)
+//! checked for a single input, which tier produced the final URL, and which
+//! other candidates were shadowed along the way.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::config::AppliedRewrite;
+
+/// The resolution tiers, in the order `process_command` evaluates them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ResolutionTier {
+    /// Built-in prefix handlers (`$TICKER`, `r/sub`, ...) and `[[patterns]]`
+    Prefix,
+    /// `[user_bindings]` entries with `override = true`
+    UserBindingOverride,
+    /// Built-in registered commands
+    BuiltIn,
+    /// `[user_bindings]` entries without `override`
+    UserBinding,
+    /// Go-links from `bunnylol link` and `/api/links`
+    Link,
+    /// Default search engine fallback
+    SearchFallback,
+}
+
+impl ResolutionTier {
+    /// 1-based tier number, matching the list on `process_command`.
+    pub fn number(self) -> u8 {
+        match self {
+            ResolutionTier::Prefix => 1,
+            ResolutionTier::UserBindingOverride => 2,
+            ResolutionTier::BuiltIn => 3,
+            ResolutionTier::UserBinding => 4,
+            ResolutionTier::Link => 5,
+            ResolutionTier::SearchFallback => 6,
+        }
+    }
+
+    /// Short human-readable name for the tier.
+    pub fn label(self) -> &'static str {
+        match self {
+            ResolutionTier::Prefix => "prefixes and patterns",
+            ResolutionTier::UserBindingOverride => "user bindings (override = true)",
+            ResolutionTier::BuiltIn => "built-in commands",
+            ResolutionTier::UserBinding => "user bindings",
+            ResolutionTier::Link => "go-links",
+            ResolutionTier::SearchFallback => "search fallback",
+        }
+    }
+}
+
+/// What happened when a tier was evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TierOutcome {
+    /// This tier produced the final URL.
+    Matched,
+    /// This tier was checked and had nothing for the input.
+    NoMatch,
+    /// This tier is not consulted in this context (e.g. user bindings while
+    /// dispatching a `Command` binding).
+    Skipped,
+    /// An earlier tier already matched.
+    NotReached,
+}
+
+impl TierOutcome {
+    fn marker(self) -> &'static str {
+        match self {
+            TierOutcome::Matched => "match",
+            TierOutcome::NoMatch => "miss",
+            TierOutcome::Skipped => "skip",
+            TierOutcome::NotReached => "----",
+        }
+    }
+}
+
+/// How a URL binding's `{}` template was filled in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateSubstitution {
+    /// The template as written in `[user_bindings]`.
+    pub template: String,
+    /// The arguments left after stripping the binding name (or the input a
+    /// pattern matched), or `None` when the template has no `{}` and
+    /// arguments are ignored.
+    pub arguments: Option<String>,
+    /// The URL-encoded arguments substituted for `{}`, if any.
+    pub encoded: Option<String>,
+    /// A `{repo}`, `{branch}` or `{sha}` placeholder with no value; the full
+    /// input was searched instead.
+    pub missing: Option<MissingPlaceholder>,
+    /// The resulting URL.
+    pub url: String,
+}
+
+/// A repository placeholder in a URL template that had no value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingPlaceholder {
+    /// `{repo}`, `{branch}` or `{sha}`.
+    pub placeholder: String,
+    pub reason: MissingReason,
+}
+
+/// Why a repository placeholder had no value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum MissingReason {
+    /// Resolved by the server, which never looks at a checkout
+    Server,
+    /// The CLI wasn't run inside a git checkout
+    NoCheckout,
+    /// No remote with a recognizable host in `.git/config` (`{repo}`)
+    NoRemote,
+    /// HEAD is detached, so there's no branch (`{branch}`)
+    DetachedHead,
+    /// HEAD's commit couldn't be read, e.g. before the first commit (`{sha}`)
+    NoCommit,
+}
+
+impl fmt::Display for MissingReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MissingReason::Server => "resolved on the server, which has no git checkout",
+            MissingReason::NoCheckout => "not in a git checkout",
+            MissingReason::NoRemote => "no git remote with a known host",
+            MissingReason::DetachedHead => "detached HEAD",
+            MissingReason::NoCommit => "HEAD has no commit",
+        })
+    }
+}
+
+/// One evaluated tier in a [`ResolutionTrace`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TierTrace {
+    pub tier: ResolutionTier,
+    pub outcome: TierOutcome,
+    /// What the tier checked and what it found.
+    pub detail: String,
+    /// Template substitution, for tiers that matched a URL binding.
+    pub substitution: Option<TemplateSubstitution>,
+}
+
+/// A candidate that would have handled the input had an earlier tier not won.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ShadowedCandidate {
+    pub tier: ResolutionTier,
+    /// The binding, built-in or alias name of the candidate.
+    pub name: String,
+    /// Why the candidate did not fire.
+    pub reason: String,
+}
+
+/// A full record of how one input was resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolutionTrace {
+    /// The full input string.
+    pub input: String,
+    /// The command word looked up in each tier.
+    pub command: String,
+    pub tiers: Vec<TierTrace>,
+    pub shadowed: Vec<ShadowedCandidate>,
+    /// The rewritten command's own trace, when a `Command` binding matched.
+    pub dispatched: Option<Box<ResolutionTrace>>,
+    /// `[[rewrites]]` rules that changed the resolved URL, in order.
+    pub rewrites: Vec<AppliedRewrite>,
+    /// The final URL, after rewrites.
+    pub url: String,
+}
+
+impl ResolutionTrace {
+    pub(crate) fn new(command: &str, full_args: &str) -> Self {
+        Self {
+            input: full_args.to_string(),
+            command: command.to_string(),
+            tiers: Vec::new(),
+            shadowed: Vec::new(),
+            dispatched: None,
+            rewrites: Vec::new(),
+            url: String::new(),
+        }
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        tier: ResolutionTier,
+        outcome: TierOutcome,
+        detail: impl Into<String>,
+    ) {
+        self.tiers.push(TierTrace {
+            tier,
+            outcome,
+            detail: detail.into(),
+            substitution: None,
+        });
+    }
+
+    pub(crate) fn shadow(
+        &mut self,
+        tier: ResolutionTier,
+        name: impl Into<String>,
+        reason: impl Into<String>,
+    ) {
+        self.shadowed.push(ShadowedCandidate {
+            tier,
+            name: name.into(),
+            reason: reason.into(),
+        });
+    }
+
+    /// The tier that matched this input. For `Command` bindings the rewritten
+    /// command's tier is in [`Self::dispatched`].
+    pub fn matched_tier(&self) -> Option<ResolutionTier> {
+        self.tiers
+            .iter()
+            .find(|t| t.outcome == TierOutcome::Matched)
+            .map(|t| t.tier)
+    }
+
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, indent: &str) -> fmt::Result {
+        writeln!(f, "{}Input:   {}", indent, self.input)?;
+        writeln!(f, "{}Command: {}", indent, self.command)?;
+        writeln!(f)?;
+        for tier in &self.tiers {
+            writeln!(
+                f,
+                "{}  [{:<5}] Tier {} · {}: {}",
+                indent,
+                tier.outcome.marker(),
+                tier.tier.number(),
+                tier.tier.label(),
+                tier.detail
+            )?;
+            if let Some(sub) = &tier.substitution {
+                writeln!(f, "{}            template: {}", indent, sub.template)?;
+                match (&sub.arguments, &sub.encoded) {
+                    (Some(args), Some(encoded)) => {
+                        writeln!(f, "{}            args:     {:?}", indent, args)?;
+                        writeln!(f, "{}            encoded:  {}", indent, encoded)?;
+                    }
+                    (Some(args), None) => {
+                        writeln!(f, "{}            input:    {:?}", indent, args)?;
+                    }
+                    (None, _) => writeln!(
+                        f,
+                        "{}            args:     (ignored, template has no {{}})",
+                        indent
+                    )?,
+                }
+                if let Some(missing) = &sub.missing {
+                    writeln!(
+                        f,
+                        "{}            missing:  {} ({}), searched instead",
+                        indent, missing.placeholder, missing.reason
+                    )?;
+                }
+                writeln!(f, "{}            url:      {}", indent, sub.url)?;
+            }
+        }
+
+        if let Some(dispatched) = &self.dispatched {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "{}  Dispatched once into the registry (user config is not re-entered):",
+                indent
+            )?;
+            dispatched.write_indented(f, &format!("{}    ", indent))?;
+        }
+
+        if !self.shadowed.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "{}Shadowed candidates:", indent)?;
+            for candidate in &self.shadowed {
+                writeln!(
+                    f,
+                    "{}  - {} (tier {}): {}",
+                    indent,
+                    candidate.name,
+                    candidate.tier.number(),
+                    candidate.reason
+                )?;
+            }
+        }
+
+        if !self.rewrites.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "{}Rewrites:", indent)?;
+            for rewrite in &self.rewrites {
+                writeln!(
+                    f,
+                    "{}  - {}: {} -> {}",
+                    indent, rewrite.rule, rewrite.before, rewrite.after
+                )?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{}Result:  {}", indent, self.url)
+    }
+}
+
+impl fmt::Display for ResolutionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, "")
+    }
+}
@@ -9,10 +9,12 @@ use std::fs;
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
 
+use serde::Serialize;
+
 use crate::config::BunnylolConfig;
 
 /// Command history entry
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct HistoryEntry {
     pub command: String,
     pub timestamp: String,
@@ -94,8 +96,10 @@ impl History {
         // Read existing history
         let mut entries = self.read_all()?;
 
-        // Add new entry
-        entries.push(HistoryEntry::new(command.to_string(), user.to_string()));
+        // Add new entry. User names can come from a proxy header, so keep
+        // them from breaking the `timestamp|user|command` line format.
+        let user = user.replace(['|', '\n', '\r'], "_");
+        entries.push(HistoryEntry::new(command.to_string(), user));
 
         // Trim to max_entries
         if entries.len() > self.max_entries {
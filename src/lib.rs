@@ -5,10 +5,15 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+pub mod binding_tests;
 pub mod bunnylol_command_registry;
 pub mod commands;
 pub mod config;
+pub mod docs_mirror;
+pub mod explain;
 pub mod history;
+pub mod links;
+pub mod repo_context;
 pub mod utils;
 
 // Server module is needed for both server runtime and CLI service management
@@ -20,6 +25,9 @@ pub mod server;
 pub use server::service;
 
 pub use bunnylol_command_registry::BunnylolCommandRegistry;
-pub use commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+pub use commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo, PrefixHandler};
 pub use config::{BunnylolConfig, ConfigReloader};
+pub use explain::ResolutionTrace;
 pub use history::{History, HistoryEntry};
+pub use links::{LinkCache, Links, ShortLink};
+pub use repo_context::RepoContext;
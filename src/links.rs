@@ -0,0 +1,591 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Go-links: short links anyone can create without editing `config.toml`.
+//!
+//! Links live in `$XDG_DATA_HOME/bunnylol/links.toml`, written by
+//! `bunnylol link` and the server's `/api/links`, and resolve after
+//! `[user_bindings]` and before the search fallback. Each link records who
+//! created it and when, how often it was used, and optionally when it
+//! expires. A link URL may use `{}` for the rest of the query, like a URL
+//! binding.
+//!
+//! The CLI reads and writes the file directly. The server keeps a
+//! [`LinkCache`], which re-reads the file only when it changes and counts
+//! hits in memory until they're flushed.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::BunnylolCommandRegistry;
+use crate::config::BunnylolConfig;
+
+/// Serializes read-modify-write cycles within one process (the server's
+/// request handlers, which also count hits).
+static LINKS_LOCK: Mutex<()> = Mutex::new(());
+
+/// One go-link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShortLink {
+    /// Target URL; `{}` is replaced with the rest of the query.
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Who created the link: the signed-in user, or the local user for the CLI.
+    pub owner: String,
+    /// Unix seconds.
+    pub created_at: u64,
+    /// How many queries the link resolved.
+    #[serde(default)]
+    pub hits: u64,
+    /// Unix seconds after which the link no longer resolves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+impl ShortLink {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Why a change to the link store was not made. Nothing is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkError {
+    /// `add` for a name that already has a link.
+    Exists,
+    /// `update` or `remove` for a name without a link.
+    NotFound,
+    /// The link belongs to someone else.
+    NotOwner(String),
+    /// A bad name, URL or expiry.
+    Invalid(String),
+    /// Reading or writing the store failed.
+    Io(String),
+}
+
+impl std::fmt::Display for LinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkError::Exists => write!(f, "a link with this name already exists"),
+            LinkError::NotFound => write!(f, "no link with this name"),
+            LinkError::NotOwner(owner) => write!(f, "this link belongs to {}", owner),
+            LinkError::Invalid(e) | LinkError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// The go-link store.
+#[derive(Debug, Clone)]
+pub struct Links {
+    path: PathBuf,
+}
+
+impl Links {
+    /// The store in the data directory, or `None` if it can't be found.
+    pub fn new() -> Option<Self> {
+        BunnylolConfig::get_links_path().map(Self::at)
+    }
+
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// All links, including expired ones. A missing file is an empty store.
+    pub fn read_all(&self) -> Result<BTreeMap<String, ShortLink>, String> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|e| format!("Failed to parse links file {:?}: {}", self.path, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(format!("Failed to read links file {:?}: {}", self.path, e)),
+        }
+    }
+
+    /// Links that haven't expired, for resolution.
+    pub fn active(&self) -> Result<BTreeMap<String, ShortLink>, String> {
+        let now = now();
+        let mut links = self.read_all()?;
+        links.retain(|_, link| !link.is_expired(now));
+        Ok(links)
+    }
+
+    /// Create `name`. Fails if it exists, even expired (remove it first).
+    pub fn add(
+        &self,
+        config: &BunnylolConfig,
+        name: &str,
+        link: ShortLink,
+    ) -> Result<ShortLink, LinkError> {
+        validate_link(config, name, &link.url)?;
+        self.modify(|links| {
+            if links.contains_key(name) {
+                return Err(LinkError::Exists);
+            }
+            links.insert(name.to_string(), link.clone());
+            Ok(link)
+        })
+    }
+
+    /// Change the URL, description or expiry of `name`, keeping its owner,
+    /// creation time and hit count. Only the owner (or an admin, with
+    /// `as_owner = None`) may change it.
+    pub fn update(
+        &self,
+        config: &BunnylolConfig,
+        name: &str,
+        as_owner: Option<&str>,
+        change: impl FnOnce(&mut ShortLink),
+    ) -> Result<ShortLink, LinkError> {
+        self.modify(|links| {
+            let link = links.get_mut(name).ok_or(LinkError::NotFound)?;
+            check_owner(link, as_owner)?;
+            change(link);
+            validate_link(config, name, &link.url)?;
+            Ok(link.clone())
+        })
+    }
+
+    /// Remove `name`. Only the owner (or an admin, with `as_owner = None`)
+    /// may remove it.
+    pub fn remove(&self, name: &str, as_owner: Option<&str>) -> Result<ShortLink, LinkError> {
+        self.modify(|links| {
+            check_owner(links.get(name).ok_or(LinkError::NotFound)?, as_owner)?;
+            Ok(links.remove(name).expect("checked above"))
+        })
+    }
+
+    /// Count a query resolved by `name`.
+    pub fn record_hit(&self, name: &str) -> Result<(), String> {
+        self.add_hits(&HashMap::from([(name.to_string(), 1)]))
+    }
+
+    /// Add `hits` to each link's count, skipping links removed since.
+    pub fn add_hits(&self, hits: &HashMap<String, u64>) -> Result<(), String> {
+        self.modify(|links| {
+            for (name, count) in hits {
+                if let Some(link) = links.get_mut(name) {
+                    link.hits += count;
+                }
+            }
+            Ok(())
+        })
+        .map_err(|e: LinkError| e.to_string())
+    }
+
+    fn modify<T>(
+        &self,
+        f: impl FnOnce(&mut BTreeMap<String, ShortLink>) -> Result<T, LinkError>,
+    ) -> Result<T, LinkError> {
+        let _guard = LINKS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut links = self.read_all().map_err(LinkError::Io)?;
+        let result = f(&mut links)?;
+        self.write_all(&links).map_err(LinkError::Io)?;
+        Ok(result)
+    }
+
+    fn write_all(&self, links: &BTreeMap<String, ShortLink>) -> Result<(), String> {
+        let parent = self
+            .path
+            .parent()
+            .ok_or_else(|| format!("Links path {:?} has no parent directory", self.path))?;
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create links directory: {}", e))?;
+        let contents = toml::to_string(links).map_err(|e| e.to_string())?;
+        let mut temp_file = tempfile::NamedTempFile::new_in(parent)
+            .map_err(|e| format!("Failed to create temporary links file: {}", e))?;
+        temp_file
+            .write_all(contents.as_bytes())
+            .map_err(|e| format!("Failed to write temporary links file: {}", e))?;
+        temp_file
+            .persist(&self.path)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to replace links file: {}", e.error))
+    }
+}
+
+/// The size and modification time of the links file, to notice changes;
+/// `None` when it doesn't exist.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &std::path::Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// The links last read from the file.
+#[derive(Default)]
+struct Loaded {
+    /// `None` until the file is first read, or after [`LinkCache::invalidate`].
+    stamp: Option<Stamp>,
+    all: BTreeMap<String, ShortLink>,
+    active: Arc<BTreeMap<String, ShortLink>>,
+    /// When the next of `active` expires.
+    next_expiry: Option<u64>,
+}
+
+/// The server's view of the go-link store: links are re-read only when the
+/// file changes, and hits are counted in memory until [`Self::flush_hits`]
+/// writes them out. Clones share the links and the hits.
+#[derive(Clone)]
+pub struct LinkCache(Arc<Shared>);
+
+struct Shared {
+    store: Option<Links>,
+    loaded: Mutex<Loaded>,
+    hits: Mutex<HashMap<String, u64>>,
+}
+
+impl LinkCache {
+    /// A cache of the store in the data directory. Without one there are no
+    /// links.
+    pub fn new() -> Self {
+        Self::of(Links::new())
+    }
+
+    pub fn of(store: Option<Links>) -> Self {
+        Self(Arc::new(Shared {
+            store,
+            loaded: Mutex::new(Loaded::default()),
+            hits: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// The store, for changes to links.
+    pub fn store(&self) -> Option<&Links> {
+        self.0.store.as_ref()
+    }
+
+    /// Links that haven't expired, for resolution. A broken links file is a
+    /// warning, once per change to it, and the links read before are kept.
+    pub fn active(&self) -> Arc<BTreeMap<String, ShortLink>> {
+        self.refresh(|loaded| loaded.active.clone())
+    }
+
+    /// All links, including expired ones, with the hits not yet flushed.
+    pub fn all(&self) -> BTreeMap<String, ShortLink> {
+        let mut all = self.refresh(|loaded| loaded.all.clone());
+        for (name, link) in &mut all {
+            link.hits += self.pending_hits(name);
+        }
+        all
+    }
+
+    /// Hits counted for `name` since the last flush.
+    pub fn pending_hits(&self, name: &str) -> u64 {
+        let hits = self.0.hits.lock().unwrap_or_else(|e| e.into_inner());
+        hits.get(name).copied().unwrap_or(0)
+    }
+
+    /// Re-read the file on the next lookup, after changing it.
+    pub fn invalidate(&self) {
+        self.0
+            .loaded
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .stamp = None;
+    }
+
+    /// Count a query resolved by `name`, in memory.
+    pub fn record_hit(&self, name: &str) {
+        let mut hits = self.0.hits.lock().unwrap_or_else(|e| e.into_inner());
+        *hits.entry(name.to_string()).or_default() += 1;
+    }
+
+    /// Write the hits counted since the last flush to the file. They're kept
+    /// for the next flush if that fails. Blocks on file I/O.
+    pub fn flush_hits(&self) -> Result<(), String> {
+        let Some(store) = &self.0.store else {
+            return Ok(());
+        };
+        let hits = std::mem::take(&mut *self.0.hits.lock().unwrap_or_else(|e| e.into_inner()));
+        if hits.is_empty() {
+            return Ok(());
+        }
+        store.add_hits(&hits).inspect_err(|_| {
+            let mut pending = self.0.hits.lock().unwrap_or_else(|e| e.into_inner());
+            for (name, count) in hits {
+                *pending.entry(name).or_default() += count;
+            }
+        })
+    }
+
+    fn refresh<T>(&self, f: impl FnOnce(&Loaded) -> T) -> T {
+        let mut loaded = self.0.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(store) = &self.0.store else {
+            return f(&loaded);
+        };
+        let now = now();
+        let current = stamp(&store.path);
+        let changed = loaded.stamp != Some(current);
+        if changed {
+            loaded.stamp = Some(current);
+            match store.read_all() {
+                Ok(all) => loaded.all = all,
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+        }
+        if changed || loaded.next_expiry.is_some_and(|expiry| expiry <= now) {
+            let active: BTreeMap<_, _> = loaded
+                .all
+                .iter()
+                .filter(|(_, link)| !link.is_expired(now))
+                .map(|(name, link)| (name.clone(), link.clone()))
+                .collect();
+            loaded.next_expiry = active.values().filter_map(|link| link.expires_at).min();
+            loaded.active = Arc::new(active);
+        }
+        f(&loaded)
+    }
+}
+
+impl Default for LinkCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check_owner(link: &ShortLink, as_owner: Option<&str>) -> Result<(), LinkError> {
+    match as_owner {
+        Some(owner) if owner != link.owner => Err(LinkError::NotOwner(link.owner.clone())),
+        _ => Ok(()),
+    }
+}
+
+/// Links resolve after built-ins and `[user_bindings]`, so a link with one
+/// of their names would never be reached.
+fn validate_link(config: &BunnylolConfig, name: &str, url: &str) -> Result<(), LinkError> {
+    if name.is_empty() || name.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(LinkError::Invalid(format!(
+            "link name {:?} must be a single word without spaces",
+            name
+        )));
+    }
+    let shadowed_by = if BunnylolCommandRegistry::builtin_binding_names().contains(name)
+        || config.command_instance(name).is_some()
+    {
+        Some("a built-in command")
+    } else if BunnylolCommandRegistry::is_prefix_command(name) {
+        Some("a built-in prefix")
+    } else if config.user_bindings.contains_key(name) {
+        Some("a [user_bindings] entry")
+    } else {
+        None
+    };
+    if let Some(shadowed_by) = shadowed_by {
+        return Err(LinkError::Invalid(format!(
+            "'{}' is {}, which would always win over the link",
+            name, shadowed_by
+        )));
+    }
+    if !url.contains("://") {
+        return Err(LinkError::Invalid(format!(
+            "link URL '{}' must be an absolute URL like https://example.com",
+            url
+        )));
+    }
+    Ok(())
+}
+
+/// Unix seconds now.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Parse an expiry like `90m`, `12h`, `30d` or `4w` into seconds.
+pub fn parse_expiry(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let unit = match value.chars().last() {
+        Some('s') => 1,
+        Some('m') => 60,
+        Some('h') => 60 * 60,
+        Some('d') => 24 * 60 * 60,
+        Some('w') => 7 * 24 * 60 * 60,
+        _ => 0,
+    };
+    value[..value.len().saturating_sub(1)]
+        .parse::<u64>()
+        .ok()
+        .filter(|count| unit > 0 && *count > 0)
+        .map(|count| count.saturating_mul(unit))
+        .ok_or_else(|| {
+            format!(
+                "expiry '{}' must be a number and a unit (s, m, h, d or w), like 30d",
+                value
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(url: &str, owner: &str) -> ShortLink {
+        ShortLink {
+            url: url.to_string(),
+            description: None,
+            owner: owner.to_string(),
+            created_at: now(),
+            hits: 0,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn test_add_update_remove_and_hits() {
+        let dir = tempfile::tempdir().unwrap();
+        let links = Links::at(dir.path().join("links.toml"));
+        let config = BunnylolConfig::default();
+
+        links
+            .add(
+                &config,
+                "onboarding",
+                link("https://wiki.example/start", "alice"),
+            )
+            .unwrap();
+        assert_eq!(
+            links.add(&config, "onboarding", link("https://other.example", "bob")),
+            Err(LinkError::Exists)
+        );
+        links.record_hit("onboarding").unwrap();
+        links.record_hit("onboarding").unwrap();
+
+        assert_eq!(
+            links.update(&config, "onboarding", Some("bob"), |_| {}),
+            Err(LinkError::NotOwner("alice".to_string()))
+        );
+        let updated = links
+            .update(&config, "onboarding", Some("alice"), |link| {
+                link.url = "https://wiki.example/welcome".to_string();
+            })
+            .unwrap();
+        assert_eq!(updated.url, "https://wiki.example/welcome");
+        assert_eq!((updated.owner.as_str(), updated.hits), ("alice", 2));
+
+        assert_eq!(
+            links.remove("onboarding", Some("bob")),
+            Err(LinkError::NotOwner("alice".to_string()))
+        );
+        links.remove("onboarding", None).unwrap();
+        assert!(links.read_all().unwrap().is_empty());
+        assert_eq!(links.remove("onboarding", None), Err(LinkError::NotFound));
+    }
+
+    #[test]
+    fn test_expired_links_are_inactive() {
+        let dir = tempfile::tempdir().unwrap();
+        let links = Links::at(dir.path().join("links.toml"));
+        let config = BunnylolConfig::default();
+        let mut expired = link("https://old.example", "alice");
+        expired.expires_at = Some(now() - 1);
+        links.add(&config, "old", expired).unwrap();
+        links
+            .add(&config, "new", link("https://new.example", "alice"))
+            .unwrap();
+
+        let active = links.active().unwrap();
+        assert_eq!(active.keys().collect::<Vec<_>>(), ["new"]);
+        assert_eq!(links.read_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_cache_reloads_changes_and_batches_hits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("links.toml");
+        let cache = LinkCache::of(Some(Links::at(path.clone())));
+        let config = BunnylolConfig::default();
+        assert!(cache.active().is_empty());
+
+        let store = cache.store().unwrap();
+        store
+            .add(
+                &config,
+                "handbook",
+                link("https://handbook.example", "alice"),
+            )
+            .unwrap();
+        assert_eq!(cache.active().keys().collect::<Vec<_>>(), ["handbook"]);
+        let active = cache.active();
+        assert!(Arc::ptr_eq(&active, &cache.active()), "re-read unchanged");
+
+        cache.record_hit("handbook");
+        cache.record_hit("handbook");
+        assert_eq!(store.read_all().unwrap()["handbook"].hits, 0);
+        assert_eq!(cache.all()["handbook"].hits, 2);
+        cache.flush_hits().unwrap();
+        assert_eq!(store.read_all().unwrap()["handbook"].hits, 2);
+        assert_eq!(cache.all()["handbook"].hits, 2);
+
+        // A broken file keeps the links already read
+        fs::write(&path, "not toml").unwrap();
+        assert_eq!(cache.active().keys().collect::<Vec<_>>(), ["handbook"]);
+
+        // Hits that can't be written wait for the next flush
+        cache.record_hit("handbook");
+        assert!(cache.flush_hits().is_err());
+        assert_eq!(cache.pending_hits("handbook"), 1);
+    }
+
+    #[test]
+    fn test_cache_drops_links_as_they_expire() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = LinkCache::of(Some(Links::at(dir.path().join("links.toml"))));
+        let mut soon = link("https://soon.example", "alice");
+        soon.expires_at = Some(now() + 1);
+        cache
+            .store()
+            .unwrap()
+            .add(&BunnylolConfig::default(), "soon", soon)
+            .unwrap();
+        assert_eq!(cache.active().len(), 1);
+        std::thread::sleep(std::time::Duration::from_millis(2100));
+        assert!(cache.active().is_empty());
+    }
+
+    #[test]
+    fn test_validate_link() {
+        let mut config = BunnylolConfig::default();
+        config.user_bindings.insert(
+            "kb".to_string(),
+            crate::config::UserBinding::Url {
+                url: "https://kb.example".to_string(),
+                description: None,
+                override_builtin: false,
+            },
+        );
+        for (name, url, expected) in [
+            ("gh", "https://example.com", "built-in command"),
+            ("r/rust", "https://example.com", "built-in prefix"),
+            ("kb", "https://example.com", "[user_bindings]"),
+            ("two words", "https://example.com", "single word"),
+            ("handbook", "example.com", "absolute URL"),
+        ] {
+            let err = validate_link(&config, name, url).unwrap_err().to_string();
+            assert!(err.contains(expected), "{}: {}", name, err);
+        }
+        validate_link(&config, "oncall-rotation", "https://oncall.example/{}").unwrap();
+    }
+
+    #[test]
+    fn test_parse_expiry() {
+        assert_eq!(parse_expiry("90m"), Ok(90 * 60));
+        assert_eq!(parse_expiry("30d"), Ok(30 * 24 * 60 * 60));
+        assert_eq!(parse_expiry("2w"), Ok(14 * 24 * 60 * 60));
+        for value in ["", "30", "d", "0d", "-1d", "1y"] {
+            assert!(parse_expiry(value).is_err(), "{}", value);
+        }
+    }
+}
@@ -12,7 +12,9 @@ use bunnylol::BunnylolConfig;
 
 // CLI-only imports
 #[cfg(feature = "cli")]
-use bunnylol::{BunnylolCommandRegistry, History, utils};
+use bunnylol::explain::ResolutionTier;
+#[cfg(feature = "cli")]
+use bunnylol::{BunnylolCommandRegistry, History, Links, RepoContext, utils};
 #[cfg(feature = "cli")]
 use clap_complete::generate;
 #[cfg(feature = "cli")]
@@ -50,7 +52,7 @@ enum Commands {
         #[arg(short, long)]
         port: Option<u16>,
 
-        /// Address to bind to (overrides config file)
+        /// Address to bind to, or unix:/path for a Unix domain socket (overrides config file)
         #[arg(short, long)]
         address: Option<String>,
     },
@@ -59,6 +61,55 @@ enum Commands {
     #[cfg(feature = "cli")]
     Bindings,
 
+    /// Show how a query is resolved, tier by tier, without opening it
+    #[cfg(feature = "cli")]
+    Explain {
+        /// The query to explain (e.g. `gh facebook/react`)
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        query: Vec<String>,
+    },
+
+    /// Run binding regression tests from a bunnylol.tests.toml file
+    #[cfg(feature = "cli")]
+    Test {
+        /// Test file to run (default: ./bunnylol.tests.toml)
+        file: Option<std::path::PathBuf>,
+
+        /// Config file to resolve against (default: the normal config file)
+        #[arg(short, long)]
+        config: Option<std::path::PathBuf>,
+
+        /// Report format
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+
+        /// Write the report to this file and print the text report to stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Trust the nearest .bunnylol.toml so its bindings are loaded
+    #[cfg(feature = "cli")]
+    Trust {
+        /// Project file to trust (default: the nearest .bunnylol.toml)
+        file: Option<std::path::PathBuf>,
+
+        /// Stop trusting the file instead
+        #[arg(long)]
+        revoke: bool,
+    },
+
+    /// Hash a password (read from stdin) for an [auth.users] entry
+    #[cfg(feature = "cli")]
+    HashPassword,
+
+    /// Manage go-links (short links stored outside config.toml)
+    #[cfg(feature = "cli")]
+    Link {
+        #[command(subcommand)]
+        action: LinkAction,
+    },
+
     /// Generate shell completion scripts
     #[cfg(feature = "cli")]
     Completion {
@@ -80,6 +131,48 @@ enum Commands {
     Command(Vec<String>),
 }
 
+#[cfg(feature = "cli")]
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ReportFormat {
+    Text,
+    Json,
+    Junit,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Subcommand)]
+enum LinkAction {
+    /// Create a go-link (e.g. `bunnylol link add oncall https://oncall.example/{}`)
+    Add {
+        name: String,
+        /// Target URL; `{}` is replaced with the rest of the query
+        url: String,
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Stop resolving the link after this long (e.g. 30d, 12h, 2w)
+        #[arg(long)]
+        expires_in: Option<String>,
+    },
+    /// Change a go-link's URL, description or expiry
+    Update {
+        name: String,
+        #[arg(long)]
+        url: Option<String>,
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Stop resolving the link this long from now (e.g. 30d, 12h, 2w)
+        #[arg(long, conflicts_with = "no_expiry")]
+        expires_in: Option<String>,
+        /// Never expire the link
+        #[arg(long)]
+        no_expiry: bool,
+    },
+    /// Remove a go-link
+    Remove { name: String },
+    /// List go-links with their owners and hit counts
+    List,
+}
+
 #[cfg(feature = "cli")]
 #[derive(Subcommand)]
 enum ServiceAction {
@@ -88,6 +181,26 @@ enum ServiceAction {
         /// Allow network access (bind to 0.0.0.0). Default: localhost only (127.0.0.1)
         #[arg(short, long)]
         network: bool,
+
+        /// Serve HTTPS with this PEM certificate chain (needs --tls-key)
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<std::path::PathBuf>,
+
+        /// PEM private key for --tls-cert
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<std::path::PathBuf>,
+
+        /// Also listen for HTTP on this port and redirect it to HTTPS
+        #[arg(long, requires = "tls_cert")]
+        redirect_http: Option<u16>,
+
+        /// Listen on this Unix domain socket instead of a TCP port (for a reverse proxy)
+        #[arg(long, conflicts_with = "network")]
+        unix_socket: Option<std::path::PathBuf>,
+
+        /// Also install a bunnylol.socket unit, so systemd holds the socket across restarts
+        #[arg(long)]
+        socket: bool,
     },
     /// Uninstall bunnylol service
     Uninstall,
@@ -113,7 +226,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
     // Load configuration. Missing config creates defaults, but invalid config is fatal.
-    let config = BunnylolConfig::load().map_err(|e| format!("Invalid configuration: {}", e))?;
+    #[allow(unused_mut)]
+    let mut config = BunnylolConfig::load().map_err(|e| format!("Invalid configuration: {}", e))?;
+
+    // Merge a trusted project-local .bunnylol.toml for commands that resolve bindings
+    #[cfg(feature = "cli")]
+    if cli.list
+        || matches!(
+            cli.command,
+            None | Some(Commands::Command(_) | Commands::Explain { .. } | Commands::Bindings)
+        )
+    {
+        merge_project_bindings(&mut config);
+        config.load_links();
+    }
 
     // Initialize the global config singleton for commands that need it
     bunnylol::config::init_global_config(config.clone());
@@ -153,6 +279,61 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(())
         }
 
+        #[cfg(feature = "cli")]
+        Some(Commands::Explain { query }) => {
+            install_repo_context();
+            let full_args = query.join(" ");
+            let command = utils::get_command_from_query_string(&full_args);
+            print!(
+                "{}",
+                BunnylolCommandRegistry::explain_command(command, &full_args)
+            );
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Test {
+            file,
+            config: config_override,
+            format,
+            output,
+        }) => {
+            if !run_binding_tests(file, config_override, format, output, &config)? {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Trust { file, revoke }) => {
+            if let Err(e) = trust_project_file(file, revoke) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::HashPassword) => {
+            match read_password().and_then(|password| bunnylol::config::hash_password(&password)) {
+                Ok(hash) => println!("{}", hash),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Link { action }) => {
+            if let Err(e) = manage_links(action, &config) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
         #[cfg(feature = "cli")]
         Some(Commands::Completion { shell }) => {
             let mut cmd = Cli::command();
@@ -165,14 +346,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             use bunnylol::service::*;
 
             let result = match action {
-                ServiceAction::Install { network } => {
+                ServiceAction::Install {
+                    network,
+                    tls_cert,
+                    tls_key,
+                    redirect_http,
+                    unix_socket,
+                    socket,
+                } => {
                     // Use ServiceConfig with appropriate address based on --network flag
                     let service_config = ServiceConfig {
-                        address: if network {
+                        address: if let Some(path) = unix_socket {
+                            format!("unix:{}", path.display())
+                        } else if network {
                             "0.0.0.0".to_string() // Network access
                         } else {
                             "127.0.0.1".to_string() // Localhost only (secure default)
                         },
+                        tls: tls_cert
+                            .zip(tls_key)
+                            .map(|(cert, key)| bunnylol::config::TlsConfig {
+                                cert,
+                                key,
+                                redirect_http_port: redirect_http,
+                            }),
+                        socket_activation: socket,
                         ..Default::default()
                     };
 
@@ -241,17 +439,36 @@ fn execute_command(
         return Ok(());
     }
 
+    install_repo_context();
+
     // Join command parts (e.g., ["ig", "reels"] -> "ig reels")
     let full_args = args.join(" ");
 
     // Extract command and process. Aliases are handled inside process_command
     // via the unified [user_bindings] table — see Q2 in the refactor plan.
     let command = utils::get_command_from_query_string(&full_args);
-    let url = BunnylolCommandRegistry::process_command(command, &full_args);
+    let trace = BunnylolCommandRegistry::explain_command(command, &full_args);
+    let url = trace.url.clone();
+
+    if config.url_policy.cli
+        && let Err(reason) = config.url_policy.check(&url)
+    {
+        return Err(format!("Refusing to open {:?}: {} (see [url_policy])", url, reason).into());
+    }
 
     // Print URL
     println!("{}", url);
 
+    // Show which [[rewrites]] changed it (stderr, so stdout stays the URL)
+    if dry_run {
+        for rewrite in &trace.rewrites {
+            eprintln!(
+                "Rewrite `{}`: {} -> {}",
+                rewrite.rule, rewrite.before, rewrite.after
+            );
+        }
+    }
+
     // Track command in history if enabled
     if config.history.enabled
         && let Some(history) = History::new(config)
@@ -262,14 +479,288 @@ fn execute_command(
         }
     }
 
-    // Open in browser unless --dry-run
+    // Open in browser unless --dry-run, counting go-link hits
     if !dry_run {
+        if trace.matched_tier() == Some(ResolutionTier::Link)
+            && let Some(links) = Links::new()
+            && let Err(e) = links.record_hit(command)
+        {
+            eprintln!("Warning: Failed to count go-link hit: {}", e);
+        }
         open_url(&url, config)?;
     }
 
     Ok(())
 }
 
+#[cfg(feature = "cli")]
+fn manage_links(action: LinkAction, config: &BunnylolConfig) -> Result<(), String> {
+    use bunnylol::links::{self, ShortLink};
+
+    let store = Links::new().ok_or("Could not determine the data directory")?;
+    let expires_at = |expires_in: &str| links::parse_expiry(expires_in).map(|s| links::now() + s);
+    match action {
+        LinkAction::Add {
+            name,
+            url,
+            description,
+            expires_in,
+        } => {
+            let link = ShortLink {
+                url,
+                description,
+                owner: whoami::username().unwrap_or_else(|_| "unknown".to_string()),
+                created_at: links::now(),
+                hits: 0,
+                expires_at: expires_in.as_deref().map(expires_at).transpose()?,
+            };
+            store
+                .add(config, &name, link)
+                .map_err(|e| format!("{}: {}", name, e))?;
+            println!("Added go-link '{}'", name);
+        }
+        LinkAction::Update {
+            name,
+            url,
+            description,
+            expires_in,
+            no_expiry,
+        } => {
+            let expiry = match (expires_in, no_expiry) {
+                (Some(expires_in), _) => Some(Some(expires_at(&expires_in)?)),
+                (None, true) => Some(None),
+                (None, false) => None,
+            };
+            // The local user owns the file, so they may change any link
+            store
+                .update(config, &name, None, |link| {
+                    if let Some(url) = url {
+                        link.url = url;
+                    }
+                    if description.is_some() {
+                        link.description = description;
+                    }
+                    if let Some(expiry) = expiry {
+                        link.expires_at = expiry;
+                    }
+                })
+                .map_err(|e| format!("{}: {}", name, e))?;
+            println!("Updated go-link '{}'", name);
+        }
+        LinkAction::Remove { name } => {
+            store
+                .remove(&name, None)
+                .map_err(|e| format!("{}: {}", name, e))?;
+            println!("Removed go-link '{}'", name);
+        }
+        LinkAction::List => {
+            let now = links::now();
+            let rows: Vec<LinkRow> = store
+                .read_all()?
+                .into_iter()
+                .map(|(name, link)| LinkRow {
+                    expires: match link.expires_at {
+                        Some(_) if link.is_expired(now) => "expired".to_string(),
+                        Some(at) => format!("in {}", format_duration(at - now)),
+                        None => "never".to_string(),
+                    },
+                    name,
+                    url: link.url,
+                    owner: link.owner,
+                    hits: link.hits,
+                })
+                .collect();
+            if rows.is_empty() {
+                println!("No go-links yet. Add one with `bunnylol link add <name> <url>`.");
+            } else {
+                println!("{}", Table::new(rows).with(Style::rounded()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A rough, human-sized duration like `3d` or `5h`.
+#[cfg(feature = "cli")]
+fn format_duration(seconds: u64) -> String {
+    match seconds {
+        s if s >= 2 * 24 * 60 * 60 => format!("{}d", s / (24 * 60 * 60)),
+        s if s >= 2 * 60 * 60 => format!("{}h", s / (60 * 60)),
+        s if s >= 2 * 60 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
+
+/// Make the git repository enclosing the working directory, if any,
+/// available to `gh .`, `gl issues` and `{repo}`-style URL placeholders.
+#[cfg(feature = "cli")]
+fn install_repo_context() {
+    let context = std::env::current_dir()
+        .ok()
+        .and_then(|dir| RepoContext::discover(&dir));
+    RepoContext::install(context);
+}
+
+/// Merge the nearest `.bunnylol.toml` over `config` if the user trusts it,
+/// asking the first time a file (or a changed file) is seen. Problems with
+/// the project file are warnings: the user config still works without it.
+#[cfg(feature = "cli")]
+fn merge_project_bindings(config: &mut BunnylolConfig) {
+    use bunnylol::config::{ProjectFile, ProjectTrust, TrustState};
+    use std::io::{BufRead, IsTerminal, Write};
+
+    let Some(path) = std::env::current_dir()
+        .ok()
+        .and_then(|dir| ProjectFile::discover(&dir))
+    else {
+        return;
+    };
+    // `bunnylol trust` records canonical paths
+    let path = path.canonicalize().unwrap_or(path);
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Warning: Failed to read {}: {}", path.display(), e);
+            return;
+        }
+    };
+
+    let mut trust = ProjectTrust::load();
+    let state = trust.state(&path, &contents);
+    if state != TrustState::Trusted {
+        let seen = if state == TrustState::Changed {
+            "has changed since you trusted it"
+        } else {
+            "is not trusted yet"
+        };
+        if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+            eprintln!(
+                "Note: ignoring {} ({}). Run `bunnylol trust` to load its bindings.",
+                path.display(),
+                seen
+            );
+            return;
+        }
+        eprint!(
+            "{} {}. Load its bindings from now on? [y/N] ",
+            path.display(),
+            seen
+        );
+        let _ = std::io::stderr().flush();
+        let mut answer = String::new();
+        let _ = std::io::stdin().lock().read_line(&mut answer);
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            eprintln!("Ignoring {}.", path.display());
+            return;
+        }
+        if let Err(e) = trust.trust(&path, &contents) {
+            eprintln!("Warning: Failed to remember trusted project file: {}", e);
+        }
+    }
+
+    match ProjectFile::parse(&contents) {
+        Ok(project) => config.merge_project_file(&path, project),
+        Err(e) => eprintln!("Warning: ignoring invalid {}: {}", path.display(), e),
+    }
+}
+
+/// `bunnylol trust [FILE] [--revoke]`
+#[cfg(feature = "cli")]
+fn trust_project_file(file: Option<std::path::PathBuf>, revoke: bool) -> Result<(), String> {
+    use bunnylol::config::{PROJECT_FILE_NAME, ProjectFile, ProjectTrust};
+
+    let path = match file {
+        Some(file) => file,
+        None => std::env::current_dir()
+            .ok()
+            .and_then(|dir| ProjectFile::discover(&dir))
+            .ok_or_else(|| {
+                format!(
+                    "No {} found in this directory or its parents",
+                    PROJECT_FILE_NAME
+                )
+            })?,
+    };
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let mut trust = ProjectTrust::load();
+    if revoke {
+        if trust.revoke(&path)? {
+            println!("No longer trusting {}", path.display());
+        } else {
+            println!("{} was not trusted", path.display());
+        }
+        return Ok(());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    ProjectFile::parse(&contents).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+    trust.trust(&path, &contents)?;
+    println!("Trusted {}", path.display());
+    Ok(())
+}
+
+/// Read one line from stdin, prompting when it's a terminal.
+#[cfg(feature = "cli")]
+fn read_password() -> Result<String, String> {
+    use std::io::IsTerminal;
+
+    if std::io::stdin().is_terminal() {
+        eprint!("Password (shown as you type): ");
+    }
+    let mut password = String::new();
+    std::io::stdin()
+        .read_line(&mut password)
+        .map_err(|e| format!("Failed to read password: {}", e))?;
+    let password = password.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        return Err("Password must not be empty".to_string());
+    }
+    Ok(password.to_string())
+}
+
+/// Run `bunnylol test` and print or write the report. Returns whether every
+/// case passed.
+#[cfg(feature = "cli")]
+fn run_binding_tests(
+    file: Option<std::path::PathBuf>,
+    config_override: Option<std::path::PathBuf>,
+    format: ReportFormat,
+    output: Option<std::path::PathBuf>,
+    config: &BunnylolConfig,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    use bunnylol::binding_tests::{self, BindingTestFile};
+
+    let path = file.unwrap_or_else(|| std::path::PathBuf::from(binding_tests::DEFAULT_TEST_FILE));
+    let test_file = BindingTestFile::load(&path)?;
+    let base = match config_override {
+        Some(config_path) => BunnylolConfig::read_from_path(&config_path)?,
+        None => config.clone(),
+    };
+
+    let report = binding_tests::run(&test_file, &path, &base);
+    let formatted = match format {
+        ReportFormat::Text => report.to_text(),
+        ReportFormat::Json => report.to_json(),
+        ReportFormat::Junit => report.to_junit(),
+    };
+
+    match output {
+        Some(output_path) => {
+            std::fs::write(&output_path, formatted).map_err(|e| {
+                format!("Failed to write report to {}: {}", output_path.display(), e)
+            })?;
+            print!("{}", report.to_text());
+        }
+        None => print!("{}", formatted),
+    }
+
+    Ok(report.is_success())
+}
+
 #[cfg(feature = "cli")]
 fn open_url(url: &str, config: &BunnylolConfig) -> Result<(), Box<dyn std::error::Error>> {
     match &config.browser {
@@ -285,6 +776,21 @@ fn open_url(url: &str, config: &BunnylolConfig) -> Result<(), Box<dyn std::error
     }
 }
 
+#[cfg(feature = "cli")]
+#[derive(Tabled)]
+struct LinkRow {
+    #[tabled(rename = "Link")]
+    name: String,
+    #[tabled(rename = "URL")]
+    url: String,
+    #[tabled(rename = "Owner")]
+    owner: String,
+    #[tabled(rename = "Hits")]
+    hits: u64,
+    #[tabled(rename = "Expires")]
+    expires: String,
+}
+
 #[cfg(feature = "cli")]
 #[derive(Tabled)]
 struct CommandRow {
@@ -320,8 +826,8 @@ fn print_commands() {
             CommandRow {
                 command: primary,
                 aliases,
+                example: cmd.all_examples().collect::<Vec<_>>().join("\n"),
                 description: cmd.description,
-                example: cmd.example,
             }
         })
         .collect();
@@ -367,7 +873,7 @@ fn print_commands() {
 
     // Append user-defined bindings as a second table, if any.
     if let Some(cfg) = bunnylol::config::get_global_config()
-        && !cfg.user_bindings.is_empty()
+        && (!cfg.user_bindings.is_empty() || !cfg.command_instance_names().is_empty())
     {
         print_user_bindings_table(&cfg);
     }
@@ -394,13 +900,18 @@ fn print_user_bindings_table(cfg: &BunnylolConfig) {
         target: String,
         #[tabled(rename = "Description")]
         description: String,
+        #[tabled(rename = "Source")]
+        source: String,
     }
 
+    let config_source = BunnylolConfig::get_config_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "config.toml".to_string());
     let builtins = BunnylolCommandRegistry::builtin_binding_names();
     let mut entries: Vec<(&String, &UserBinding)> = cfg.user_bindings.iter().collect();
     entries.sort_by_key(|(k, _)| k.to_lowercase());
 
-    let rows: Vec<UserBindingRow> = entries
+    let mut rows: Vec<UserBindingRow> = entries
         .into_iter()
         .map(|(name, b)| {
             let status = if b.overrides_builtin() {
@@ -416,11 +927,39 @@ fn print_user_bindings_table(cfg: &BunnylolConfig) {
                 status: status.to_string(),
                 target: b.display_target().to_string(),
                 description: b.description().unwrap_or("—").to_string(),
+                source: cfg
+                    .binding_source(name)
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_else(|| config_source.clone()),
             }
         })
         .collect();
 
-    println!("User bindings (from ~/.config/bunnylol/config.toml):");
+    // Named [commands.<name>] instances, e.g. `ghe` for GitHub Enterprise
+    for name in cfg.command_instance_names() {
+        let Some((kind, settings)) = cfg.command_instance(name) else {
+            continue;
+        };
+        let Some(info) = BunnylolCommandRegistry::command_instance_info(name, kind, settings)
+        else {
+            continue;
+        };
+        rows.push(UserBindingRow {
+            command: name.to_string(),
+            kind: format!("{} instance", kind),
+            status: if builtins.contains(name) {
+                "ignored"
+            } else {
+                "active"
+            }
+            .to_string(),
+            target: info.example,
+            description: info.description,
+            source: config_source.clone(),
+        });
+    }
+
+    println!("User bindings:");
     let mut table = Table::new(rows);
     table
         .with(Style::rounded())
@@ -439,13 +978,34 @@ fn print_user_bindings_table(cfg: &BunnylolConfig) {
 fn report_custom_bindings_status(config: &BunnylolConfig) {
     let bindings_count = config.user_bindings.len();
     let aliases_count = config.aliases.len();
+    let patterns_count = config.patterns.len();
 
-    if bindings_count == 0 && aliases_count == 0 {
+    if bindings_count == 0
+        && aliases_count == 0
+        && patterns_count == 0
+        && config.commands.is_empty()
+    {
         return;
     }
 
     let conflicts = bunnylol::BunnylolCommandRegistry::validate_user_bindings(config);
-    let accepted = bindings_count - conflicts.len();
+
+    // Bindings and patterns merged from a project-local .bunnylol.toml
+    let (project_bindings, project_patterns) = config
+        .project
+        .as_ref()
+        .map_or((0, 0), |project| (project.bindings.len(), project.patterns));
+    let from_config = |name: &str| config.binding_source(name).is_none();
+    let accepted = config
+        .user_bindings
+        .keys()
+        .filter(|name| from_config(name))
+        .count()
+        - conflicts
+            .iter()
+            .filter(|conflict| from_config(&conflict.name))
+            .count();
+    let patterns_count = patterns_count - project_patterns;
 
     if accepted > 0 {
         eprintln!(
@@ -455,6 +1015,25 @@ fn report_custom_bindings_status(config: &BunnylolConfig) {
         );
     }
 
+    if patterns_count > 0 {
+        eprintln!(
+            "Loaded {} pattern rule{} from config.toml.",
+            patterns_count,
+            if patterns_count == 1 { "" } else { "s" },
+        );
+    }
+
+    if let Some(project) = &config.project {
+        eprintln!(
+            "Loaded {} binding{} and {} pattern rule{} from {}.",
+            project_bindings,
+            if project_bindings == 1 { "" } else { "s" },
+            project_patterns,
+            if project_patterns == 1 { "" } else { "s" },
+            project.path.display(),
+        );
+    }
+
     if aliases_count > 0 {
         eprintln!(
             "Note: [aliases] is deprecated — move entries into [user_bindings] as \
@@ -471,4 +1050,19 @@ fn report_custom_bindings_status(config: &BunnylolConfig) {
             conflict.name, conflict.name, conflict.target,
         );
     }
+
+    for name in bunnylol::BunnylolCommandRegistry::shadowed_command_instances(config) {
+        eprintln!(
+            "  Warning: [commands.{}] is shadowed by the built-in '{}' and was ignored. \
+             Pick another name for the instance.",
+            name, name,
+        );
+    }
+
+    for diagnostic in bunnylol::BunnylolCommandRegistry::validate_patterns(config) {
+        eprintln!(
+            "  Warning: pattern rule '{}' {}",
+            diagnostic.rule, diagnostic.message
+        );
+    }
 }
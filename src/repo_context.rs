@@ -0,0 +1,332 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! The git repository the CLI was run from, for `gh .`, `gl issues` and the
+//! `{repo}`, `{branch}` and `{sha}` URL template placeholders.
+//!
+//! Discovery only reads files under `.git` (remotes from `config`, the
+//! branch from `HEAD`, the commit from loose refs or `packed-refs`); it never
+//! runs `git` or touches the network. The CLI installs the context for the
+//! current thread before resolving a command. The server never does, so
+//! [`RepoContext::current`] is always `None` there and every repo-aware
+//! form falls back to its normal behavior.
+
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A remote from `.git/config`, reduced to a host and repository path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitRemote {
+    pub name: String,
+    /// Lowercased host, without user info or port (e.g. "github.com").
+    pub host: String,
+    /// Repository path without `.git` (e.g. "facebook/react").
+    pub path: String,
+}
+
+/// The enclosing git repository of the CLI's working directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoContext {
+    /// Remotes with a recognizable host, `origin` first, then in config order.
+    pub remotes: Vec<GitRemote>,
+    /// Checked-out branch, or `None` for a detached HEAD.
+    pub branch: Option<String>,
+    /// Commit HEAD points at, if it could be read.
+    pub sha: Option<String>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<RepoContext>> = const { RefCell::new(None) };
+    static INSTALLED: Cell<bool> = const { Cell::new(false) };
+}
+
+impl RepoContext {
+    /// Find the repository enclosing `start` by walking up to the nearest
+    /// `.git` directory (or `.git` file, for worktrees and submodules).
+    pub fn discover(start: &Path) -> Option<Self> {
+        let git_dir = start.ancestors().find_map(git_dir_of)?;
+        // Worktrees keep HEAD locally and share config and refs via `commondir`
+        let common_dir = fs::read_to_string(git_dir.join("commondir"))
+            .map(|common| git_dir.join(common.trim()))
+            .unwrap_or_else(|_| git_dir.clone());
+
+        let mut remotes = fs::read_to_string(common_dir.join("config"))
+            .map(|config| parse_remotes(&config))
+            .unwrap_or_default();
+        remotes.sort_by_key(|remote| remote.name != "origin");
+
+        let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+        let head = head.trim();
+        let (branch, sha) = match head.strip_prefix("ref: ") {
+            Some(reference) => (
+                reference.strip_prefix("refs/heads/").map(str::to_string),
+                read_ref(&git_dir, &common_dir, reference),
+            ),
+            None => (None, Some(head.to_string()).filter(|sha| is_sha(sha))),
+        };
+
+        Some(RepoContext {
+            remotes,
+            branch,
+            sha,
+        })
+    }
+
+    /// Make `context` the one commands see on this thread. Only the CLI
+    /// calls this.
+    pub fn install(context: Option<RepoContext>) {
+        CURRENT.with(|current| *current.borrow_mut() = context);
+        INSTALLED.with(|installed| installed.set(true));
+    }
+
+    /// Whether the CLI looked for a repository on this thread, even if it
+    /// found none. Always `false` on the server.
+    pub fn installed() -> bool {
+        INSTALLED.with(Cell::get)
+    }
+
+    /// The context installed on this thread, if any.
+    pub fn current() -> Option<RepoContext> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// The repository path of the first remote hosted at `base_url`
+    /// (e.g. "https://github.com" or "https://code.corp.example/gitlab").
+    pub fn repo_for(&self, base_url: &str) -> Option<&str> {
+        let (host, prefix) = split_url(base_url)?;
+        self.remotes
+            .iter()
+            .filter(|remote| remote.host == host)
+            .map(|remote| {
+                // A base URL with a path ("/gitlab") may or may not appear in
+                // the remote URL; ssh remotes usually omit it.
+                remote
+                    .path
+                    .strip_prefix(&format!("{}/", prefix))
+                    .filter(|_| !prefix.is_empty())
+                    .unwrap_or(&remote.path)
+            })
+            .next()
+    }
+
+    /// The repository path of the preferred remote, whatever its host.
+    pub fn repo(&self) -> Option<&str> {
+        self.remotes.first().map(|remote| remote.path.as_str())
+    }
+}
+
+/// The git directory for a checkout rooted at `dir`, if there is one.
+fn git_dir_of(dir: &Path) -> Option<PathBuf> {
+    let dot_git = dir.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let pointer = fs::read_to_string(&dot_git).ok()?;
+    let target = pointer.trim().strip_prefix("gitdir:")?.trim();
+    Some(dir.join(target))
+}
+
+/// Resolve `reference` (e.g. "refs/heads/main") from loose refs, then
+/// `packed-refs`.
+fn read_ref(git_dir: &Path, common_dir: &Path, reference: &str) -> Option<String> {
+    [git_dir, common_dir]
+        .iter()
+        .find_map(|dir| fs::read_to_string(dir.join(reference)).ok())
+        .map(|sha| sha.trim().to_string())
+        .or_else(|| {
+            let packed = fs::read_to_string(common_dir.join("packed-refs")).ok()?;
+            packed.lines().find_map(|line| {
+                let (sha, name) = line.split_once(' ')?;
+                (name == reference).then(|| sha.to_string())
+            })
+        })
+        .filter(|sha| is_sha(sha))
+}
+
+fn is_sha(s: &str) -> bool {
+    s.len() >= 40 && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Collect `[remote "<name>"] url = ...` entries from a git config file.
+fn parse_remotes(config: &str) -> Vec<GitRemote> {
+    let mut remotes = Vec::new();
+    let mut section: Option<String> = None;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line
+                .strip_prefix("[remote \"")
+                .and_then(|rest| rest.strip_suffix("\"]"))
+                .map(str::to_string);
+            continue;
+        }
+        let (Some(name), Some((key, value))) = (&section, line.split_once('=')) else {
+            continue;
+        };
+        if key.trim() != "url" || remotes.iter().any(|r: &GitRemote| &r.name == name) {
+            continue;
+        }
+        if let Some((host, path)) = parse_remote_url(value.trim()) {
+            remotes.push(GitRemote {
+                name: name.clone(),
+                host,
+                path,
+            });
+        }
+    }
+    remotes
+}
+
+/// Split a remote URL into host and repository path. Handles
+/// `https://host/owner/repo.git`, `ssh://git@host:22/owner/repo` and
+/// `git@host:owner/repo.git`; local paths return `None`.
+fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    let (host, path) = if url.contains("://") {
+        split_url(url)?
+    } else {
+        // scp-like syntax: [user@]host:path
+        let (authority, path) = url.split_once(':')?;
+        if authority.contains('/') {
+            return None;
+        }
+        let host = authority.rsplit('@').next()?.to_lowercase();
+        (host, path.trim_matches('/').to_string())
+    };
+    let path = path.strip_suffix(".git").unwrap_or(&path).to_string();
+    (!host.is_empty() && path.contains('/')).then_some((host, path))
+}
+
+/// Split `scheme://[user@]host[:port]/path` into a lowercased host and the
+/// path without surrounding slashes.
+fn split_url(url: &str) -> Option<(String, String)> {
+    let (_, rest) = url.split_once("://")?;
+    let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?.to_lowercase();
+    Some((host, path.trim_matches('/').to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn checkout(config: &str, head: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join(".git/config"), config);
+        write(&dir.path().join(".git/HEAD"), head);
+        dir
+    }
+
+    #[test]
+    fn test_parse_remote_url_forms() {
+        let expected = Some(("github.com".to_string(), "facebook/react".to_string()));
+        assert_eq!(
+            parse_remote_url("https://github.com/facebook/react.git"),
+            expected
+        );
+        assert_eq!(
+            parse_remote_url("git@github.com:facebook/react.git"),
+            expected
+        );
+        assert_eq!(
+            parse_remote_url("ssh://git@GitHub.com:22/facebook/react"),
+            expected
+        );
+        assert_eq!(
+            parse_remote_url("https://gitlab.com/group/sub/project.git"),
+            Some(("gitlab.com".to_string(), "group/sub/project".to_string()))
+        );
+        assert_eq!(parse_remote_url("/srv/git/project.git"), None);
+        assert_eq!(parse_remote_url("../project"), None);
+    }
+
+    #[test]
+    fn test_discover_branch_sha_and_remotes() {
+        let dir = checkout(
+            r#"
+[core]
+	bare = false
+[remote "upstream"]
+	url = git@gitlab.com:group/project.git
+[remote "origin"]
+	url = https://github.com/facebook/react.git
+	fetch = +refs/heads/*:refs/remotes/origin/*
+[branch "main"]
+	remote = origin
+"#,
+            "ref: refs/heads/feature/x\n",
+        );
+        write(&dir.path().join(".git/refs/heads/feature/x"), SHA);
+        let nested = dir.path().join("packages/react");
+        fs::create_dir_all(&nested).unwrap();
+
+        let context = RepoContext::discover(&nested).expect("repo found");
+        assert_eq!(context.branch.as_deref(), Some("feature/x"));
+        assert_eq!(context.sha.as_deref(), Some(SHA));
+        assert_eq!(context.repo(), Some("facebook/react"));
+        assert_eq!(
+            context.repo_for("https://github.com"),
+            Some("facebook/react")
+        );
+        assert_eq!(
+            context.repo_for("https://gitlab.com"),
+            Some("group/project")
+        );
+        assert_eq!(context.repo_for("https://github.corp.example"), None);
+    }
+
+    #[test]
+    fn test_discover_packed_refs_and_detached_head() {
+        let dir = checkout("", "ref: refs/heads/main\n");
+        write(
+            &dir.path().join(".git/packed-refs"),
+            &format!("# pack-refs with: peeled\n{} refs/heads/main\n", SHA),
+        );
+        let context = RepoContext::discover(dir.path()).unwrap();
+        assert_eq!(context.sha.as_deref(), Some(SHA));
+        assert!(context.remotes.is_empty());
+
+        let dir = checkout("", &format!("{}\n", SHA));
+        let context = RepoContext::discover(dir.path()).unwrap();
+        assert_eq!(context.branch, None);
+        assert_eq!(context.sha.as_deref(), Some(SHA));
+    }
+
+    #[test]
+    fn test_repo_for_strips_base_path_prefix() {
+        let context = RepoContext {
+            remotes: vec![GitRemote {
+                name: "origin".to_string(),
+                host: "code.corp.example".to_string(),
+                path: "gitlab/infra/deploy".to_string(),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            context.repo_for("https://code.corp.example/gitlab"),
+            Some("infra/deploy")
+        );
+        assert_eq!(
+            context.repo_for("https://code.corp.example"),
+            Some("gitlab/infra/deploy")
+        );
+    }
+
+    #[test]
+    fn test_no_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(RepoContext::discover(dir.path()), None);
+        assert_eq!(RepoContext::current(), None);
+    }
+}
@@ -0,0 +1,347 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! `/api/user_bindings`: list, add, replace and remove `[user_bindings]`
+//! entries of the running server's config file.
+//!
+//! Every request needs the admin role (see [`super::auth`]); with neither
+//! `[server] admin_token` nor `[auth]` credentials configured the API answers
+//! 404. `GET` returns the bindings and an `ETag`; `POST`, `PUT` and `DELETE`
+//! must send it back in `If-Match` and get 412 if the file changed in between.
+//!
+//! `/api/personal_bindings` works the same way on the signed-in user's
+//! [`PersonalBindings`], and only needs a signed-in user.
+
+use rocket::http::{Header, Status};
+use rocket::request::{self, FromRequest, Request};
+use rocket::response::{self, Responder, Response};
+use rocket::serde::json::Json;
+use rocket::{State, serde::json};
+use serde::Deserialize;
+
+use super::auth::Identity;
+use crate::config::{
+    BindingEdit, BindingEditError, PersonalBindings, Role, UserBinding, UserBindingsSnapshot,
+};
+use crate::{BunnylolCommandRegistry, ConfigReloader};
+
+/// Who sent the request, and its `If-Match` header.
+pub(super) struct AdminHeaders {
+    identity: Identity,
+    if_match: Option<String>,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminHeaders {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let identity = Identity::of(req).clone();
+        let if_match = req.headers().get_one("If-Match").map(str::to_string);
+        request::Outcome::Success(AdminHeaders { identity, if_match })
+    }
+}
+
+/// A JSON response with an optional `ETag`.
+pub(super) struct ApiResponse {
+    status: Status,
+    etag: Option<String>,
+    body: serde_json::Value,
+}
+
+impl ApiResponse {
+    pub(super) fn json(status: Status, body: serde_json::Value) -> Self {
+        ApiResponse {
+            status,
+            etag: None,
+            body,
+        }
+    }
+
+    fn ok(status: Status, snapshot: UserBindingsSnapshot) -> Self {
+        ApiResponse {
+            status,
+            etag: Some(snapshot.etag.clone()),
+            body: serde_json::json!(snapshot),
+        }
+    }
+
+    pub(super) fn error(status: Status, message: impl std::fmt::Display) -> Self {
+        ApiResponse {
+            status,
+            etag: None,
+            body: serde_json::json!({ "error": message.to_string() }),
+        }
+    }
+}
+
+impl From<BindingEditError> for ApiResponse {
+    fn from(error: BindingEditError) -> Self {
+        let status = match &error {
+            BindingEditError::NoConfigFile | BindingEditError::Io(_) => Status::InternalServerError,
+            BindingEditError::Stale(_) => Status::PreconditionFailed,
+            BindingEditError::Exists => Status::Conflict,
+            BindingEditError::NotFound => Status::NotFound,
+            BindingEditError::Invalid(_) => Status::UnprocessableEntity,
+        };
+        let etag = match &error {
+            BindingEditError::Stale(etag) => Some(etag.clone()),
+            _ => None,
+        };
+        ApiResponse {
+            etag,
+            ..ApiResponse::error(status, error)
+        }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ApiResponse {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Response::build_from(json::Json(self.body).respond_to(req)?);
+        response.status(self.status);
+        if let Some(etag) = self.etag {
+            response.header(Header::new("ETag", etag));
+        }
+        if self.status == Status::Unauthorized {
+            response.header(Header::new("WWW-Authenticate", "Bearer"));
+        }
+        response.ok()
+    }
+}
+
+impl AdminHeaders {
+    fn authorize(&self, config: &ConfigReloader) -> Result<(), ApiResponse> {
+        let config = config.current();
+        if config.server.admin_token.is_none() && !config.auth.has_credentials() {
+            return Err(ApiResponse::error(
+                Status::NotFound,
+                "the admin API is disabled; set [server] admin_token or configure [auth] to enable it",
+            ));
+        }
+        if self.identity.allows(Role::Admin) {
+            Ok(())
+        } else if self.identity.user.is_some() {
+            Err(ApiResponse::error(
+                Status::Forbidden,
+                "editing bindings needs the admin role",
+            ))
+        } else {
+            Err(ApiResponse::error(
+                Status::Unauthorized,
+                "missing or wrong admin credentials",
+            ))
+        }
+    }
+
+    /// The signed-in user, for changes that are recorded against them.
+    pub(super) fn user(&self) -> Result<&str, ApiResponse> {
+        self.identity
+            .user
+            .as_deref()
+            .ok_or_else(|| ApiResponse::error(Status::Unauthorized, "sign in to make this change"))
+    }
+
+    pub(super) fn is_admin(&self) -> bool {
+        self.identity.allows(Role::Admin)
+    }
+
+    fn if_match(&self) -> Result<&str, ApiResponse> {
+        self.if_match.as_deref().ok_or_else(|| {
+            ApiResponse::error(
+                Status::PreconditionRequired,
+                "send the ETag from GET /api/user_bindings in an If-Match header",
+            )
+        })
+    }
+}
+
+/// Body of `POST /api/user_bindings`: a binding plus its name.
+#[derive(Deserialize)]
+pub(super) struct NewBinding {
+    name: String,
+    #[serde(flatten)]
+    binding: UserBinding,
+}
+
+fn edit(
+    config: &ConfigReloader,
+    headers: &AdminHeaders,
+    name: &str,
+    edit: BindingEdit,
+    status: Status,
+) -> ApiResponse {
+    if let Err(response) = headers.authorize(config) {
+        return response;
+    }
+    let if_match = match headers.if_match() {
+        Ok(if_match) => if_match,
+        Err(response) => return response,
+    };
+    match config.edit_user_binding(
+        if_match,
+        name,
+        edit,
+        BunnylolCommandRegistry::validate_user_bindings,
+    ) {
+        Ok(snapshot) => ApiResponse::ok(status, snapshot),
+        Err(e) => e.into(),
+    }
+}
+
+fn edit_personal(
+    config: &ConfigReloader,
+    personal: &PersonalBindings,
+    headers: &AdminHeaders,
+    name: &str,
+    edit: BindingEdit,
+    status: Status,
+) -> ApiResponse {
+    let (user, if_match) = match (headers.user(), headers.if_match()) {
+        (Ok(user), Ok(if_match)) => (user, if_match),
+        (Err(response), _) | (_, Err(response)) => return response,
+    };
+    match personal.edit(
+        user,
+        if_match,
+        name,
+        edit,
+        &config.current(),
+        BunnylolCommandRegistry::validate_user_bindings,
+    ) {
+        Ok(snapshot) => ApiResponse::ok(status, snapshot),
+        Err(e) => e.into(),
+    }
+}
+
+#[rocket::get("/api/user_bindings")]
+pub(super) fn list_user_bindings(
+    config: &State<ConfigReloader>,
+    headers: AdminHeaders,
+) -> ApiResponse {
+    if let Err(response) = headers.authorize(config) {
+        return response;
+    }
+    match config.user_bindings_snapshot() {
+        Ok(snapshot) => ApiResponse::ok(Status::Ok, snapshot),
+        Err(e) => e.into(),
+    }
+}
+
+#[rocket::post("/api/user_bindings", data = "<body>")]
+pub(super) fn create_user_binding(
+    config: &State<ConfigReloader>,
+    headers: AdminHeaders,
+    body: Result<Json<NewBinding>, json::Error<'_>>,
+) -> ApiResponse {
+    match body {
+        Ok(Json(NewBinding { name, binding })) => edit(
+            config,
+            &headers,
+            &name,
+            BindingEdit::Create(binding),
+            Status::Created,
+        ),
+        Err(e) => ApiResponse::error(Status::BadRequest, e),
+    }
+}
+
+#[rocket::put("/api/user_bindings/<name>", data = "<body>")]
+pub(super) fn replace_user_binding(
+    name: &str,
+    config: &State<ConfigReloader>,
+    headers: AdminHeaders,
+    body: Result<Json<UserBinding>, json::Error<'_>>,
+) -> ApiResponse {
+    match body {
+        Ok(Json(binding)) => edit(
+            config,
+            &headers,
+            name,
+            BindingEdit::Replace(binding),
+            Status::Ok,
+        ),
+        Err(e) => ApiResponse::error(Status::BadRequest, e),
+    }
+}
+
+#[rocket::delete("/api/user_bindings/<name>")]
+pub(super) fn delete_user_binding(
+    name: &str,
+    config: &State<ConfigReloader>,
+    headers: AdminHeaders,
+) -> ApiResponse {
+    edit(config, &headers, name, BindingEdit::Delete, Status::Ok)
+}
+
+#[rocket::get("/api/personal_bindings")]
+pub(super) fn list_personal_bindings(
+    personal: &State<PersonalBindings>,
+    headers: AdminHeaders,
+) -> ApiResponse {
+    match headers.user() {
+        Ok(user) => ApiResponse::ok(Status::Ok, personal.snapshot(user)),
+        Err(response) => response,
+    }
+}
+
+#[rocket::post("/api/personal_bindings", data = "<body>")]
+pub(super) fn create_personal_binding(
+    config: &State<ConfigReloader>,
+    personal: &State<PersonalBindings>,
+    headers: AdminHeaders,
+    body: Result<Json<NewBinding>, json::Error<'_>>,
+) -> ApiResponse {
+    match body {
+        Ok(Json(NewBinding { name, binding })) => edit_personal(
+            config,
+            personal,
+            &headers,
+            &name,
+            BindingEdit::Create(binding),
+            Status::Created,
+        ),
+        Err(e) => ApiResponse::error(Status::BadRequest, e),
+    }
+}
+
+#[rocket::put("/api/personal_bindings/<name>", data = "<body>")]
+pub(super) fn replace_personal_binding(
+    name: &str,
+    config: &State<ConfigReloader>,
+    personal: &State<PersonalBindings>,
+    headers: AdminHeaders,
+    body: Result<Json<UserBinding>, json::Error<'_>>,
+) -> ApiResponse {
+    match body {
+        Ok(Json(binding)) => edit_personal(
+            config,
+            personal,
+            &headers,
+            name,
+            BindingEdit::Replace(binding),
+            Status::Ok,
+        ),
+        Err(e) => ApiResponse::error(Status::BadRequest, e),
+    }
+}
+
+#[rocket::delete("/api/personal_bindings/<name>")]
+pub(super) fn delete_personal_binding(
+    name: &str,
+    config: &State<ConfigReloader>,
+    personal: &State<PersonalBindings>,
+    headers: AdminHeaders,
+) -> ApiResponse {
+    edit_personal(
+        config,
+        personal,
+        &headers,
+        name,
+        BindingEdit::Delete,
+        Status::Ok,
+    )
+}
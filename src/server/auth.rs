@@ -0,0 +1,345 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Request guards for `[auth]`.
+//!
+//! [`Identity`] works out who sent a request: a bearer token from
+//! `[auth.tokens]` (or `[server] admin_token`), HTTP basic auth for
+//! `[auth.users]`, or a header set by a `[auth.proxy]` reverse proxy.
+//! Requests without valid credentials get the anonymous role for
+//! `[auth] protect`. The role guards ([`Resolver`], [`HistoryViewer`],
+//! [`Admin`]) fail with 401 (no credentials) or 403 (too little access).
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use base64::Engine;
+use rocket::http::{Header, Status};
+use rocket::request::{self, FromRequest, Request};
+use rocket::response::{self, Responder, Response};
+
+use crate::ConfigReloader;
+use crate::config::{BunnylolConfig, Role, secrets_match};
+
+/// Basic auth headers that passed an Argon2 check, with the hash they were
+/// checked against. Browsers resend the header on every request, and a
+/// redirect shouldn't cost a password hash each time.
+static VERIFIED_BASIC_AUTH: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+const VERIFIED_BASIC_AUTH_LIMIT: usize = 1024;
+
+/// Who sent a request, and what they may do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    /// The token name, user name or proxy user; `None` when signed out.
+    pub user: Option<String>,
+    /// `None` when the request may do nothing but `/health`.
+    pub role: Option<Role>,
+}
+
+impl Identity {
+    pub fn allows(&self, role: Role) -> bool {
+        self.role.is_some_and(|own| own >= role)
+    }
+
+    /// Work out the identity for `req` under `config`. Cached per request.
+    pub fn of<'r>(req: &'r Request<'_>) -> &'r Identity {
+        req.local_cache(|| match req.rocket().state::<ConfigReloader>() {
+            Some(config) => Self::from_headers(req, &config.current()),
+            None => Identity {
+                user: None,
+                role: None,
+            },
+        })
+    }
+
+    fn from_headers(req: &Request<'_>, config: &BunnylolConfig) -> Identity {
+        let auth = &config.auth;
+        let anonymous = auth.protect.anonymous_role();
+        let signed_in = |user: &str, role: Role| Identity {
+            user: Some(user.to_string()),
+            role: Some(anonymous.map_or(role, |anonymous| anonymous.max(role))),
+        };
+
+        if let Some(authorization) = req.headers().get_one("Authorization") {
+            if let Some(token) = authorization.strip_prefix("Bearer ") {
+                let token = token.trim();
+                if let Some((name, role)) = auth.token_identity(token) {
+                    return signed_in(name, role);
+                }
+                if let Some(admin_token) = &config.server.admin_token
+                    && secrets_match(token, admin_token)
+                {
+                    return signed_in("admin_token", Role::Admin);
+                }
+            } else if let Some((user, role)) = check_basic_auth(authorization, config) {
+                return signed_in(&user, role);
+            }
+        }
+
+        if let Some(proxy) = &auth.proxy
+            && let Some(user) = req.headers().get_one(&proxy.header)
+            && let Some(peer) = super::forwarded::peer(req)
+            && let Some(role) = auth.proxy_role(peer, user.trim(), &config.server.trusted_proxies)
+        {
+            return signed_in(user.trim(), role);
+        }
+
+        Identity {
+            user: None,
+            role: anonymous,
+        }
+    }
+}
+
+fn check_basic_auth(authorization: &str, config: &BunnylolConfig) -> Option<(String, Role)> {
+    let encoded = authorization.strip_prefix("Basic ")?.trim();
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())?;
+    let (user, password) = decoded.split_once(':')?;
+    let credential = config.auth.users.get(user)?;
+
+    let cache = VERIFIED_BASIC_AUTH.get_or_init(|| Mutex::new(HashMap::new()));
+    if cache
+        .lock()
+        .expect("basic auth cache lock poisoned")
+        .get(authorization)
+        .is_some_and(|hash| *hash == credential.password_hash)
+    {
+        return Some((user.to_string(), credential.role));
+    }
+
+    let role = config.auth.check_password(user, password)?;
+    let mut cache = cache.lock().expect("basic auth cache lock poisoned");
+    if cache.len() >= VERIFIED_BASIC_AUTH_LIMIT {
+        cache.clear();
+    }
+    cache.insert(authorization.to_string(), credential.password_hash.clone());
+    Some((user.to_string(), role))
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Identity {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        request::Outcome::Success(Identity::of(req).clone())
+    }
+}
+
+fn require(req: &Request<'_>, role: Role) -> request::Outcome<Identity, ()> {
+    let identity = Identity::of(req);
+    if identity.allows(role) {
+        request::Outcome::Success(identity.clone())
+    } else if identity.user.is_some() {
+        request::Outcome::Error((Status::Forbidden, ()))
+    } else {
+        request::Outcome::Error((Status::Unauthorized, ()))
+    }
+}
+
+/// A request that may resolve queries.
+pub struct Resolver(pub Identity);
+
+/// A request that may read history.
+pub struct HistoryViewer(pub Identity);
+
+/// A request that may edit the config.
+pub struct Admin(pub Identity);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Resolver {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        require(req, Role::Resolve).map(Resolver)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for HistoryViewer {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        require(req, Role::History).map(HistoryViewer)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Admin {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        require(req, Role::Admin).map(Admin)
+    }
+}
+
+/// A 401 that asks the browser for basic auth credentials.
+pub(super) struct Challenge;
+
+impl<'r> Responder<'r, 'static> for Challenge {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        Response::build_from("Sign in to use this bunnylol server.\n".respond_to(req)?)
+            .status(Status::Unauthorized)
+            .header(Header::new(
+                "WWW-Authenticate",
+                "Basic realm=\"bunnylol\", charset=\"UTF-8\"",
+            ))
+            .ok()
+    }
+}
+
+#[rocket::catch(401)]
+pub(super) fn unauthorized() -> Challenge {
+    Challenge
+}
+
+#[rocket::catch(403)]
+pub(super) fn forbidden() -> &'static str {
+    "Your account can't do that on this bunnylol server.\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::local::blocking::Client;
+
+    use super::*;
+
+    #[rocket::get("/whoami")]
+    fn whoami(identity: Identity) -> String {
+        format!("{:?} {:?}", identity.user, identity.role)
+    }
+
+    #[rocket::get("/history")]
+    fn history(_viewer: HistoryViewer) -> &'static str {
+        "history"
+    }
+
+    fn client(config_toml: &str) -> Client {
+        let config: BunnylolConfig = toml::from_str(config_toml).unwrap();
+        config.validate_auth().unwrap();
+        let rocket = rocket::build()
+            .manage(ConfigReloader::new_without_file(config))
+            .mount("/", rocket::routes![whoami, history])
+            .register("/", rocket::catchers![unauthorized, forbidden]);
+        Client::untracked(rocket).unwrap()
+    }
+
+    fn basic(user: &str, password: &str) -> Header<'static> {
+        let encoded =
+            base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, password));
+        Header::new("Authorization", format!("Basic {}", encoded))
+    }
+
+    #[test]
+    fn test_identity_from_credentials() {
+        let hash = crate::config::hash_password("hunter2").unwrap();
+        let client = client(&format!(
+            r#"
+            [server]
+            admin_token = "legacy-admin-token"
+
+            [auth.tokens]
+            bot = {{ token = "0123456789abcdef", role = "history" }}
+
+            [auth.users]
+            alice = {{ password_hash = "{}", role = "admin" }}
+            "#,
+            hash
+        ));
+        let whoami = |header: Option<Header<'static>>| {
+            let mut request = client.get("/whoami");
+            if let Some(header) = header {
+                request = request.header(header);
+            }
+            request.dispatch().into_string().unwrap()
+        };
+
+        assert_eq!(whoami(None), "None Some(Resolve)");
+        assert_eq!(
+            whoami(Some(Header::new(
+                "Authorization",
+                "Bearer 0123456789abcdef"
+            ))),
+            "Some(\"bot\") Some(History)"
+        );
+        assert_eq!(
+            whoami(Some(Header::new(
+                "Authorization",
+                "Bearer legacy-admin-token"
+            ))),
+            "Some(\"admin_token\") Some(Admin)"
+        );
+        assert_eq!(
+            whoami(Some(basic("alice", "hunter2"))),
+            "Some(\"alice\") Some(Admin)"
+        );
+        // Cached after the first check
+        assert_eq!(
+            whoami(Some(basic("alice", "hunter2"))),
+            "Some(\"alice\") Some(Admin)"
+        );
+        assert_eq!(whoami(Some(basic("alice", "wrong"))), "None Some(Resolve)");
+    }
+
+    #[test]
+    fn test_role_guards_challenge_or_forbid() {
+        let client = client(
+            r#"
+            [auth]
+            protect = "all"
+
+            [auth.tokens]
+            reader = { token = "0123456789abcdef", role = "resolve" }
+            "#,
+        );
+
+        let response = client.get("/history").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        assert!(
+            response
+                .headers()
+                .get_one("WWW-Authenticate")
+                .is_some_and(|value| value.starts_with("Basic"))
+        );
+        assert_eq!(
+            client.get("/whoami").dispatch().into_string().unwrap(),
+            "None None"
+        );
+
+        let response = client
+            .get("/history")
+            .header(Header::new("Authorization", "Bearer 0123456789abcdef"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn test_proxy_identity_needs_trusted_peer() {
+        let client = client(
+            r#"
+            [auth.proxy]
+            header = "X-Forwarded-User"
+            trusted = ["10.0.0.0/8"]
+            roles = { alice = "history" }
+            "#,
+        );
+        let whoami = |peer: &str| {
+            client
+                .get("/whoami")
+                .remote(format!("{}:1234", peer).parse().unwrap())
+                .header(Header::new("X-Forwarded-User", "alice"))
+                .dispatch()
+                .into_string()
+                .unwrap()
+        };
+        assert_eq!(whoami("10.1.2.3"), "Some(\"alice\") Some(History)");
+        assert_eq!(whoami("192.168.1.2"), "None Some(Resolve)");
+    }
+}
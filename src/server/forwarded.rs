@@ -0,0 +1,195 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! The real client address behind `[server] trusted_proxies`.
+//!
+//! A request from a trusted proxy is attributed to the address it forwarded
+//! for: the `for=` parameters of `Forwarded`, else `X-Forwarded-For`, else
+//! `X-Real-IP`. Each proxy appends the address it saw, so the list is read
+//! from the right and the first untrusted address is the client. Anything to
+//! its left came from the client and could be made up.
+//!
+//! Clients of a Unix domain socket have no address; they are trusted only
+//! when `trusted_proxies` lists "unix".
+
+use std::net::{IpAddr, SocketAddr};
+
+use rocket::Request;
+use rocket::http::HeaderMap;
+
+use crate::config::{IpRange, Peer};
+
+/// Set on requests that arrived on the Unix domain socket (see
+/// [`super::listener`]), which have no remote address.
+pub(super) struct UnixPeer(pub bool);
+
+/// Who sent `req`, if known.
+pub(super) fn peer(req: &Request<'_>) -> Option<Peer> {
+    match req.remote() {
+        Some(remote) => Some(Peer::Ip(remote.ip())),
+        None => req.local_cache(|| UnixPeer(false)).0.then_some(Peer::Unix),
+    }
+}
+
+/// The address `peer` forwarded for, if it is a trusted proxy; else `peer`.
+pub(super) fn client_addr(peer: Peer, headers: &HeaderMap<'_>, trusted: &[IpRange]) -> Peer {
+    let is_trusted = |peer: Peer| trusted.iter().any(|range| range.contains_peer(peer));
+    if !is_trusted(peer) {
+        return peer;
+    }
+
+    let hops: Vec<&str> = if headers.contains("Forwarded") {
+        headers
+            .get("Forwarded")
+            .flat_map(|value| value.split(','))
+            .filter_map(forwarded_for)
+            .collect()
+    } else if headers.contains("X-Forwarded-For") {
+        headers
+            .get("X-Forwarded-For")
+            .flat_map(|value| value.split(','))
+            .collect()
+    } else {
+        headers.get_one("X-Real-IP").into_iter().collect()
+    };
+
+    let mut client = peer;
+    for hop in hops.iter().rev() {
+        // "unknown" or an obfuscated name: the proxy didn't say who it was
+        let Some(addr) = parse_node(hop) else {
+            break;
+        };
+        client = Peer::Ip(addr);
+        if !is_trusted(client) {
+            break;
+        }
+    }
+    client
+}
+
+/// The `for=` value of one `Forwarded` element, e.g. `for=192.0.2.60;proto=https`.
+fn forwarded_for(element: &str) -> Option<&str> {
+    element.split(';').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        key.trim().eq_ignore_ascii_case("for").then_some(value)
+    })
+}
+
+/// An address as proxies write it: `192.0.2.1`, `192.0.2.1:4711`, `2001:db8::1`
+/// or `"[2001:db8::1]:4711"`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Ok(addr) = node.parse::<IpAddr>() {
+        return Some(addr);
+    }
+    if let Ok(addr) = node.parse::<SocketAddr>() {
+        return Some(addr.ip());
+    }
+    node.strip_prefix('[')?.strip_suffix(']')?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::http::Header;
+
+    use super::*;
+
+    fn client(peer: &str, headers: &[(&'static str, &'static str)], trusted: &[&str]) -> String {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.add(Header::new(*name, *value));
+        }
+        let trusted: Vec<IpRange> = trusted.iter().map(|range| range.parse().unwrap()).collect();
+        let peer = match peer {
+            "unix" => Peer::Unix,
+            ip => Peer::Ip(ip.parse().unwrap()),
+        };
+        client_addr(peer, &map, &trusted).to_string()
+    }
+
+    #[test]
+    fn test_headers_only_count_from_trusted_proxies() {
+        let xff = [("X-Forwarded-For", "203.0.113.7")];
+        assert_eq!(client("10.0.0.2", &xff, &[]), "10.0.0.2");
+        assert_eq!(client("192.168.1.5", &xff, &["10.0.0.0/8"]), "192.168.1.5");
+        assert_eq!(client("10.0.0.2", &xff, &["10.0.0.0/8"]), "203.0.113.7");
+        assert_eq!(client("10.0.0.2", &[], &["10.0.0.0/8"]), "10.0.0.2");
+
+        assert_eq!(client("unix", &xff, &["127.0.0.1"]), "unix");
+        assert_eq!(client("unix", &xff, &["unix"]), "203.0.113.7");
+        assert_eq!(client("unix", &[], &["unix"]), "unix");
+    }
+
+    #[test]
+    fn test_rightmost_untrusted_hop_is_the_client() {
+        let trusted = ["10.0.0.0/8", "::1"];
+        // The client made up the first entry
+        assert_eq!(
+            client(
+                "10.0.0.2",
+                &[("X-Forwarded-For", "1.2.3.4, 203.0.113.7, 10.0.0.9")],
+                &trusted
+            ),
+            "203.0.113.7"
+        );
+        // Repeated headers are one list
+        assert_eq!(
+            client(
+                "::1",
+                &[
+                    ("X-Forwarded-For", "1.2.3.4"),
+                    ("X-Forwarded-For", "203.0.113.7:5123")
+                ],
+                &trusted
+            ),
+            "203.0.113.7"
+        );
+        assert_eq!(
+            client(
+                "10.0.0.2",
+                &[("X-Forwarded-For", "10.0.0.8, 10.0.0.9")],
+                &trusted
+            ),
+            "10.0.0.8"
+        );
+        assert_eq!(
+            client("10.0.0.2", &[("X-Forwarded-For", "unknown")], &trusted),
+            "10.0.0.2"
+        );
+        assert_eq!(
+            client("10.0.0.2", &[("X-Real-IP", "203.0.113.7")], &trusted),
+            "203.0.113.7"
+        );
+    }
+
+    #[test]
+    fn test_forwarded_header_wins() {
+        let trusted = ["10.0.0.0/8"];
+        assert_eq!(
+            client(
+                "10.0.0.2",
+                &[
+                    (
+                        "Forwarded",
+                        "for=1.2.3.4, For=\"[2001:db8:cafe::17]:4711\";proto=https"
+                    ),
+                    ("X-Forwarded-For", "198.51.100.1"),
+                ],
+                &trusted
+            ),
+            "2001:db8:cafe::17"
+        );
+        assert_eq!(
+            client(
+                "10.0.0.2",
+                &[("Forwarded", "by=10.0.0.2;for=192.0.2.60")],
+                &trusted
+            ),
+            "192.0.2.60"
+        );
+    }
+}
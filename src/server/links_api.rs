@@ -0,0 +1,229 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! `/api/links`: list, create, update and remove go-links (see
+//! [`crate::links`]).
+//!
+//! Anyone who may resolve queries can list links. Creating one needs a
+//! signed-in user, who becomes its owner; only the owner or an admin can
+//! change or remove it afterwards.
+//!
+//! Changes are written off the async workers. Hits counted while resolving
+//! are written every [`HIT_FLUSH_INTERVAL`] and at shutdown, and listings
+//! include the ones not yet written.
+
+use std::time::Duration;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Status;
+use rocket::serde::json::{self, Json};
+use rocket::{Orbit, Rocket, State};
+use serde::Deserialize;
+
+use super::admin_api::{AdminHeaders, ApiResponse};
+use super::auth::Resolver;
+use crate::links::{self, LinkError, Links, ShortLink};
+use crate::{ConfigReloader, LinkCache};
+
+/// How often hits counted in memory are written to the links file.
+const HIT_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Body of `PUT /api/links/<name>`, and of `POST` with a `name`.
+#[derive(Deserialize)]
+pub(super) struct LinkBody {
+    #[serde(default)]
+    name: Option<String>,
+    url: String,
+    #[serde(default)]
+    description: Option<String>,
+    /// e.g. `"30d"`; the link never expires without one.
+    #[serde(default)]
+    expires_in: Option<String>,
+}
+
+impl LinkBody {
+    fn expires_at(&self) -> Result<Option<u64>, ApiResponse> {
+        self.expires_in
+            .as_deref()
+            .map(|expires_in| links::parse_expiry(expires_in).map(|s| links::now() + s))
+            .transpose()
+            .map_err(|e| ApiResponse::error(Status::UnprocessableEntity, e))
+    }
+}
+
+impl From<LinkError> for ApiResponse {
+    fn from(error: LinkError) -> Self {
+        let status = match &error {
+            LinkError::Exists => Status::Conflict,
+            LinkError::NotFound => Status::NotFound,
+            LinkError::NotOwner(_) => Status::Forbidden,
+            LinkError::Invalid(_) => Status::UnprocessableEntity,
+            LinkError::Io(_) => Status::InternalServerError,
+        };
+        ApiResponse::error(status, error)
+    }
+}
+
+fn store(cache: &LinkCache) -> Result<Links, ApiResponse> {
+    cache.store().cloned().ok_or_else(|| {
+        ApiResponse::error(
+            Status::InternalServerError,
+            "the server has no data directory for links",
+        )
+    })
+}
+
+/// Run `change` on the store on a blocking thread, then have the cache
+/// re-read the file.
+async fn write<T: Send + 'static>(
+    cache: &LinkCache,
+    change: impl FnOnce(Links) -> Result<T, LinkError> + Send + 'static,
+) -> Result<T, ApiResponse> {
+    let store = store(cache)?;
+    let result = rocket::tokio::task::spawn_blocking(move || change(store))
+        .await
+        .map_err(|e| ApiResponse::error(Status::InternalServerError, e))?;
+    cache.invalidate();
+    Ok(result?)
+}
+
+fn link_response(
+    cache: &LinkCache,
+    status: Status,
+    name: &str,
+    mut link: ShortLink,
+) -> ApiResponse {
+    link.hits += cache.pending_hits(name);
+    ApiResponse::json(status, serde_json::json!({ "name": name, "link": link }))
+}
+
+/// Writes the hits counted in memory every [`HIT_FLUSH_INTERVAL`], and
+/// once more at shutdown.
+pub(super) struct FlushHits;
+
+#[rocket::async_trait]
+impl Fairing for FlushHits {
+    fn info(&self) -> Info {
+        Info {
+            name: "go-link hit counter",
+            kind: Kind::Liftoff | Kind::Shutdown,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let Some(cache) = rocket.state::<LinkCache>().cloned() else {
+            return;
+        };
+        let shutdown = rocket.shutdown();
+        rocket::tokio::spawn(async move {
+            loop {
+                rocket::tokio::select! {
+                    _ = shutdown.clone() => return,
+                    _ = rocket::tokio::time::sleep(HIT_FLUSH_INTERVAL) => flush(&cache).await,
+                }
+            }
+        });
+    }
+
+    async fn on_shutdown(&self, rocket: &Rocket<Orbit>) {
+        if let Some(cache) = rocket.state::<LinkCache>() {
+            flush(cache).await;
+        }
+    }
+}
+
+async fn flush(cache: &LinkCache) {
+    let cache = cache.clone();
+    match rocket::tokio::task::spawn_blocking(move || cache.flush_hits()).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => eprintln!("Warning: Failed to count go-link hits: {}", e),
+        Err(e) => eprintln!("Warning: Failed to count go-link hits: {}", e),
+    }
+}
+
+#[rocket::get("/api/links")]
+pub(super) fn list_links(cache: &State<LinkCache>, _resolver: Resolver) -> ApiResponse {
+    match store(cache) {
+        Ok(_) => ApiResponse::json(Status::Ok, serde_json::json!({ "links": cache.all() })),
+        Err(response) => response,
+    }
+}
+
+#[rocket::post("/api/links", data = "<body>")]
+pub(super) async fn create_link(
+    config: &State<ConfigReloader>,
+    cache: &State<LinkCache>,
+    headers: AdminHeaders,
+    body: Result<Json<LinkBody>, json::Error<'_>>,
+) -> ApiResponse {
+    let result = async {
+        let owner = headers.user()?;
+        let Json(body) = body.map_err(|e| ApiResponse::error(Status::BadRequest, e))?;
+        let name = body
+            .name
+            .clone()
+            .ok_or_else(|| ApiResponse::error(Status::BadRequest, "missing field `name`"))?;
+        let link = ShortLink {
+            expires_at: body.expires_at()?,
+            url: body.url,
+            description: body.description,
+            owner: owner.to_string(),
+            created_at: links::now(),
+            hits: 0,
+        };
+        let (config, added) = (config.current(), name.clone());
+        let link = write(cache, move |store| store.add(&config, &added, link)).await?;
+        Ok(link_response(cache, Status::Created, &name, link))
+    };
+    result.await.unwrap_or_else(|response| response)
+}
+
+#[rocket::put("/api/links/<name>", data = "<body>")]
+pub(super) async fn update_link(
+    name: &str,
+    config: &State<ConfigReloader>,
+    cache: &State<LinkCache>,
+    headers: AdminHeaders,
+    body: Result<Json<LinkBody>, json::Error<'_>>,
+) -> ApiResponse {
+    let result = async {
+        let owner = headers.user()?;
+        let Json(body) = body.map_err(|e| ApiResponse::error(Status::BadRequest, e))?;
+        let expires_at = body.expires_at()?;
+        let as_owner = (!headers.is_admin()).then(|| owner.to_string());
+        let (config, updated) = (config.current(), name.to_string());
+        let link = write(cache, move |store| {
+            store.update(&config, &updated, as_owner.as_deref(), |link| {
+                link.url = body.url;
+                link.description = body.description;
+                link.expires_at = expires_at;
+            })
+        })
+        .await?;
+        Ok(link_response(cache, Status::Ok, name, link))
+    };
+    result.await.unwrap_or_else(|response| response)
+}
+
+#[rocket::delete("/api/links/<name>")]
+pub(super) async fn delete_link(
+    name: &str,
+    cache: &State<LinkCache>,
+    headers: AdminHeaders,
+) -> ApiResponse {
+    let result = async {
+        let owner = headers.user()?;
+        let as_owner = (!headers.is_admin()).then(|| owner.to_string());
+        let removed = name.to_string();
+        let link = write(cache, move |store| {
+            store.remove(&removed, as_owner.as_deref())
+        })
+        .await?;
+        Ok(link_response(cache, Status::Ok, name, link))
+    };
+    result.await.unwrap_or_else(|response| response)
+}
@@ -0,0 +1,723 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Listeners Rocket can't open itself: a Unix domain socket
+//! (`address = "unix:/path"`) and a socket handed over by systemd socket
+//! activation (`LISTEN_FDS`).
+//!
+//! Rocket 0.5 only serves TCP listeners it binds itself. With either of
+//! these it's launched without a listener, and hyper serves the connections
+//! (HTTP/1.1, or HTTP/2 over TLS or with prior knowledge) and hands each
+//! request to it in-process, so the server listens nowhere else. HTTPS here
+//! uses [`super::tls::server_config`], which renews the certificate in place.
+//!
+//! Unlike Rocket's own server, request and response bodies are buffered in
+//! memory, so request bodies are limited to [`MAX_BODY`] and a large mirror
+//! file is read whole before it's sent. A client gets [`REQUEST_TIMEOUT`] to
+//! send a request's headers and again for its body, and a connection that
+//! reads and writes nothing for [`IDLE_TIMEOUT`] is closed. Shutdown follows
+//! Rocket's `shutdown` settings: its signals, `Shutdown` handle, and grace
+//! and mercy periods.
+
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use rocket::http::hyper::{self, body::HttpBody};
+use rocket::http::tls::rustls;
+use rocket::http::{Header, Method};
+use rocket::local::asynchronous::Client;
+use rocket::tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use rocket::tokio::sync::watch;
+use rocket::tokio::task::JoinSet;
+use rocket::{Build, Rocket, tokio};
+use tokio_rustls::TlsAcceptor;
+
+use super::forwarded::UnixPeer;
+use crate::config::ServerConfig;
+
+/// The largest request body read, the same as Rocket's JSON limit.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// How long a client gets to send a request's headers, and then its body.
+const REQUEST_TIMEOUT: Duration = if cfg!(test) {
+    Duration::from_millis(300)
+} else {
+    Duration::from_secs(30)
+};
+
+/// How long a connection may go without reading or writing anything.
+const IDLE_TIMEOUT: Duration = if cfg!(test) {
+    Duration::from_millis(500)
+} else {
+    Duration::from_secs(60)
+};
+
+/// A listening socket served with [`serve`].
+pub(super) enum Listener {
+    Tcp(tokio::net::TcpListener),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener),
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send + 'static> Stream for S {}
+
+impl Listener {
+    /// The listener to serve on instead of `server.address`, if any: a
+    /// socket from systemd, else a Unix domain socket.
+    pub(super) fn open(server: &ServerConfig) -> io::Result<Option<(Self, String)>> {
+        if let Some(listener) = from_systemd()? {
+            return Ok(Some(listener));
+        }
+        match server.unix_socket() {
+            Some(path) => bind_unix(path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// The TCP port being listened on.
+    pub(super) fn port(&self) -> Option<u16> {
+        match self {
+            Listener::Tcp(listener) => listener.local_addr().ok().map(|addr| addr.port()),
+            #[cfg(unix)]
+            Listener::Unix(_) => None,
+        }
+    }
+
+    /// The next connection, and the client's address unless it came over a
+    /// Unix socket.
+    async fn accept(&self) -> io::Result<(Box<dyn Stream>, Option<SocketAddr>)> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, peer) = listener.accept().await?;
+                Ok((Box::new(stream), Some(peer)))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener) => {
+                let (stream, _) = listener.accept().await?;
+                Ok((Box::new(stream), None))
+            }
+        }
+    }
+}
+
+/// Serve `listener` with `rocket`, over TLS if `tls` is set, until Rocket is
+/// shut down.
+pub(super) async fn serve(
+    listener: &Listener,
+    rocket: Rocket<Build>,
+    tls: Option<Arc<rustls::ServerConfig>>,
+) -> Result<(), rocket::Error> {
+    let client = Arc::new(Client::untracked(rocket).await?);
+    let config = client.rocket().config().shutdown.clone();
+    let shutdown = client.rocket().shutdown();
+    let tls = tls.map(TlsAcceptor::from);
+    let (stop, stopping) = watch::channel(false);
+    let mut connections = JoinSet::new();
+    let signal = shutdown_signal(&config);
+    tokio::pin!(signal);
+    loop {
+        tokio::select! {
+            _ = &mut signal => {
+                shutdown.clone().notify();
+                break;
+            }
+            _ = shutdown.clone() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => {
+                    let connection = serve_connection(
+                        stream,
+                        peer,
+                        tls.clone(),
+                        client.clone(),
+                        stopping.clone(),
+                    );
+                    connections.spawn(connection);
+                }
+                Err(e) => {
+                    eprintln!("Warning: Failed to accept a connection: {}", e);
+                    // e.g. out of file descriptors; don't spin
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            },
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+
+    // Open connections finish their current requests, then get cut off
+    let _ = stop.send(true);
+    let drained = async { while connections.join_next().await.is_some() {} };
+    let patience = Duration::from_secs(u64::from(config.grace) + u64::from(config.mercy));
+    let _ = tokio::time::timeout(patience, drained).await;
+    connections.abort_all();
+    while connections.join_next().await.is_some() {}
+    // Run shutdown fairings
+    if let Some(client) = Arc::into_inner(client) {
+        client.terminate().await;
+    }
+    Ok(())
+}
+
+/// Resolves on the first of Rocket's shutdown signals (`ctrlc` and, on Unix,
+/// `signals`); never if there are none.
+async fn shutdown_signal(config: &rocket::config::Shutdown) {
+    let (sender, mut received) = tokio::sync::mpsc::channel::<()>(1);
+    if config.ctrlc {
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                let _ = sender.send(()).await;
+            }
+        });
+    }
+    #[cfg(unix)]
+    for sig in &config.signals {
+        use rocket::config::Sig;
+        use tokio::signal::unix::{SignalKind, signal};
+
+        let kind = match sig {
+            Sig::Alrm => SignalKind::alarm(),
+            Sig::Chld => SignalKind::child(),
+            Sig::Hup => SignalKind::hangup(),
+            Sig::Int => SignalKind::interrupt(),
+            Sig::Io => SignalKind::io(),
+            Sig::Pipe => SignalKind::pipe(),
+            Sig::Quit => SignalKind::quit(),
+            Sig::Term => SignalKind::terminate(),
+            Sig::Usr1 => SignalKind::user_defined1(),
+            Sig::Usr2 => SignalKind::user_defined2(),
+        };
+        match signal(kind) {
+            Ok(mut signal) => {
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    if signal.recv().await.is_some() {
+                        let _ = sender.send(()).await;
+                    }
+                });
+            }
+            Err(e) => eprintln!("Warning: Failed to listen for {}: {}", sig, e),
+        }
+    }
+    drop(sender);
+    if received.recv().await.is_none() {
+        std::future::pending::<()>().await;
+    }
+}
+
+async fn serve_connection(
+    stream: Box<dyn Stream>,
+    peer: Option<SocketAddr>,
+    tls: Option<TlsAcceptor>,
+    client: Arc<Client>,
+    mut stopping: watch::Receiver<bool>,
+) {
+    let stream = Idle::new(stream);
+    let stream: Box<dyn Stream> = match tls {
+        Some(tls) => match tls.accept(stream).await {
+            Ok(stream) => Box::new(stream),
+            // A client that gave up or doesn't speak TLS
+            Err(_) => return,
+        },
+        None => Box::new(stream),
+    };
+    let service =
+        hyper::service::service_fn(move |request| dispatch(client.clone(), peer, request));
+    let connection = hyper::server::conn::Http::new()
+        .http1_header_read_timeout(REQUEST_TIMEOUT)
+        .serve_connection(stream, service);
+    tokio::pin!(connection);
+    tokio::select! {
+        _ = connection.as_mut() => return,
+        _ = stopping.changed() => connection.as_mut().graceful_shutdown(),
+    }
+    let _ = connection.await;
+}
+
+/// A connection that fails once it has read and written nothing for
+/// [`IDLE_TIMEOUT`].
+struct Idle<S> {
+    stream: S,
+    deadline: Pin<Box<tokio::time::Sleep>>,
+}
+
+impl<S> Idle<S> {
+    fn new(stream: S) -> Self {
+        Idle {
+            stream,
+            deadline: Box::pin(tokio::time::sleep(IDLE_TIMEOUT)),
+        }
+    }
+
+    fn track<T>(&mut self, cx: &mut Context<'_>, poll: Poll<io::Result<T>>) -> Poll<io::Result<T>> {
+        match poll {
+            Poll::Ready(result) => {
+                let deadline = tokio::time::Instant::now() + IDLE_TIMEOUT;
+                self.deadline.as_mut().reset(deadline);
+                Poll::Ready(result)
+            }
+            Poll::Pending => match self.deadline.as_mut().poll(cx) {
+                Poll::Ready(()) => Poll::Ready(Err(io::ErrorKind::TimedOut.into())),
+                Poll::Pending => Poll::Pending,
+            },
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Idle<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let poll = Pin::new(&mut self.stream).poll_read(cx, buf);
+        self.track(cx, poll)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Idle<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.stream).poll_write(cx, buf);
+        self.track(cx, poll)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let poll = Pin::new(&mut self.stream).poll_flush(cx);
+        self.track(cx, poll)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+/// Hand one request to Rocket.
+async fn dispatch(
+    client: Arc<Client>,
+    peer: Option<SocketAddr>,
+    request: hyper::Request<hyper::Body>,
+) -> Result<hyper::Response<hyper::Body>, BoxError> {
+    let (parts, body) = request.into_parts();
+    let Some(method) = Method::from_hyp(&parts.method) else {
+        return empty_response(501);
+    };
+    let body = match tokio::time::timeout(REQUEST_TIMEOUT, read_body(body)).await {
+        Ok(Some(body)) => body,
+        Ok(None) => return empty_response(413),
+        Err(_) => return empty_response(408),
+    };
+    let uri = parts.uri.path_and_query().map_or("/", |uri| uri.as_str());
+
+    let mut local = client.req(method, uri);
+    for (name, value) in &parts.headers {
+        if let Ok(value) = value.to_str() {
+            local.add_header(Header::new(name.as_str().to_string(), value.to_string()));
+        }
+    }
+    let local = match peer {
+        Some(peer) => local.remote(peer),
+        None => {
+            local.inner().local_cache(|| UnixPeer(true));
+            local
+        }
+    };
+    let response = local.body(body).dispatch().await;
+
+    let mut builder = hyper::Response::builder().status(response.status().code);
+    for header in response.headers().iter() {
+        builder = builder.header(header.name().as_str(), header.value());
+    }
+    let body = response.into_bytes().await.unwrap_or_default();
+    Ok(builder.body(hyper::Body::from(body))?)
+}
+
+fn empty_response(status: u16) -> Result<hyper::Response<hyper::Body>, BoxError> {
+    Ok(hyper::Response::builder()
+        .status(status)
+        .body(hyper::Body::empty())?)
+}
+
+/// The whole request body, or `None` if it's over [`MAX_BODY`].
+async fn read_body(mut body: hyper::Body) -> Option<Vec<u8>> {
+    // Refuse a declared Content-Length without waiting for the body
+    if body.size_hint().lower() > MAX_BODY as u64 {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.ok()?;
+        if bytes.len() + chunk.len() > MAX_BODY {
+            return None;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Some(bytes)
+}
+
+/// Bind `path`, replacing a socket file left behind by an earlier run. A
+/// socket another server still accepts connections on is left alone.
+#[cfg(unix)]
+fn bind_unix(path: &Path) -> io::Result<(Listener, String)> {
+    use std::os::unix::fs::FileTypeExt;
+
+    if let Ok(metadata) = std::fs::symlink_metadata(path)
+        && metadata.file_type().is_socket()
+    {
+        match std::os::unix::net::UnixStream::connect(path) {
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use by a running server", path.display()),
+                ));
+            }
+            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => std::fs::remove_file(path)?,
+            // Leave it for bind to report
+            Err(_) => {}
+        }
+    }
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = tokio::net::UnixListener::bind(path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Failed to bind {}: {}", path.display(), e),
+        )
+    })?;
+    Ok((Listener::Unix(listener), format!("unix:{}", path.display())))
+}
+
+#[cfg(not(unix))]
+fn bind_unix(_path: &Path) -> io::Result<(Listener, String)> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Unix domain sockets are only supported on Unix",
+    ))
+}
+
+/// The first socket systemd passed this process, per `sd_listen_fds(3)`.
+///
+/// `LISTEN_PID`, `LISTEN_FDS` and `LISTEN_FDNAMES` are left set: removing
+/// them isn't safe once the runtime's threads exist, and a child process
+/// ignores them since `LISTEN_PID` names this one. The descriptor is made
+/// close-on-exec so children don't inherit it.
+#[cfg(target_os = "linux")]
+fn from_systemd() -> io::Result<Option<(Listener, String)>> {
+    use std::os::fd::{FromRawFd, IntoRawFd};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// The first file descriptor systemd passes (`SD_LISTEN_FDS_START`)
+    const LISTEN_FDS_START: i32 = 3;
+    /// The descriptor has one owner, even if this is called again
+    static TAKEN: AtomicBool = AtomicBool::new(false);
+
+    let for_us = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        == Some(std::process::id());
+    let count = std::env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|count| count.parse::<i32>().ok())
+        .unwrap_or(0);
+    if !for_us || count < 1 || TAKEN.swap(true, Ordering::SeqCst) {
+        return Ok(None);
+    }
+    if count > 1 {
+        eprintln!(
+            "Warning: systemd passed {} sockets; using the first one",
+            count
+        );
+    }
+    // SAFETY: fcntl on a descriptor number only changes its flags
+    if unsafe { libc::fcntl(LISTEN_FDS_START, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: systemd hands this process ownership of the descriptor
+    let unix = unsafe { std::os::unix::net::UnixListener::from_raw_fd(LISTEN_FDS_START) };
+    if let Ok(addr) = unix.local_addr() {
+        unix.set_nonblocking(true)?;
+        let name = match addr.as_pathname() {
+            Some(path) => format!("unix:{} (from systemd)", path.display()),
+            None => "a Unix socket from systemd".to_string(),
+        };
+        return Ok(Some((
+            Listener::Unix(tokio::net::UnixListener::from_std(unix)?),
+            name,
+        )));
+    }
+    // Not a Unix socket, so a TCP one
+    // SAFETY: the same descriptor, released by `unix` above
+    let tcp = unsafe { std::net::TcpListener::from_raw_fd(unix.into_raw_fd()) };
+    tcp.set_nonblocking(true)?;
+    let name = format!("{} (from systemd)", tcp.local_addr()?);
+    Ok(Some((
+        Listener::Tcp(tokio::net::TcpListener::from_std(tcp)?),
+        name,
+    )))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn from_systemd() -> io::Result<Option<(Listener, String)>> {
+    Ok(None)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::config::Peer;
+    use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[rocket::post("/echo", data = "<body>")]
+    fn echo(req_peer: PeerName, body: String) -> String {
+        format!("{} {}", req_peer.0, body)
+    }
+
+    struct PeerName(String);
+
+    #[rocket::async_trait]
+    impl<'r> rocket::request::FromRequest<'r> for PeerName {
+        type Error = ();
+
+        async fn from_request(
+            req: &'r rocket::Request<'_>,
+        ) -> rocket::request::Outcome<Self, Self::Error> {
+            let peer = super::super::forwarded::peer(req);
+            rocket::request::Outcome::Success(PeerName(
+                peer.map_or("none".into(), |peer| peer.to_string()),
+            ))
+        }
+    }
+
+    async fn request(path: &Path, request: &[u8]) -> String {
+        let mut client = tokio::net::UnixStream::connect(path).await.unwrap();
+        client.write_all(request).await.unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    /// A test server on a fresh socket, with no signal handlers.
+    fn rocket() -> Rocket<Build> {
+        let mut config = rocket::Config::debug_default();
+        config.shutdown.ctrlc = false;
+        config.shutdown.signals.clear();
+        config.shutdown.grace = 0;
+        config.shutdown.mercy = 0;
+        rocket::custom(config).mount("/", rocket::routes![echo])
+    }
+
+    fn socket(dir: &tempfile::TempDir) -> (Listener, std::path::PathBuf) {
+        let path = dir.path().join("bunnylol.sock");
+        let (listener, _) = bind_unix(&path).unwrap();
+        (listener, path)
+    }
+
+    #[rocket::async_test]
+    async fn test_unix_socket_serves_rocket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bunnylol.sock");
+        // A stale socket file from an earlier run is replaced
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let (listener, name) = bind_unix(&path).unwrap();
+        assert_eq!(name, format!("unix:{}", path.display()));
+        tokio::spawn(async move { serve(&listener, rocket(), None).await });
+
+        // A socket that's being served is left alone
+        assert_eq!(
+            bind_unix(&path).err().map(|e| e.kind()),
+            Some(io::ErrorKind::AddrInUse)
+        );
+
+        let response = request(
+            &path,
+            b"POST /echo HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\n\
+              Connection: close\r\n\r\nhi",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(
+            response.ends_with(&format!("{} hi", Peer::Unix)),
+            "{response}"
+        );
+
+        let response = request(
+            &path,
+            format!(
+                "POST /echo HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n",
+                MAX_BODY + 1
+            )
+            .as_bytes(),
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 413"), "{response}");
+    }
+
+    #[rocket::async_test]
+    async fn test_slow_clients_are_cut_off() {
+        let dir = tempfile::tempdir().unwrap();
+        let (listener, path) = socket(&dir);
+        tokio::spawn(async move { serve(&listener, rocket(), None).await });
+        let patience = REQUEST_TIMEOUT.max(IDLE_TIMEOUT) * 4;
+
+        // A client that never sends anything
+        let mut idle = tokio::net::UnixStream::connect(&path).await.unwrap();
+        let mut response = Vec::new();
+        tokio::time::timeout(patience, idle.read_to_end(&mut response))
+            .await
+            .expect("idle connection stayed open")
+            .ok();
+        assert!(response.is_empty());
+
+        // Headers trickled in too slowly to ever trip the idle timeout
+        let mut trickle = tokio::net::UnixStream::connect(&path).await.unwrap();
+        trickle
+            .write_all(b"GET /echo HTTP/1.1\r\nHost: localhost\r\n")
+            .await
+            .unwrap();
+        let (mut reader, mut writer) = trickle.into_split();
+        tokio::spawn(async move {
+            while writer.write_all(b"X-Slow: 1\r\n").await.is_ok() {
+                tokio::time::sleep(IDLE_TIMEOUT / 5).await;
+            }
+        });
+        tokio::time::timeout(patience, reader.read_to_end(&mut Vec::new()))
+            .await
+            .expect("slow headers kept the connection open")
+            .ok();
+
+        // And a body trickled in the same way
+        let mut trickle = tokio::net::UnixStream::connect(&path).await.unwrap();
+        trickle
+            .write_all(b"POST /echo HTTP/1.1\r\nHost: localhost\r\nContent-Length: 1000\r\n\r\n")
+            .await
+            .unwrap();
+        let (mut reader, mut writer) = trickle.into_split();
+        tokio::spawn(async move {
+            while writer.write_all(b"x").await.is_ok() {
+                tokio::time::sleep(IDLE_TIMEOUT / 5).await;
+            }
+        });
+        let mut response = Vec::new();
+        tokio::time::timeout(patience, reader.read_to_end(&mut response))
+            .await
+            .expect("slow body kept the connection open")
+            .ok();
+        let response = String::from_utf8_lossy(&response);
+        assert!(response.starts_with("HTTP/1.1 408"), "{response}");
+    }
+
+    #[rocket::async_test]
+    async fn test_serve_stops_with_rocket_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let (listener, _path) = socket(&dir);
+        let (sender, shutdown) = tokio::sync::oneshot::channel();
+        let sender = std::sync::Mutex::new(Some(sender));
+        let rocket = rocket().attach(rocket::fairing::AdHoc::on_liftoff("shutdown", move |r| {
+            let _ = sender.lock().unwrap().take().unwrap().send(r.shutdown());
+            Box::pin(async {})
+        }));
+        let served = tokio::spawn(async move { serve(&listener, rocket, None).await });
+
+        shutdown.await.unwrap().notify();
+        tokio::time::timeout(Duration::from_secs(5), served)
+            .await
+            .expect("serve kept running after shutdown")
+            .unwrap()
+            .unwrap();
+    }
+
+    /// The certificate a TLS handshake over `path` is answered with.
+    async fn served_certificate(path: &Path, roots: &[rustls::Certificate]) -> rustls::Certificate {
+        let mut store = rustls::RootCertStore::empty();
+        for root in roots {
+            store.add(root).unwrap();
+        }
+        let config = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(store)
+            .with_no_client_auth();
+        let stream = tokio::net::UnixStream::connect(path).await.unwrap();
+        let mut stream = tokio_rustls::TlsConnector::from(Arc::new(config))
+            .connect("localhost".try_into().unwrap(), stream)
+            .await
+            .unwrap();
+        let certificate = stream.get_ref().1.peer_certificates().unwrap()[0].clone();
+
+        stream
+            .write_all(
+                b"POST /echo HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\n\
+                         Connection: close\r\n\r\nhi",
+            )
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.ok();
+        let response = String::from_utf8_lossy(&response);
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        certificate
+    }
+
+    #[rocket::async_test]
+    async fn test_tls_certificate_is_renewed_in_place() {
+        use rocket::http::tls::util::load_certs;
+
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tls/reload");
+        let fixture = |name: &str| fixtures.join(name);
+        let certificate = |name: &str| {
+            load_certs(&mut std::fs::read(fixture(name)).unwrap().as_slice()).unwrap()[0].clone()
+        };
+        let (first, second) = (
+            certificate("first-cert.pem"),
+            certificate("second-cert.pem"),
+        );
+        let roots = [first.clone(), second.clone()];
+
+        let dir = tempfile::tempdir().unwrap();
+        let tls = crate::config::TlsConfig {
+            cert: dir.path().join("cert.pem"),
+            key: dir.path().join("key.pem"),
+            redirect_http_port: None,
+        };
+        std::fs::copy(fixture("first-cert.pem"), &tls.cert).unwrap();
+        std::fs::copy(fixture("first-key.pem"), &tls.key).unwrap();
+        let (listener, path) = socket(&dir);
+        let config = super::super::tls::server_config(&tls).unwrap();
+        tokio::spawn(async move { serve(&listener, rocket(), Some(config)).await });
+        assert_eq!(served_certificate(&path, &roots).await, first);
+
+        std::fs::copy(fixture("second-cert.pem"), &tls.cert).unwrap();
+        std::fs::copy(fixture("second-key.pem"), &tls.key).unwrap();
+        let mut served = first.clone();
+        for _ in 0..50 {
+            served = served_certificate(&path, &roots).await;
+            if served != first {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(served, second);
+    }
+}
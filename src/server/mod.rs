@@ -9,6 +9,24 @@
 #[cfg(feature = "server")]
 pub mod web;
 
+#[cfg(feature = "server")]
+mod admin_api;
+
+#[cfg(feature = "server")]
+pub mod auth;
+
+#[cfg(feature = "server")]
+mod forwarded;
+
+#[cfg(feature = "server")]
+mod links_api;
+
+#[cfg(feature = "server")]
+mod listener;
+
+#[cfg(feature = "server")]
+mod tls;
+
 // Service management - only needed for CLI feature
 #[cfg(feature = "cli")]
 pub mod service;
@@ -22,13 +40,19 @@ use rocket::request::{self, FromRequest, Request};
 use rocket::response::Redirect;
 
 #[cfg(feature = "server")]
-use crate::{BunnylolCommandRegistry, BunnylolConfig, ConfigReloader, History, utils};
-
+use crate::config::PersonalBindings;
+#[cfg(feature = "server")]
+use crate::{
+    BunnylolCommandRegistry, BunnylolConfig, ConfigReloader, History, LinkCache, docs_mirror, utils,
+};
+#[cfg(feature = "server")]
+use auth::{HistoryViewer, Identity, Resolver};
 #[cfg(feature = "server")]
 mod server_impl {
     use super::*;
 
-    // Request guard to extract client IP address
+    // Request guard to extract client IP address, looking through
+    // `[server] trusted_proxies`
     pub(super) struct ClientIP(pub String);
 
     #[rocket::async_trait]
@@ -36,50 +60,352 @@ mod server_impl {
         type Error = ();
 
         async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
-            let ip = req
-                .client_ip()
-                .map(|addr| addr.to_string())
-                .unwrap_or_else(|| "unknown".to_string());
+            let ip = match (forwarded::peer(req), req.rocket().state::<ConfigReloader>()) {
+                (Some(peer), Some(config)) => forwarded::client_addr(
+                    peer,
+                    req.headers(),
+                    &config.current().server.trusted_proxies,
+                )
+                .to_string(),
+                (Some(peer), None) => peer.to_string(),
+                (None, _) => "unknown".to_string(),
+            };
             request::Outcome::Success(ClientIP(ip))
         }
     }
 
+    /// Responses from the `search` route: a redirect to the resolved URL,
+    /// the landing page, a plain-text resolution trace, or a refusal for a
+    /// URL `[url_policy]` doesn't allow.
+    #[derive(rocket::Responder)]
+    pub(super) enum SearchResponse {
+        Redirect(Box<Redirect>),
+        Html(rocket::response::content::RawHtml<String>),
+        Text(rocket::response::content::RawText<String>),
+        Blocked(rocket::response::status::Forbidden<rocket::response::content::RawText<String>>),
+    }
+
+    /// The config a request resolves against: the server's, with the
+    /// current go-links and the signed-in user's personal bindings merged in.
+    pub(super) fn config_for(
+        config: &ConfigReloader,
+        links: Option<&LinkCache>,
+        personal: Option<&PersonalBindings>,
+        identity: &Identity,
+    ) -> BunnylolConfig {
+        let mut config = config.current();
+        if let Some(links) = links {
+            config.links = links.active();
+        }
+        if let (Some(personal), Some(user)) = (personal, &identity.user) {
+            personal.merge_into(&mut config, user);
+        }
+        config
+    }
+
+    /// `explain=1` (or `true`/`yes`/`on`, or a bare `explain`) turns on the
+    /// trace view.
+    fn is_truthy(value: &str) -> bool {
+        matches!(
+            value.to_ascii_lowercase().as_str(),
+            "" | "1" | "true" | "yes" | "on"
+        )
+    }
+
+    /// Redirect to what `cmd_str` resolves to, or show its trace if
+    /// `explain` is on.
+    fn resolve_query(
+        cmd_str: &str,
+        explain: bool,
+        config: &BunnylolConfig,
+        links: &LinkCache,
+        client_ip: &ClientIP,
+        resolver: &Resolver,
+    ) -> SearchResponse {
+        let command = utils::get_command_from_query_string(cmd_str);
+        let trace = BunnylolCommandRegistry::explain_command_with_config(config, command, cmd_str);
+        if explain {
+            return SearchResponse::Text(rocket::response::content::RawText(trace.to_string()));
+        }
+        println!("bunnylol command: {}", cmd_str);
+
+        // Server-relative URLs (`/bindings`, docs mirrors) live under the base path
+        let redirect_url = if trace.url.starts_with('/') && !trace.url.starts_with("//") {
+            format!("{}{}", config.server.base_path(), trace.url)
+        } else {
+            trace.url.clone()
+        };
+        if let Err(reason) = config.url_policy.check(&redirect_url) {
+            eprintln!("blocked redirect to {:?}: {}", redirect_url, reason);
+            return SearchResponse::Blocked(rocket::response::status::Forbidden(
+                rocket::response::content::RawText(format!(
+                    "bunnylol won't redirect to this URL: {}\n",
+                    reason
+                )),
+            ));
+        }
+        println!("redirecting to: {}", redirect_url);
+
+        // Written out by `links_api::FlushHits`
+        if trace.matched_tier() == Some(crate::explain::ResolutionTier::Link) {
+            links.record_hit(command);
+        }
+
+        // Track command in history if enabled, under the signed-in
+        // user's name or else the client's address
+        let user = resolver.0.user.as_deref().unwrap_or(&client_ip.0);
+        if config.history.enabled
+            && let Some(history) = History::new(config)
+            && let Err(e) = history.add(cmd_str, user)
+        {
+            eprintln!("Warning: Failed to save command to history: {}", e);
+        }
+
+        SearchResponse::Redirect(Box::new(Redirect::to(redirect_url)))
+    }
+
     // http://localhost:8000/?cmd=gh
-    #[rocket::get("/?<cmd>")]
+    // http://localhost:8000/?q=gh (same as cmd)
+    // http://localhost:8000/?cmd=gh&explain=1
+    #[rocket::get("/?<cmd>&<q>&<explain>")]
+    #[allow(clippy::too_many_arguments)] // query parameters and request guards
     pub(super) fn search(
         cmd: Option<&str>,
+        q: Option<&str>,
+        explain: Option<&str>,
         config: &State<ConfigReloader>,
+        links: &State<LinkCache>,
+        personal: &State<PersonalBindings>,
         client_ip: ClientIP,
-    ) -> Result<Redirect, rocket::response::content::RawHtml<String>> {
-        let config = config.current();
-
-        match cmd {
-            Some(cmd_str) => {
-                println!("bunnylol command: {}", cmd_str);
+        resolver: Resolver,
+    ) -> SearchResponse {
+        let config = config_for(config, Some(links), Some(personal), &resolver.0);
 
-                let command = utils::get_command_from_query_string(cmd_str);
-                let redirect_url = BunnylolCommandRegistry::process_command(command, cmd_str);
-                println!("redirecting to: {}", redirect_url);
-
-                // Track command in history if enabled
-                if config.history.enabled
-                    && let Some(history) = History::new(&config)
-                    && let Err(e) = history.add(cmd_str, &client_ip.0)
-                {
-                    eprintln!("Warning: Failed to save command to history: {}", e);
-                }
-
-                Ok(Redirect::to(redirect_url))
-            }
+        match cmd.or(q) {
+            Some(cmd_str) => resolve_query(
+                cmd_str,
+                explain.is_some_and(is_truthy),
+                &config,
+                links,
+                &client_ip,
+                &resolver,
+            ),
             None => {
                 // No cmd parameter, show landing page
-                Err(rocket::response::content::RawHtml(
+                SearchResponse::Html(rocket::response::content::RawHtml(
                     web::render_landing_page_html(&config),
                 ))
             }
         }
     }
 
+    // http://localhost:8000/bindings, where the `bindings` command redirects
+    #[rocket::get("/bindings")]
+    pub(super) fn bindings(
+        config: &State<ConfigReloader>,
+        links: &State<LinkCache>,
+        personal: &State<PersonalBindings>,
+        resolver: Resolver,
+    ) -> rocket::response::content::RawHtml<String> {
+        let config = config_for(config, Some(links), Some(personal), &resolver.0);
+        rocket::response::content::RawHtml(web::render_landing_page_html(&config))
+    }
+
+    /// Files browsers ask for on their own, which shouldn't be searched for
+    /// (or land in history).
+    const BROWSER_REQUESTS: &[&str] = &["favicon.ico", "robots.txt", "apple-touch-icon.png"];
+
+    /// The command a request path spells out: the first segment is the
+    /// command and the rest are its arguments, so `/gh/facebook/react` is
+    /// `gh facebook/react`. Unknown `/api/...` paths and browser requests
+    /// aren't commands.
+    fn command_from_path(segments: &[&str]) -> Option<String> {
+        match segments {
+            [] | ["api", ..] => None,
+            [file] if BROWSER_REQUESTS.contains(file) => None,
+            [command] => Some(command.to_string()),
+            [command, args @ ..] => Some(format!("{} {}", command, args.join("/"))),
+        }
+    }
+
+    // http://localhost:8000/gh/facebook/react (e.g. behind a `go` DNS name)
+    // http://localhost:8000/jira/PROJ-1?explain=1
+    // Ranked after every fixed route, so those keep their paths
+    #[rocket::get("/<path..>?<explain>", rank = 20)]
+    pub(super) fn go(
+        path: rocket::http::uri::Segments<'_, rocket::http::uri::fmt::Path>,
+        explain: Option<&str>,
+        config: &State<ConfigReloader>,
+        links: &State<LinkCache>,
+        personal: &State<PersonalBindings>,
+        client_ip: ClientIP,
+        resolver: Resolver,
+    ) -> Option<SearchResponse> {
+        let segments: Vec<&str> = path.collect();
+        let cmd_str = command_from_path(&segments)?;
+        let config = config_for(config, Some(links), Some(personal), &resolver.0);
+        Some(resolve_query(
+            &cmd_str,
+            explain.is_some_and(is_truthy),
+            &config,
+            links,
+            &client_ip,
+            &resolver,
+        ))
+    }
+
+    /// Files and directory listings from a `[commands.<name>] mirror`.
+    #[derive(rocket::Responder)]
+    pub(super) enum MirrorResponse {
+        File(rocket::fs::NamedFile),
+        Listing(rocket::response::content::RawHtml<String>),
+    }
+
+    fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    /// Characters escaped in one path segment of a listing link.
+    const PATH_SEGMENT: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS
+        .add(b' ')
+        .add(b'"')
+        .add(b'#')
+        .add(b'%')
+        .add(b'/')
+        .add(b'<')
+        .add(b'>')
+        .add(b'?')
+        .add(b'`')
+        .add(b'{')
+        .add(b'}');
+
+    fn encode_segment(segment: &str) -> String {
+        percent_encoding::utf8_percent_encode(segment, PATH_SEGMENT).to_string()
+    }
+
+    /// A plain index of `dir`, for mirrors that open directories (`godocs`).
+    /// Links are absolute (`base` is the directory's URL path, ending in
+    /// `/`), so they work whether or not the request had a trailing slash.
+    fn render_listing(dir: &std::path::Path, base: &str, title: &str) -> Option<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let name = entry.file_name().into_string().ok()?;
+                let slash = if entry.path().is_dir() { "/" } else { "" };
+                Some(format!("{}{}", name, slash))
+            })
+            .collect();
+        names.sort();
+        let items: String = names
+            .iter()
+            .map(|name| {
+                let (file, slash) = match name.strip_suffix('/') {
+                    Some(dir) => (dir, "/"),
+                    None => (name.as_str(), ""),
+                };
+                let href = format!("{}{}{}", base, encode_segment(file), slash);
+                format!(
+                    "<li><a href=\"{}\">{}</a></li>",
+                    escape_html(&href),
+                    escape_html(name)
+                )
+            })
+            .collect();
+        let title = escape_html(title);
+        Some(format!(
+            "<html><head><title>{}</title></head><body><h1>{}</h1><ul>{}</ul></body></html>",
+            title, title, items
+        ))
+    }
+
+    /// `path` under `root`, if it exists and stays inside `root` once
+    /// symlinks are resolved.
+    fn inside_mirror(root: &std::path::Path, path: &std::path::Path) -> Option<std::path::PathBuf> {
+        let root = root.canonicalize().ok()?;
+        let target = root.join(path).canonicalize().ok()?;
+        target.starts_with(&root).then_some(target)
+    }
+
+    // http://localhost:8000/mirror/<id>/std/index.html
+    // Rocket's PathBuf segments reject `..`, and symlinks out of the mirror
+    // are refused, so requests stay inside the mirror
+    #[rocket::get("/mirror/<id>/<path..>")]
+    pub(super) async fn mirror(
+        id: &str,
+        path: std::path::PathBuf,
+        config: &State<ConfigReloader>,
+        _resolver: Resolver,
+    ) -> Option<MirrorResponse> {
+        let config = config.current();
+        let root = docs_mirror::find_mirror(&config, id)?;
+        let target = inside_mirror(&root, &path)?;
+        if target.is_dir() {
+            if let Some(index) =
+                inside_mirror(&root, &path.join("index.html")).filter(|index| index.is_file())
+            {
+                return rocket::fs::NamedFile::open(index)
+                    .await
+                    .ok()
+                    .map(MirrorResponse::File);
+            }
+            let mut base = format!(
+                "{}/mirror/{}/",
+                config.server.base_path(),
+                encode_segment(id)
+            );
+            for segment in path.iter() {
+                base.push_str(&encode_segment(&segment.to_string_lossy()));
+                base.push('/');
+            }
+            let title = format!("/{}", path.display());
+            return render_listing(&target, &base, &title)
+                .map(|html| MirrorResponse::Listing(rocket::response::content::RawHtml(html)));
+        }
+        rocket::fs::NamedFile::open(target)
+            .await
+            .ok()
+            .map(MirrorResponse::File)
+    }
+
+    // http://localhost:8000/bindings/edit
+    // The page asks for credentials and talks to /api/user_bindings and
+    // /api/personal_bindings, so it's only served once there is a way to sign in
+    #[rocket::get("/bindings/edit")]
+    pub(super) fn binding_editor(
+        config: &State<ConfigReloader>,
+    ) -> Option<rocket::response::content::RawHtml<&'static str>> {
+        let config = config.current();
+        (config.server.admin_token.is_some() || config.auth.has_credentials())
+            .then_some(rocket::response::content::RawHtml(web::BINDING_EDITOR_HTML))
+    }
+
+    // http://localhost:8000/api/history?limit=50
+    // Most recent first; needs the history role
+    #[rocket::get("/api/history?<limit>")]
+    pub(super) fn history(
+        limit: Option<usize>,
+        config: &State<ConfigReloader>,
+        _viewer: HistoryViewer,
+    ) -> Result<
+        rocket::serde::json::Json<Vec<crate::HistoryEntry>>,
+        rocket::response::status::Custom<String>,
+    > {
+        let config = config.current();
+        let Some(history) = History::new(&config).filter(|_| config.history.enabled) else {
+            return Ok(rocket::serde::json::Json(Vec::new()));
+        };
+        history
+            .get_recent(limit.unwrap_or(100))
+            .map(rocket::serde::json::Json)
+            .map_err(|e| {
+                rocket::response::status::Custom(rocket::http::Status::InternalServerError, e)
+            })
+    }
+
     // Health check endpoint for Docker healthcheck (no verbose logging)
     #[rocket::get("/health")]
     pub(super) fn health() -> &'static str {
@@ -89,12 +415,17 @@ mod server_impl {
     // Catch 404 errors and show landing page
     #[rocket::catch(404)]
     pub(super) fn not_found(req: &rocket::Request) -> rocket::response::content::RawHtml<String> {
-        // Get config from request state
-        if let Some(config) = req.rocket().state::<ConfigReloader>() {
-            let config = config.current();
+        // Get config from request state; the landing page lists the bindings,
+        // so it needs the same role as resolving them
+        let identity = Identity::of(req);
+        if let Some(config) = req.rocket().state::<ConfigReloader>()
+            && identity.allows(crate::config::Role::Resolve)
+        {
+            let rocket = req.rocket();
+            let config = config_for(config, rocket.state(), rocket.state(), identity);
             rocket::response::content::RawHtml(web::render_landing_page_html(&config))
         } else {
-            // Fallback if config is not available (shouldn't happen)
+            // Signed out on a private server (or config not available)
             rocket::response::content::RawHtml(
                 "<html><body><h1>404 Not Found</h1></body></html>".to_string(),
             )
@@ -107,27 +438,170 @@ use server_impl::*;
 
 /// Launch the Bunnylol web server with the given configuration
 #[cfg(feature = "server")]
-pub async fn launch(config: BunnylolConfig) -> Result<(), Box<rocket::Error>> {
+pub async fn launch(mut config: BunnylolConfig) -> Result<(), Box<dyn std::error::Error>> {
     println!(
         "Bunnylol server starting with default search: {}",
-        config.default_search
-    );
-    println!(
-        "Server listening on {}:{}",
-        config.server.address, config.server.port
+        crate::commands::providers::preferred(
+            crate::commands::ProviderCategory::Search,
+            Some(&config)
+        )
+        .name
     );
+    let scheme = if config.server.tls.is_some() {
+        "https"
+    } else {
+        "http"
+    };
 
-    let figment = rocket::Config::figment()
-        .merge(("address", config.server.address.clone()))
+    // Rocket can't open a Unix socket or take one from systemd, so those are
+    // served in-process
+    let listener = match listener::Listener::open(&config.server)? {
+        Some((listener, name)) => {
+            println!("Server listening on {} ({})", name, scheme);
+            Some(listener)
+        }
+        None => {
+            println!(
+                "Server listening on {}://{}:{}",
+                scheme, config.server.address, config.server.port
+            );
+            None
+        }
+    };
+    let loopback = config
+        .server
+        .address
+        .parse::<std::net::IpAddr>()
+        .is_ok_and(|ip| ip.is_loopback());
+    if !loopback && !config.auth.has_credentials() && config.server.admin_token.is_none() {
+        println!(
+            "Note: no [auth] credentials are configured, so anyone who can reach this address \
+             can use the server. See the [auth] section of config.toml."
+        );
+    }
+
+    // Docs mirror hits redirect to the `mirror` route rather than file:// URLs
+    docs_mirror::serve_mirrors();
+
+    let Some(tls_config) = config.server.tls.clone() else {
+        match &listener {
+            Some(listener) => listener::serve(listener, build(&config, None), None).await?,
+            None => {
+                build(&config, None).launch().await?;
+            }
+        }
+        return Ok(());
+    };
+    if let Some(http_port) = tls_config.redirect_http_port {
+        match listener
+            .as_ref()
+            .map_or(Some(config.server.port), |l| l.port())
+        {
+            Some(https_port) => {
+                let server = config.server.clone();
+                rocket::tokio::spawn(tls::redirect_http(server, http_port, https_port));
+            }
+            None => {
+                eprintln!("Warning: redirect_http_port needs HTTPS on a TCP port; not redirecting")
+            }
+        }
+    }
+
+    // The in-process listener swaps in a renewed certificate itself
+    if let Some(listener) = &listener {
+        let certificate = tls::server_config(&tls_config)?;
+        listener::serve(listener, build(&config, None), Some(certificate)).await?;
+        return Ok(());
+    }
+
+    // Rocket can't, so it's restarted whenever the certificate is renewed
+    let mut certificate = tls::load(&tls_config)?;
+    loop {
+        let watched = tls::modified(&tls_config);
+        let reload = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let rocket = build(&config, Some(certificate.clone()))
+            .attach(tls::restart_on_renewal(
+                tls_config.clone(),
+                watched,
+                reload.clone(),
+            ))
+            .launch()
+            .await?;
+        if !reload.load(std::sync::atomic::Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        // Keep config edits made while running, but not a new listener
+        if let Some(reloader) = rocket.state::<ConfigReloader>() {
+            let server = config.server.clone();
+            config = reloader.current();
+            config.server = server;
+        }
+        match tls::load(&tls_config) {
+            Ok(renewed) => certificate = renewed,
+            Err(e) => eprintln!("Warning: {}; keeping the current certificate", e),
+        }
+    }
+}
+
+/// The server, ready to launch, with `certificate` if Rocket serves HTTPS.
+#[cfg(feature = "server")]
+fn build(
+    config: &BunnylolConfig,
+    certificate: Option<rocket::config::TlsConfig>,
+) -> rocket::Rocket<rocket::Build> {
+    // Routes are mounted once, so a new base_path needs a restart
+    let mount_point = config.server.mount_point().to_string();
+    let mut figment = rocket::Config::figment()
         .merge(("port", config.server.port))
         .merge(("log_level", config.server.log_level.clone()))
+        // Anyone can send X-Real-IP; `ClientIP` only believes trusted proxies
+        .merge(("ip_header", false))
         .merge(("ident", format!("Bunnylol/{}", env!("CARGO_PKG_VERSION"))));
+    // A `unix:` address is served by `listener`, never by Rocket
+    if config.server.unix_socket().is_none() {
+        figment = figment.merge(("address", config.server.address.clone()));
+    }
+    if let Some(certificate) = certificate {
+        figment = figment.merge(("tls", certificate));
+    }
 
-    let _rocket = rocket::custom(figment)
-        .manage(ConfigReloader::new(config))
-        .mount("/", rocket::routes![search, health])
-        .register("/", rocket::catchers![not_found])
-        .launch()
-        .await?;
-    Ok(())
+    rocket::custom(figment)
+        .manage(ConfigReloader::new(config.clone()))
+        .manage(PersonalBindings::load())
+        .manage(LinkCache::new())
+        .attach(links_api::FlushHits)
+        .mount(
+            mount_point.as_str(),
+            rocket::routes![
+                search,
+                go,
+                bindings,
+                mirror,
+                binding_editor,
+                history,
+                health
+            ],
+        )
+        .mount(
+            mount_point.as_str(),
+            rocket::routes![
+                admin_api::list_user_bindings,
+                admin_api::create_user_binding,
+                admin_api::replace_user_binding,
+                admin_api::delete_user_binding,
+                admin_api::list_personal_bindings,
+                admin_api::create_personal_binding,
+                admin_api::replace_personal_binding,
+                admin_api::delete_personal_binding,
+                links_api::list_links,
+                links_api::create_link,
+                links_api::update_link,
+                links_api::delete_link
+            ],
+        )
+        .register(
+            mount_point.as_str(),
+            rocket::catchers![not_found, auth::unauthorized, auth::forbidden],
+        )
 }
@@ -22,6 +22,11 @@ pub const SERVICE_LABEL: &str = "bunnylol";
 /// Service name used in systemctl/journalctl commands
 pub const SERVICE_NAME: &str = "bunnylol";
 
+/// Socket unit written for socket activation; systemd pairs it with the
+/// service of the same name
+#[cfg(target_os = "linux")]
+const SOCKET_UNIT_PATH: &str = "/etc/systemd/system/bunnylol.socket";
+
 // ============================================================================
 // Error Types
 // ============================================================================
@@ -92,6 +97,10 @@ pub struct ServiceConfig {
     pub port: u16,
     pub address: String,
     pub log_level: String,
+    /// Serve HTTPS with this certificate; written to `[server.tls]`
+    pub tls: Option<crate::config::TlsConfig>,
+    /// Also install a `.socket` unit, so systemd owns the listening socket
+    pub socket_activation: bool,
 }
 
 impl Default for ServiceConfig {
@@ -100,6 +109,8 @@ impl Default for ServiceConfig {
             port: 8000,
             address: "127.0.0.1".to_string(), // Localhost only by default (secure)
             log_level: "normal".to_string(),
+            tls: None,
+            socket_activation: false,
         }
     }
 }
@@ -125,6 +136,39 @@ fn setup_manager() -> Result<(Box<dyn ServiceManager>, ServiceLabel), ServiceErr
     Ok((manager, label))
 }
 
+/// Run `systemctl` with `args`.
+#[cfg(target_os = "linux")]
+fn systemctl(args: &[&str]) -> Result<(), ServiceError> {
+    let status = Command::new("systemctl")
+        .args(args)
+        .status()
+        .map_err(|e| ServiceError::ServiceManagerError(e.to_string()))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(ServiceError::ServiceManagerError(format!(
+            "systemctl {} failed ({})",
+            args.join(" "),
+            status
+        )))
+    }
+}
+
+/// A socket unit listening where `server` would. systemd keeps the socket
+/// open while the service restarts and passes it to `bunnylol serve`
+/// (`LISTEN_FDS`), so connections wait instead of being refused.
+#[cfg(target_os = "linux")]
+fn socket_unit(server: &crate::config::ServerConfig) -> String {
+    let listen = match server.unix_socket() {
+        Some(path) => format!("ListenStream={}\nSocketMode=0660", path.display()),
+        None => format!("ListenStream={}:{}", server.address, server.port),
+    };
+    format!(
+        "[Unit]\nDescription=bunnylol listening socket\n\n[Socket]\n{}\n\n[Install]\nWantedBy=sockets.target\n",
+        listen
+    )
+}
+
 // ============================================================================
 // Service Lifecycle Functions
 // ============================================================================
@@ -143,6 +187,17 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
         SERVICE_NAME
     );
 
+    if let Some(tls) = &config.tls {
+        for path in [&tls.cert, &tls.key] {
+            if !path.is_file() {
+                return Err(ServiceError::ConfigError(format!(
+                    "TLS file not found: {}",
+                    path.display()
+                )));
+            }
+        }
+    }
+
     // Create or update config file at /etc/bunnylol/config.toml
     let system_config_path = PathBuf::from("/etc/bunnylol/config.toml");
 
@@ -152,7 +207,7 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
 
     use crate::config::BunnylolConfig;
 
-    if system_config_path.exists() {
+    let server = if system_config_path.exists() {
         println!("✓ Found existing config file: /etc/bunnylol/config.toml");
 
         // Load existing config
@@ -163,12 +218,17 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
         let current_address = existing_config.server.address.clone();
         println!("  Current address: {}", current_address);
         println!("  New address:     {}", config.address);
+        // Keep an existing [server.tls] unless new TLS settings were given
+        let tls_changed = config.tls.is_some() && existing_config.server.tls != config.tls;
 
-        if current_address == config.address {
+        if current_address == config.address && !tls_changed {
             println!("✓ Config already has correct address, no changes needed");
         } else {
-            // Update only the address field, preserve everything else
+            // Update only the address and TLS fields, preserve everything else
             existing_config.server.address = config.address.clone();
+            if tls_changed {
+                existing_config.server.tls = config.tls.clone();
+            }
 
             println!("✓ Updating address in config file (preserving other settings)...");
             if let Err(e) = existing_config.write_to_file(&system_config_path) {
@@ -178,6 +238,7 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
                 )));
             }
         }
+        existing_config.server
     } else {
         println!("✓ Creating system config file: /etc/bunnylol/config.toml");
 
@@ -186,6 +247,7 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
         default_config.server.port = config.port;
         default_config.server.address = config.address.clone();
         default_config.server.log_level = config.log_level.clone();
+        default_config.server.tls = config.tls.clone();
 
         // Write config file
         if let Err(e) = default_config.write_to_file(&system_config_path) {
@@ -194,7 +256,8 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
                 e
             )));
         }
-    }
+        default_config.server
+    };
 
     println!();
 
@@ -215,6 +278,16 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
         "    Log level: {} (can be changed in config file)",
         config.log_level
     );
+    if let Some(tls) = &config.tls {
+        println!("    TLS cert:  {}", tls.cert.display());
+        println!("    TLS key:   {}", tls.key.display());
+        if let Some(http_port) = tls.redirect_http_port {
+            println!("    HTTP port: {} (redirects to HTTPS)", http_port);
+        }
+    }
+    if config.socket_activation {
+        println!("  Socket unit: {}", SOCKET_UNIT_PATH);
+    }
     println!("  Run as:      root");
     println!("  Autostart:   enabled");
     println!();
@@ -248,6 +321,16 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
 
     println!("✓ Service file created and registered");
 
+    if config.socket_activation {
+        println!("Creating socket unit...");
+        // A running server would hold the address the socket needs
+        let _ = systemctl(&["stop", SERVICE_NAME]);
+        std::fs::write(SOCKET_UNIT_PATH, socket_unit(&server))?;
+        systemctl(&["daemon-reload"])?;
+        systemctl(&["enable", "--now", "bunnylol.socket"])?;
+        println!("✓ Socket unit created and listening");
+    }
+
     println!();
     println!("Starting service...");
 
@@ -260,14 +343,29 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
     println!();
     println!("🎉 Bunnylol server installed successfully!");
     println!();
-    println!(
-        "Server URL (from config): http://{}:{}",
-        config.address, config.port
-    );
-    println!(
-        "Add to browser search: http://{}:{}/?cmd=%s",
-        config.address, config.port
-    );
+    let scheme = if config.tls.is_some() {
+        "https"
+    } else {
+        "http"
+    };
+    if let Some(path) = server.unix_socket() {
+        println!("Server socket (from config): {}", path.display());
+        println!("Point a reverse proxy at the socket and add the proxy's URL to your browser.");
+    } else {
+        println!(
+            "Server URL (from config): {}://{}:{}",
+            scheme, config.address, config.port
+        );
+        println!(
+            "Add to browser search: {}://{}:{}/?cmd=%s",
+            scheme, config.address, config.port
+        );
+    }
+    if config.address == "0.0.0.0" {
+        println!();
+        println!("The server is reachable from the network. To require sign-in, add an");
+        println!("[auth] section to /etc/bunnylol/config.toml (protect = \"all\").");
+    }
     println!();
     println!("To change port/address, edit: /etc/bunnylol/config.toml");
     println!("Then restart the service: sudo bunnylol service restart");
@@ -296,6 +394,16 @@ pub fn uninstall_service() -> Result<(), ServiceError> {
 
     let (manager, label) = setup_manager()?;
 
+    if std::path::Path::new(SOCKET_UNIT_PATH).exists() {
+        println!("Removing socket unit...");
+        // Stop the socket first, so it can't start the service again
+        if let Err(e) = systemctl(&["disable", "--now", "bunnylol.socket"]) {
+            println!("⚠ Warning: Could not stop socket: {}", e);
+        }
+        std::fs::remove_file(SOCKET_UNIT_PATH)?;
+        println!("✓ Socket unit removed");
+    }
+
     println!("Stopping service...");
     let stop_ctx = ServiceStopCtx {
         label: label.clone(),
@@ -0,0 +1,254 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Built-in HTTPS (`[server.tls]`): loading the certificate, noticing when
+//! it's renewed, and the plain HTTP listener that redirects to HTTPS.
+//!
+//! On a TCP address Rocket serves HTTPS itself. It can't swap certificates
+//! on a running listener, so a renewal shuts it down and `launch` starts it
+//! again with the new files, dropping the connections open at the time. On
+//! a Unix or systemd socket [`super::listener`] serves HTTPS with a
+//! certificate resolver, and a renewal only affects new connections.
+
+use std::fs;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use rocket::fairing::AdHoc;
+use rocket::http::tls::rustls::{self, server::ClientHello, sign::CertifiedKey};
+use rocket::http::uri::Origin;
+use rocket::response::Redirect;
+
+use crate::config::{ServerConfig, TlsConfig};
+
+/// How often the certificate files are checked for changes.
+const CERT_POLL_INTERVAL: Duration = if cfg!(test) {
+    Duration::from_millis(100)
+} else {
+    Duration::from_secs(10)
+};
+
+/// When the certificate and key files were last modified.
+pub(super) type Modified = (Option<SystemTime>, Option<SystemTime>);
+
+pub(super) fn modified(tls: &TlsConfig) -> Modified {
+    let mtime = |path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    (mtime(&tls.cert), mtime(&tls.key))
+}
+
+/// Wait until the certificate files differ from `loaded`, and then stay
+/// unchanged for one more check, so a certificate and key written one after
+/// the other are picked up together.
+async fn renewal(tls: &TlsConfig, loaded: Modified) -> Modified {
+    let mut last = loaded;
+    loop {
+        rocket::tokio::time::sleep(CERT_POLL_INTERVAL).await;
+        let current = modified(tls);
+        if current != loaded && current == last {
+            return current;
+        }
+        last = current;
+    }
+}
+
+/// The certificate chain and key files, checked to be usable.
+struct Pem {
+    cert: Vec<u8>,
+    key: Vec<u8>,
+    chain: Vec<rustls::Certificate>,
+    private_key: rustls::PrivateKey,
+}
+
+fn read(tls: &TlsConfig) -> Result<Pem, String> {
+    use rocket::http::tls::util::{load_certs, load_private_key};
+
+    let cert = fs::read(&tls.cert)
+        .map_err(|e| format!("Failed to read TLS certificate {:?}: {}", tls.cert, e))?;
+    let key =
+        fs::read(&tls.key).map_err(|e| format!("Failed to read TLS key {:?}: {}", tls.key, e))?;
+    let chain = match load_certs(&mut cert.as_slice()) {
+        Ok(chain) if !chain.is_empty() => chain,
+        Ok(_) => return Err(format!("No certificates found in {:?}", tls.cert)),
+        Err(e) => return Err(format!("Invalid TLS certificate {:?}: {}", tls.cert, e)),
+    };
+    let private_key = load_private_key(&mut key.as_slice())
+        .map_err(|e| format!("Invalid TLS key {:?}: {}", tls.key, e))?;
+    Ok(Pem {
+        cert,
+        key,
+        chain,
+        private_key,
+    })
+}
+
+/// Read the certificate chain and key, and check that Rocket can use them.
+pub(super) fn load(tls: &TlsConfig) -> Result<rocket::config::TlsConfig, String> {
+    let pem = read(tls)?;
+    Ok(rocket::config::TlsConfig::from_bytes(&pem.cert, &pem.key))
+}
+
+/// Shut Rocket down once the certificate files are renewed, setting `reload`
+/// so `launch` starts it again.
+pub(super) fn restart_on_renewal(
+    tls: TlsConfig,
+    loaded: Modified,
+    reload: Arc<std::sync::atomic::AtomicBool>,
+) -> AdHoc {
+    AdHoc::on_liftoff("TLS certificate watcher", move |rocket| {
+        let shutdown = rocket.shutdown();
+        Box::pin(async move {
+            rocket::tokio::spawn(async move {
+                rocket::tokio::select! {
+                    _ = shutdown.clone() => {}
+                    _ = renewal(&tls, loaded) => {
+                        println!("TLS certificate changed, restarting the listener");
+                        reload.store(true, std::sync::atomic::Ordering::SeqCst);
+                        shutdown.notify();
+                    }
+                }
+            });
+        })
+    })
+}
+
+fn certified_key(tls: &TlsConfig) -> Result<CertifiedKey, String> {
+    let pem = read(tls)?;
+    let key = rustls::sign::any_supported_type(&pem.private_key)
+        .map_err(|e| format!("Unsupported TLS key {:?}: {}", tls.key, e))?;
+    Ok(CertifiedKey::new(pem.chain, key))
+}
+
+/// The certificate handed to new connections.
+struct Certificate(RwLock<Arc<CertifiedKey>>);
+
+impl rustls::server::ResolvesServerCert for Certificate {
+    fn resolve(&self, _: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.0.read().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+}
+
+/// TLS settings for [`super::listener`], with the certificate swapped
+/// whenever it's renewed. If the new files don't load, the current
+/// certificate is kept.
+pub(super) fn server_config(tls: &TlsConfig) -> Result<Arc<rustls::ServerConfig>, String> {
+    let loaded = modified(tls);
+    let certificate = Arc::new(Certificate(RwLock::new(Arc::new(certified_key(tls)?))));
+    let (tls, current) = (tls.clone(), certificate.clone());
+    rocket::tokio::spawn(async move {
+        let mut loaded = loaded;
+        loop {
+            loaded = renewal(&tls, loaded).await;
+            match certified_key(&tls) {
+                Ok(renewed) => {
+                    *current.0.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(renewed);
+                    println!("TLS certificate renewed");
+                }
+                Err(e) => eprintln!("Warning: {}; keeping the current certificate", e),
+            }
+        }
+    });
+    let mut config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_cert_resolver(certificate);
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+/// Where plain HTTP requests are sent: the host from `server_display_url`,
+/// else `address`, on the HTTPS port.
+struct HttpsOrigin(String);
+
+fn https_origin(server: &ServerConfig, https_port: u16) -> HttpsOrigin {
+    let display = server
+        .server_display_url
+        .as_ref()
+        .and_then(|_| url::Url::parse(&server.get_display_url()).ok());
+    let host = match display.as_ref().and_then(|url| url.host_str()) {
+        Some(host) => host.to_string(),
+        None => match server.address.parse::<std::net::IpAddr>() {
+            Ok(ip) if ip.is_unspecified() => "localhost".to_string(),
+            Ok(std::net::IpAddr::V6(ip)) => format!("[{}]", ip),
+            _ => server.address.clone(),
+        },
+    };
+    let port = display.and_then(|url| url.port()).unwrap_or(https_port);
+    match port {
+        443 => HttpsOrigin(format!("https://{}", host)),
+        port => HttpsOrigin(format!("https://{}:{}", host, port)),
+    }
+}
+
+/// Send a plain HTTP request to the same path over HTTPS, on the configured
+/// host rather than whatever `Host` the client sent.
+#[rocket::get("/<_..>")]
+fn to_https(uri: &Origin<'_>, origin: &rocket::State<HttpsOrigin>) -> Redirect {
+    Redirect::permanent(format!("{}{}", origin.0, uri))
+}
+
+/// Redirect every request on `redirect_http_port` to the same path over HTTPS
+/// on `https_port`, the port actually being served.
+pub(super) async fn redirect_http(server: ServerConfig, http_port: u16, https_port: u16) {
+    let figment = rocket::Config::figment()
+        .merge(("address", server.address.clone()))
+        .merge(("port", http_port))
+        .merge(("log_level", server.log_level.clone()))
+        .merge(("ident", format!("Bunnylol/{}", env!("CARGO_PKG_VERSION"))));
+    println!(
+        "Redirecting HTTP on {}:{} to HTTPS",
+        server.address, http_port
+    );
+    if let Err(e) = rocket::custom(figment)
+        .manage(https_origin(&server, https_port))
+        .mount("/", rocket::routes![to_https])
+        .launch()
+        .await
+    {
+        eprintln!("Warning: HTTP to HTTPS redirect listener failed: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_rejects_files_without_a_certificate() {
+        let dir = tempfile::tempdir().unwrap();
+        let tls = TlsConfig {
+            cert: dir.path().join("cert.pem"),
+            key: dir.path().join("key.pem"),
+            redirect_http_port: None,
+        };
+        assert!(load(&tls).err().unwrap().contains("Failed to read"));
+
+        fs::write(&tls.cert, "not a certificate").unwrap();
+        fs::write(&tls.key, "not a key").unwrap();
+        assert!(load(&tls).err().unwrap().contains("No certificates"));
+        assert_ne!(modified(&tls), (None, None));
+    }
+
+    #[test]
+    fn test_https_origin_uses_the_configured_host() {
+        let mut server = ServerConfig {
+            address: "0.0.0.0".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(https_origin(&server, 443).0, "https://localhost");
+        server.address = "::1".to_string();
+        assert_eq!(https_origin(&server, 8443).0, "https://[::1]:8443");
+        server.address = "127.0.0.1".to_string();
+        assert_eq!(https_origin(&server, 8443).0, "https://127.0.0.1:8443");
+        server.server_display_url = Some("bunny.example.com".to_string());
+        assert_eq!(https_origin(&server, 443).0, "https://bunny.example.com");
+        server.server_display_url = Some("https://bunny.example.com:9443/bunny".to_string());
+        assert_eq!(
+            https_origin(&server, 443).0,
+            "https://bunny.example.com:9443"
+        );
+    }
+}
@@ -17,6 +17,7 @@ static LANDING_PAGE_HTML_CACHE: OnceLock<RwLock<Option<LandingPageHtmlCache>>> =
 #[derive(Clone, PartialEq, Eq)]
 struct LandingPageCacheKey {
     display_url: String,
+    personal_bindings: Vec<BindingData>,
     user_bindings: Vec<BindingData>,
 }
 
@@ -28,9 +29,13 @@ struct LandingPageHtmlCache {
 /// Render the landing page HTML with the given config
 pub fn render_landing_page_html(config: &BunnylolConfig) -> String {
     let display_url = config.server.get_display_url();
-    let user_bindings: Vec<BindingData> = collect_user_bindings(config);
+    let (personal_bindings, user_bindings): (Vec<BindingData>, Vec<BindingData>) =
+        collect_user_bindings(config)
+            .into_iter()
+            .partition(|row| config.is_personal_binding(&row.command));
     let key = LandingPageCacheKey {
         display_url: display_url.clone(),
+        personal_bindings: personal_bindings.clone(),
         user_bindings: user_bindings.clone(),
     };
 
@@ -44,7 +49,7 @@ pub fn render_landing_page_html(config: &BunnylolConfig) -> String {
         }
     }
 
-    let html = render_landing_page_html_uncached(display_url, user_bindings);
+    let html = render_landing_page_html_uncached(display_url, personal_bindings, user_bindings);
     let mut cached = cache.write().expect("landing page cache lock poisoned");
     if let Some(cached) = cached.as_ref()
         && cached.key == key
@@ -61,12 +66,14 @@ pub fn render_landing_page_html(config: &BunnylolConfig) -> String {
 
 fn render_landing_page_html_uncached(
     display_url: String,
+    personal_bindings: Vec<BindingData>,
     user_bindings: Vec<BindingData>,
 ) -> String {
     let body_content = Owner::new().with(|| {
         view! {
             <LandingPage
                 server_display_url=display_url.clone()
+                personal_bindings=personal_bindings.clone()
                 user_bindings=user_bindings.clone()
             />
         }
@@ -131,6 +138,9 @@ pub struct BindingData {
     pub command: String,
     pub description: String,
     pub example: String,
+    /// Further examples shown under `example`, one per input form
+    #[serde(default)]
+    pub examples: Vec<String>,
 }
 
 impl From<BunnylolCommandInfo> for BindingData {
@@ -143,15 +153,16 @@ impl From<BunnylolCommandInfo> for BindingData {
                 .clone(),
             description: info.description,
             example: info.example,
+            examples: info.examples,
         }
     }
 }
 
-/// Collect user `[user_bindings]` entries into `BindingData` rows, sorted
-/// alphabetically. Silently-shadowed bindings (collide with a built-in and
-/// `override = false`) are filtered out — they won't fire at runtime, so
-/// showing them here would be misleading. Bindings with `override = true`
-/// are kept.
+/// Collect user `[user_bindings]` entries and named `[commands.<name>]`
+/// instances into `BindingData` rows, sorted alphabetically.
+/// Silently-shadowed bindings (collide with a built-in and `override =
+/// false`) are filtered out — they won't fire at runtime, so showing them
+/// here would be misleading. Bindings with `override = true` are kept.
 fn collect_user_bindings(config: &BunnylolConfig) -> Vec<BindingData> {
     let builtins = BunnylolCommandRegistry::builtin_binding_names();
     let mut rows: Vec<BindingData> = config
@@ -167,9 +178,23 @@ fn collect_user_bindings(config: &BunnylolConfig) -> Vec<BindingData> {
                 command: name.clone(),
                 description: binding.description().unwrap_or(default_desc).to_string(),
                 example: format!("{} — {}", binding.kind_label(), binding.display_target()),
+                examples: Vec::new(),
             }
         })
         .collect();
+    // Named [commands.<name>] instances shadowed by a built-in never fire,
+    // so they're filtered out like shadowed bindings.
+    rows.extend(
+        config
+            .command_instance_names()
+            .into_iter()
+            .filter(|name| !builtins.contains(name))
+            .filter_map(|name| {
+                let (kind, settings) = config.command_instance(name)?;
+                BunnylolCommandRegistry::command_instance_info(name, kind, settings)
+            })
+            .map(BindingData::from),
+    );
     rows.sort_by_key(|a| a.command.to_lowercase());
     rows
 }
@@ -217,17 +242,22 @@ fn BindingCard(binding: BindingData) -> impl IntoView {
                     style:margin-bottom="5px"
                     style:font-weight="600"
                 >
-                    "Example:"
-                </div>
-                <div
-                    style:font-family="'JetBrains Mono', monospace"
-                    style:color="var(--accent-purple)"
-                    style:font-weight="500"
-                    style:overflow-wrap="anywhere"
-                    style:word-break="break-word"
-                >
-                    {binding.example}
+                    {if binding.examples.is_empty() { "Example:" } else { "Examples:" }}
                 </div>
+                {std::iter::once(binding.example)
+                    .chain(binding.examples)
+                    .map(|example| view! {
+                        <div
+                            style:font-family="'JetBrains Mono', monospace"
+                            style:color="var(--accent-purple)"
+                            style:font-weight="500"
+                            style:overflow-wrap="anywhere"
+                            style:word-break="break-word"
+                        >
+                            {example}
+                        </div>
+                    })
+                    .collect_view()}
             </div>
         </div>
     }
@@ -236,6 +266,7 @@ fn BindingCard(binding: BindingData) -> impl IntoView {
 #[component]
 pub fn LandingPage(
     server_display_url: String,
+    #[prop(default = Vec::new())] personal_bindings: Vec<BindingData>,
     #[prop(default = Vec::new())] user_bindings: Vec<BindingData>,
 ) -> impl IntoView {
     let mut bindings: Vec<BindingData> = BunnylolCommandRegistry::get_all_commands()
@@ -443,44 +474,15 @@ pub fn LandingPage(
                 </div>
             </div>
 
-            // ---------------- User bindings section ----------------
-            // Rendered only when at least one [user_bindings] entry exists
-            // (after filtering out silently-shadowed names by
+            // ---------------- Personal and user bindings sections ----------------
+            // Each is rendered only when it has at least one entry (after
+            // filtering out silently-shadowed names by
             // `collect_user_bindings`). Bindings with `override = true` are
             // shown; bindings that collide with a built-in without override
-            // are hidden because they won't resolve at runtime.
-            {
-                let has_user_bindings = !user_bindings.is_empty();
-                view! {
-                    <Show when=move || has_user_bindings fallback=|| view! { <></> }>
-                        <div
-                            style:text-align="center"
-                            style:color="var(--text-medium)"
-                            style:margin-bottom="20px"
-                            style:margin-top="20px"
-                            style:font-size="1.1em"
-                            style:font-weight="600"
-                        >
-                            "User Bindings"
-                        </div>
-                        <div
-                            style:display="grid"
-                            style:grid-template-columns="repeat(auto-fill, minmax(350px, 1fr))"
-                            style:gap="20px"
-                            style:margin-bottom="40px"
-                        >
-                            <For
-                                each={
-                                    let ub = user_bindings.clone();
-                                    move || ub.clone()
-                                }
-                                key=|binding| binding.command.clone()
-                                children=|binding| view! { <BindingCard binding=binding /> }
-                            />
-                        </div>
-                    </Show>
-                }
-            }
+            // are hidden because they won't resolve at runtime. Personal
+            // bindings are the signed-in user's own (see `PersonalBindings`).
+            <UserBindingsSection title="Your Bindings" bindings=personal_bindings />
+            <UserBindingsSection title="User Bindings" bindings=user_bindings />
 
             <div
                 style:text-align="center"
@@ -508,6 +510,188 @@ pub fn LandingPage(
     }
 }
 
+#[component]
+fn UserBindingsSection(title: &'static str, bindings: Vec<BindingData>) -> impl IntoView {
+    let has_bindings = !bindings.is_empty();
+    view! {
+        <Show when=move || has_bindings fallback=|| view! { <></> }>
+            <div
+                style:text-align="center"
+                style:color="var(--text-medium)"
+                style:margin-bottom="20px"
+                style:margin-top="20px"
+                style:font-size="1.1em"
+                style:font-weight="600"
+            >
+                {title}
+            </div>
+            <div
+                style:display="grid"
+                style:grid-template-columns="repeat(auto-fill, minmax(350px, 1fr))"
+                style:gap="20px"
+                style:margin-bottom="40px"
+            >
+                <For
+                    each={
+                        let bindings = bindings.clone();
+                        move || bindings.clone()
+                    }
+                    key=|binding| binding.command.clone()
+                    children=|binding| view! { <BindingCard binding=binding /> }
+                />
+            </div>
+        </Show>
+    }
+}
+
+/// The `/bindings/edit` page: a client for `/api/user_bindings` and
+/// `/api/personal_bindings`. It keeps
+/// an admin bearer token, if one is entered, in session storage and sends the last `ETag` with every
+/// change, so an edit made elsewhere in the meantime is reported instead of
+/// overwritten.
+pub const BINDING_EDITOR_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>bunnylol - edit bindings</title>
+    <link rel="icon" href="data:image/svg+xml,<svg xmlns=%22http://www.w3.org/2000/svg%22 viewBox=%220 0 100 100%22><text y=%22.9em%22 font-size=%2290%22>🐰</text></svg>">
+    <style>
+        * { margin: 0; padding: 0; box-sizing: border-box; }
+        body {
+            font-family: 'JetBrains Mono', monospace;
+            background: linear-gradient(135deg, #87CEEB 0%, #6D28D9 100%);
+            background-attachment: fixed;
+            min-height: 100vh;
+            padding: 20px;
+        }
+        main {
+            max-width: 1000px;
+            margin: 0 auto;
+            background: white;
+            border-radius: 12px;
+            padding: 20px 30px 30px 30px;
+            box-shadow: 0 20px 60px rgba(0, 0, 0, 0.3);
+            color: #333;
+        }
+        h1 { text-align: center; font-size: 2em; margin-bottom: 20px; }
+        h1 a { color: inherit; text-decoration: none; }
+        fieldset { border: 1px solid #e0e0e0; border-radius: 8px; padding: 12px; margin-bottom: 20px; }
+        label { display: inline-block; margin: 4px 12px 4px 0; }
+        input, select, button { font: inherit; padding: 4px 8px; }
+        input[type=text], input[type=password] { min-width: 240px; }
+        table { width: 100%; border-collapse: collapse; }
+        th, td { text-align: left; padding: 6px; border-bottom: 1px solid #e0e0e0; word-break: break-all; }
+        #status { min-height: 1.5em; margin-bottom: 12px; }
+        .error { color: #b91c1c; }
+    </style>
+</head>
+<body>
+<main>
+    <h1><a href="../">bunnylol</a> bindings</h1>
+    <fieldset>
+        <label>Bindings <select id="scope"><option value="../api/user_bindings">Team</option><option value="../api/personal_bindings">Mine</option></select></label>
+        <label>Admin token (optional with a password or proxy sign-in) <input id="token" type="password" autocomplete="off"></label>
+        <button id="load">Load</button>
+    </fieldset>
+    <div id="status"></div>
+    <fieldset>
+        <label>Name <input id="name" type="text"></label>
+        <label>Kind <select id="kind"><option value="url">URL</option><option value="command">Command</option></select></label>
+        <label>Target <input id="target" type="text" placeholder="https://example.com/{}"></label>
+        <label>Description <input id="description" type="text"></label>
+        <label><input id="override" type="checkbox"> Override built-in</label>
+        <button id="save">Save</button>
+    </fieldset>
+    <table>
+        <thead><tr><th>Name</th><th>Kind</th><th>Target</th><th>Description</th><th></th></tr></thead>
+        <tbody id="bindings"></tbody>
+    </table>
+</main>
+<script>
+    let etag = null;
+    const $ = (id) => document.getElementById(id);
+    $("token").value = sessionStorage.getItem("bunnylol-admin-token") || "";
+
+    function showStatus(message, isError) {
+        $("status").textContent = message;
+        $("status").className = isError ? "error" : "";
+    }
+
+    async function api(method, path, body) {
+        sessionStorage.setItem("bunnylol-admin-token", $("token").value);
+        // Without a token, the browser's basic auth or proxy sign-in applies
+        const headers = {};
+        if ($("token").value) headers["Authorization"] = "Bearer " + $("token").value;
+        if (method !== "GET") headers["If-Match"] = etag;
+        if (body) headers["Content-Type"] = "application/json";
+        const response = await fetch(path, { method, headers, body: body && JSON.stringify(body) });
+        const data = await response.json();
+        if (!response.ok) {
+            if (response.status === 412) await load();
+            throw new Error(data.error);
+        }
+        etag = response.headers.get("ETag");
+        render(data.bindings);
+    }
+
+    function render(bindings) {
+        const rows = $("bindings");
+        rows.replaceChildren();
+        for (const [name, binding] of Object.entries(bindings)) {
+            const row = rows.insertRow();
+            const kind = binding.url !== undefined ? "url" : "command";
+            for (const text of [name, kind, binding[kind], binding.description || ""]) {
+                row.insertCell().textContent = text;
+            }
+            const actions = row.insertCell();
+            const edit = document.createElement("button");
+            edit.textContent = "Edit";
+            edit.onclick = () => {
+                $("name").value = name;
+                $("kind").value = kind;
+                $("target").value = binding[kind];
+                $("description").value = binding.description || "";
+                $("override").checked = binding.override;
+            };
+            const remove = document.createElement("button");
+            remove.textContent = "Delete";
+            remove.onclick = () => run("DELETE", name);
+            actions.append(edit, " ", remove);
+        }
+    }
+
+    async function run(method, name, body) {
+        try {
+            await api(method, $("scope").value + "/" + encodeURIComponent(name), body);
+            showStatus((method === "DELETE" ? "Deleted " : "Saved ") + name);
+        } catch (e) {
+            showStatus(e.message, true);
+        }
+    }
+
+    async function load() {
+        try {
+            await api("GET", $("scope").value);
+            showStatus("");
+        } catch (e) {
+            showStatus(e.message, true);
+        }
+    }
+
+    $("load").onclick = load;
+    $("scope").onchange = load;
+    $("save").onclick = () => {
+        const body = { [$("kind").value]: $("target").value, override: $("override").checked };
+        if ($("description").value) body.description = $("description").value;
+        run("PUT", $("name").value.trim(), body);
+    };
+    load();
+</script>
+</body>
+</html>
+"#;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -544,4 +728,48 @@ mod tests {
         assert!(second_html.contains("cache-new-binding-xyz"));
         assert!(!second_html.contains("cache-old-binding-xyz"));
     }
+
+    #[test]
+    fn test_landing_page_lists_personal_bindings_separately() {
+        let mut config =
+            config_with_user_binding("team-landing-xyz", "https://example.com/team-landing-xyz");
+        config.merge_personal_bindings(
+            "alice",
+            [(
+                "mine-landing-xyz".to_string(),
+                UserBinding::Url {
+                    url: "https://example.com/mine-landing-xyz".to_string(),
+                    description: None,
+                    override_builtin: false,
+                },
+            )]
+            .into(),
+        );
+
+        let html = render_landing_page_html(&config);
+        let personal = html.find("Your Bindings").expect("personal section");
+        let team = html.find("User Bindings").expect("team section");
+        let mine = html.find("mine-landing-xyz").unwrap();
+        assert!(personal < mine && mine < team);
+        assert!(html[team..].contains("team-landing-xyz"));
+    }
+
+    #[test]
+    fn test_landing_page_lists_named_command_instances() {
+        let mut config = BunnylolConfig::default();
+        config.commands.insert(
+            "ghe-landing-xyz".to_string(),
+            crate::config::CommandConfig {
+                command_type: Some("github".to_string()),
+                host: Some("github.corp.example".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let rows = collect_user_bindings(&config);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].command, "ghe-landing-xyz");
+        assert!(rows[0].description.contains("https://github.corp.example"));
+        assert!(render_landing_page_html(&config).contains("ghe-landing-xyz"));
+    }
 }
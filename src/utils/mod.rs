@@ -17,10 +17,28 @@ pub fn get_command_from_query_string(query_string: &str) -> &str {
     query_string
 }
 
+/// Lowercase hex SHA-256 of `data`. Ids and ETags use a prefix of it.
+pub fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
     #[test]
     fn test_get_command_from_query_string_no_whitespace() {
         // Test with command only
@@ -119,6 +119,23 @@ fn test_cli_dry_run_github_repo() {
         .stdout("https://github.com/facebook/react\n");
 }
 
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_prefix_handlers_listed_and_resolved() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.arg("--list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("u/<user>"))
+        .stdout(predicate::str::contains("@<handle>"));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["--dry-run", "@zuck", "ig"])
+        .assert()
+        .success()
+        .stdout("https://www.instagram.com/zuck\n");
+}
+
 #[test]
 #[cfg(feature = "cli")]
 fn test_cli_missing_config_uses_defaults() {
@@ -357,6 +374,597 @@ ig = { url = "https://example.com/override", override = true }
         .stdout(predicate::str::contains("override"));
 }
 
+#[test]
+#[cfg(feature = "cli")]
+fn test_explain_traces_tiers_without_opening() {
+    let xdg = write_test_config(
+        "explain",
+        r#"
+[user_bindings]
+gh = { url = "https://example.com/shadowed" }
+jira = { url = "https://corp.atlassian.net/browse/{}" }
+"#,
+    );
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .args(["explain", "gh", "facebook/react"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tier 3"))
+        .stdout(predicate::str::contains("Shadowed candidates"))
+        .stdout(predicate::str::contains("user binding `gh`"))
+        .stdout(predicate::str::contains(
+            "Result:  https://github.com/facebook/react",
+        ));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .args(["explain", "jira", "PROJ-123"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "template: https://corp.atlassian.net/browse/{}",
+        ))
+        .stdout(predicate::str::contains(
+            "Result:  https://corp.atlassian.net/browse/PROJ-123",
+        ));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_pattern_rules_resolve_and_warn_on_shadowing() {
+    let xdg = write_test_config(
+        "patterns",
+        r#"
+[[patterns]]
+name = "jira"
+pattern = '^([A-Z][A-Z0-9]+)-(\d+)$'
+url = "https://corp.atlassian.net/browse/$1-$2"
+
+[[patterns]]
+name = "greedy"
+pattern = '^g\w$'
+command = "search $0"
+examples = ["ghx"]
+"#,
+    );
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .args(["--dry-run", "PROJ-123"])
+        .assert()
+        .success()
+        .stdout("https://corp.atlassian.net/browse/PROJ-123\n")
+        .stderr(predicate::str::contains(
+            "pattern rule 'greedy' matches built-in command",
+        ))
+        .stderr(predicate::str::contains("'gh'"))
+        .stderr(predicate::str::contains(
+            "example 'ghx' does not match this rule",
+        ));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .args(["explain", "gh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pattern rule `greedy`"))
+        .stdout(predicate::str::contains("Dispatched once"));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_invalid_pattern_regex_is_fatal() {
+    let xdg = write_test_config(
+        "patterns-invalid",
+        r#"
+[[patterns]]
+pattern = "^(unclosed"
+url = "https://example.com/$1"
+"#,
+    );
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .args(["--dry-run", "gh"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("patterns[1]"))
+        .stderr(predicate::str::contains("invalid regex"));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_command_host_config_and_named_instances() {
+    let xdg = write_test_config(
+        "command-hosts",
+        r#"
+[commands.gitlab]
+host = "gitlab.corp.example"
+
+[commands.ghe]
+type = "github"
+host = "github.corp.example"
+"#,
+    );
+
+    assert_dry_run_stdout(
+        &xdg,
+        &["gl", "infra/deploy"],
+        "https://gitlab.corp.example/infra/deploy\n",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["ghe", "settings"],
+        "https://github.corp.example/settings/profile\n",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["gh", "settings"],
+        "https://github.com/settings/profile\n",
+    );
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .arg("--list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("github instance"));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_github_deep_links_with_default_org() {
+    let xdg = write_test_config(
+        "github-default-org",
+        r#"
+[commands.github]
+default_org = "facebook"
+"#,
+    );
+
+    assert_dry_run_stdout(
+        &xdg,
+        &["gh", "react#12"],
+        "https://github.com/facebook/react/issues/12\n",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["gh", "rust-lang/rust!1"],
+        "https://github.com/rust-lang/rust/pull/1\n",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["gh", "code:", "useState"],
+        "https://github.com/search?q=useState&type=code\n",
+    );
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_docs_versions_from_input_and_config() {
+    let xdg = write_test_config(
+        "docs-versions",
+        r#"
+[commands.node]
+version = "18"
+"#,
+    );
+
+    assert_dry_run_stdout(
+        &xdg,
+        &["node", "fs"],
+        "https://nodejs.org/docs/latest-v18.x/api/fs.html
+",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["node", "20", "fs"],
+        "https://nodejs.org/docs/latest-v20.x/api/fs.html
+",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["py3.9", "asyncio"],
+        "https://docs.python.org/3.9/search.html?q=asyncio
+",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["rust", "1.70", "Vec"],
+        "https://doc.rust-lang.org/1.70.0/std/index.html?search=Vec
+",
+    );
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_locale_selects_regional_sites() {
+    let xdg = write_test_config(
+        "locale",
+        r#"
+locale = "de-DE"
+
+[commands.wikipedia]
+locale = "ja"
+"#,
+    );
+
+    assert_dry_run_stdout(
+        &xdg,
+        &["az", "kopfhoerer"],
+        "https://www.amazon.de/s?k=kopfhoerer\n",
+    );
+    assert_dry_run_stdout(&xdg, &["wiki"], "https://ja.wikipedia.org/\n");
+    assert_dry_run_stdout(
+        &xdg,
+        &["wiki", "fr:Paris"],
+        "https://fr.wikipedia.org/w/index.php?search=Paris&title=Special%3ASearch&ns0=1\n",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["amazon.co.uk", "kettle"],
+        "https://www.amazon.co.uk/s?k=kettle\n",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["wetter", "berlin"],
+        "https://www.google.com/search?q=wetter%20berlin&hl=de&gl=DE\n",
+    );
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_google_account_selection() {
+    let xdg = write_test_config(
+        "google-accounts",
+        r#"
+[google]
+account = "work"
+
+[google.accounts]
+work = "me@corp.example"
+personal = 0
+"#,
+    );
+
+    assert_dry_run_stdout(
+        &xdg,
+        &["gmail", "@personal", "is:unread"],
+        "https://mail.google.com/mail/u/0/#search/is:unread\n",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["docs"],
+        "https://docs.google.com/document/?authuser=me@corp.example\n",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["maps", "@2", "coffee"],
+        "https://www.google.com/maps/search/coffee/?authuser=2\n",
+    );
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_preferred_providers() {
+    let xdg = write_test_config(
+        "providers",
+        r#"
+default_search = "bing"
+stock_provider = "finviz"
+
+[providers]
+stock = "tv"
+maps = "osm"
+video = "invidious"
+
+[providers.custom.invidious]
+category = "video"
+url = "https://yewtu.be/search?q={}"
+"#,
+    );
+
+    assert_dry_run_stdout(
+        &xdg,
+        &["$META"],
+        "https://www.tradingview.com/symbols/META/\n",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["maps", "berlin"],
+        "https://www.openstreetmap.org/search?query=berlin\n",
+    );
+    assert_dry_run_stdout(
+        &xdg,
+        &["yt", "rust", "talks"],
+        "https://yewtu.be/search?q=rust%20talks\n",
+    );
+    assert_dry_run_stdout(&xdg, &["yt", "studio"], "https://studio.youtube.com/\n");
+    assert_dry_run_stdout(
+        &xdg,
+        &["unknowncmd", "hello"],
+        "https://www.bing.com/search?q=unknowncmd%20hello\n",
+    );
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_rewrites_show_in_dry_run_and_explain() {
+    let xdg = write_test_config(
+        "rewrites",
+        r#"
+[user_bindings]
+promo = { url = "http://shop.example/sale?utm_source=mail&id=3" }
+
+[[rewrites]]
+name = "nitter"
+host = '^(www\.)?twitter\.com$'
+replace_host = "nitter.net"
+
+[[rewrites]]
+name = "clean"
+strip_params = ["utm_*"]
+https = true
+"#,
+    );
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .args(["--dry-run", "promo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("https://shop.example/sale?id=3\n"))
+        .stderr(predicate::str::contains(
+            "Rewrite `clean`: http://shop.example/sale?utm_source=mail&id=3 -> https://shop.example/sale?id=3",
+        ));
+
+    assert_dry_run_stdout(&xdg, &["tw", "@rustlang"], "https://nitter.net/rustlang\n");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .args(["explain", "tw", "@rustlang"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Rewrites:"))
+        .stdout(predicate::str::contains(
+            "  - nitter: https://twitter.com/rustlang -> https://nitter.net/rustlang",
+        ))
+        .stdout(predicate::str::contains(
+            "Result:  https://nitter.net/rustlang",
+        ));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_url_policy_in_cli_is_opt_in() {
+    let body = r#"
+[user_bindings]
+local = { url = "file:///etc/hosts" }
+"#;
+    let xdg = write_test_config("url-policy-off", body);
+    assert_dry_run_stdout(&xdg, &["local"], "file:///etc/hosts\n");
+
+    let xdg = write_test_config(
+        "url-policy-cli",
+        &format!("{}\n[url_policy]\ncli = true\n", body),
+    );
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .args(["--dry-run", "local"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("scheme 'file:' is not allowed"));
+    assert_dry_run_stdout(
+        &xdg,
+        &["gh", "facebook/react"],
+        "https://github.com/facebook/react\n",
+    );
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_current_repository_awareness() {
+    let xdg = write_test_config(
+        "repo-context",
+        r#"
+[user_bindings]
+ci = { url = "https://ci.example.com/{repo}/tree/{branch}?sha={sha}" }
+"#,
+    );
+    let checkout = xdg.join("checkout");
+    fs::create_dir_all(checkout.join(".git/refs/heads")).expect("create .git");
+    fs::create_dir_all(checkout.join("src")).expect("create src");
+    fs::write(
+        checkout.join(".git/config"),
+        "[remote \"origin\"]\n\turl = git@github.com:facebook/react.git\n",
+    )
+    .expect("write .git/config");
+    fs::write(checkout.join(".git/HEAD"), "ref: refs/heads/main\n").expect("write HEAD");
+    let sha = "0123456789abcdef0123456789abcdef01234567";
+    fs::write(checkout.join(".git/refs/heads/main"), sha).expect("write ref");
+
+    let run = |args: &[&str], stdout: String| {
+        let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+        cmd.env("XDG_CONFIG_HOME", &xdg)
+            .current_dir(checkout.join("src"))
+            .arg("--dry-run")
+            .args(args)
+            .assert()
+            .success()
+            .stdout(predicate::str::diff(stdout));
+    };
+    run(
+        &["gh", "."],
+        "https://github.com/facebook/react\n".to_string(),
+    );
+    run(
+        &["gh", "pr"],
+        "https://github.com/facebook/react/pulls?q=is:pr%20head:main\n".to_string(),
+    );
+    // The remote is on github.com, so `gl` keeps searching
+    run(
+        &["gl", "issues"],
+        "https://gitlab.com/search?search=issues\n".to_string(),
+    );
+    run(
+        &["ci"],
+        format!(
+            "https://ci.example.com/facebook/react/tree/main?sha={}\n",
+            sha
+        ),
+    );
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_project_file_requires_trust_and_merges_over_user_config() {
+    let xdg = write_test_config(
+        "project-file",
+        r#"
+[user_bindings]
+dash = { url = "https://grafana.example/home" }
+"#,
+    );
+    let project = xdg.join("payments");
+    fs::create_dir_all(project.join("src")).expect("create project dir");
+    fs::write(
+        project.join(".bunnylol.toml"),
+        r#"
+[user_bindings]
+dash = { url = "https://grafana.example/d/payments" }
+runbook = { url = "https://wiki.example/payments/{}" }
+"#,
+    )
+    .expect("write .bunnylol.toml");
+
+    let bunnylol = |args: &[&str]| {
+        let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+        cmd.env("XDG_CONFIG_HOME", &xdg)
+            .env("XDG_DATA_HOME", xdg.join("data"))
+            .current_dir(project.join("src"))
+            .args(args);
+        cmd
+    };
+
+    // Untrusted: ignored with a note, the user config still applies
+    bunnylol(&["--dry-run", "dash"])
+        .assert()
+        .success()
+        .stdout("https://grafana.example/home\n")
+        .stderr(predicate::str::contains("bunnylol trust"));
+
+    bunnylol(&["trust"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Trusted"));
+
+    bunnylol(&["--dry-run", "dash"])
+        .assert()
+        .success()
+        .stdout("https://grafana.example/d/payments\n");
+    bunnylol(&["--dry-run", "runbook", "failover"])
+        .assert()
+        .success()
+        .stdout("https://wiki.example/payments/failover\n");
+    bunnylol(&["bindings"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".bunnylol.toml"));
+
+    // Editing the file revokes trust until it is trusted again
+    fs::write(
+        project.join(".bunnylol.toml"),
+        "[user_bindings]\ndash = { url = \"https://evil.example\" }\n",
+    )
+    .expect("rewrite .bunnylol.toml");
+    bunnylol(&["--dry-run", "dash"])
+        .assert()
+        .success()
+        .stdout("https://grafana.example/home\n")
+        .stderr(predicate::str::contains("has changed"));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_unknown_command_table_is_fatal() {
+    let xdg = write_test_config(
+        "command-hosts-invalid",
+        r#"
+[commands.bitbucket]
+host = "bitbucket.corp.example"
+"#,
+    );
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .args(["--dry-run", "gh"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "[commands.bitbucket] is not a configurable command",
+        ));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_binding_tests_report_failures_and_exit_non_zero() {
+    let xdg = write_test_config(
+        "binding-tests",
+        r#"
+[user_bindings]
+jira = { url = "https://corp.atlassian.net/browse/{}" }
+"#,
+    );
+    let tests_path = xdg.join("bunnylol.tests.toml");
+    fs::write(
+        &tests_path,
+        r#"
+[[case]]
+input = "jira PROJ-1"
+expected = "https://corp.atlassian.net/browse/PROJ-1"
+
+[[case]]
+name = "github home"
+input = "gh"
+expected = "https://github.com"
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .arg("test")
+        .arg(&tests_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 passed, 0 failed"));
+
+    fs::write(
+        &tests_path,
+        r#"
+[[case]]
+input = "jira PROJ-1"
+expected = "https://jira.example.com/PROJ-1"
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .arg("test")
+        .arg(&tests_path)
+        .args(["--format", "junit"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(r#"failures="1""#))
+        .stdout(predicate::str::contains(
+            "got https://corp.atlassian.net/browse/PROJ-1",
+        ));
+}
+
 // =====================================================================
 // [aliases] deprecation / migration tests
 // =====================================================================
@@ -385,3 +993,98 @@ work = "gh mycompany/repo"
         .stderr(predicate::str::contains("[aliases]"))
         .stderr(predicate::str::contains("deprecated"));
 }
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_hash_password_output_is_accepted_by_auth_users() {
+    let xdg = write_test_config("hash-password", "");
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    let output = cmd
+        .env("XDG_CONFIG_HOME", &xdg)
+        .arg("hash-password")
+        .write_stdin("hunter2\n")
+        .output()
+        .expect("run hash-password");
+    assert!(output.status.success());
+    let hash = String::from_utf8(output.stdout).unwrap();
+    assert!(hash.starts_with("$argon2id$"), "{}", hash);
+
+    fs::write(
+        xdg.join("bunnylol/config.toml"),
+        format!(
+            "[auth.users]\nalice = {{ password_hash = \"{}\", role = \"admin\" }}\n",
+            hash.trim()
+        ),
+    )
+    .expect("write config.toml");
+    assert_dry_run_stdout(&xdg, &["gh"], "https://github.com\n");
+
+    fs::write(
+        xdg.join("bunnylol/config.toml"),
+        "[auth.users]\nalice = { password_hash = \"hunter2\", role = \"admin\" }\n",
+    )
+    .expect("write config.toml");
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_CONFIG_HOME", &xdg)
+        .args(["--dry-run", "gh"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("bunnylol hash-password"));
+
+    fs::remove_dir_all(&xdg).ok();
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_link_add_resolves_after_user_bindings() {
+    let xdg = write_test_config(
+        "links",
+        r#"
+[user_bindings]
+handbook = { url = "https://wiki.example/handbook" }
+"#,
+    );
+    let bunnylol = |args: &[&str]| {
+        let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+        cmd.env("XDG_CONFIG_HOME", &xdg)
+            .env("XDG_DATA_HOME", xdg.join("data"))
+            .args(args);
+        cmd
+    };
+
+    bunnylol(&["link", "add", "oncall", "https://wiki.example/oncall/{}"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added go-link 'oncall'"));
+    bunnylol(&["link", "add", "handbook", "https://example.com"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("[user_bindings]"));
+    bunnylol(&["link", "add", "oncall", "https://example.com"])
+        .assert()
+        .failure();
+
+    bunnylol(&["--dry-run", "oncall", "payments"])
+        .assert()
+        .success()
+        .stdout("https://wiki.example/oncall/payments\n");
+    bunnylol(&["link", "update", "oncall", "--url", "https://pager.example"])
+        .assert()
+        .success();
+    bunnylol(&["--dry-run", "oncall"])
+        .assert()
+        .success()
+        .stdout("https://pager.example\n");
+    bunnylol(&["link", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("https://pager.example"));
+
+    bunnylol(&["link", "remove", "oncall"]).assert().success();
+    bunnylol(&["--dry-run", "oncall"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("google.com/search"));
+
+    fs::remove_dir_all(&xdg).ok();
+}
@@ -143,6 +143,24 @@ docker exec "$container_name" bash -lc '
   ! systemctl is-active --quiet bunnylol
   test ! -e /etc/systemd/system/bunnylol.service
 
+  echo "Installing with a socket unit on a Unix domain socket..."
+  socket=/run/bunnylol/bunnylol.sock
+  bunnylol service install --unix-socket "$socket" --socket
+  systemctl is-active --quiet bunnylol.socket
+  test -S "$socket"
+
+  echo "Verifying /health over the socket..."
+  curl --fail --silent --show-error --unix-socket "$socket" http://localhost/health | grep -qx ok
+
+  echo "Restarting service behind the socket..."
+  bunnylol service restart
+  curl --fail --silent --show-error --unix-socket "$socket" http://localhost/health | grep -qx ok
+
+  echo "Uninstalling service and socket..."
+  bunnylol service uninstall
+  ! systemctl is-active --quiet bunnylol.socket
+  test ! -e /etc/systemd/system/bunnylol.socket
+
   echo "Systemd service test completed successfully."
 '
 end_log